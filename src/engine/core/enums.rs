@@ -16,7 +16,10 @@ limitations under the License.
 
 use crate::engine::render::RenderUnitId;
 
-use super::super::super::render::{Layer, ObjectData};
+use super::super::super::render::{
+    Bounds, CameraInfo, Cells, FrameStats, Hit, Layer, ObjectData, RenderTarget, Reply, Screen,
+    Tween,
+};
 use super::super::Error;
 use super::super::types::Position3D;
 use super::traits::Scene;
@@ -33,7 +36,11 @@ pub enum Signal {
     Quit,
     Scenes(SceneSignal),
     Render(RenderSignal),
+    /// Fatal errors stop the engine, anything else is shown in an alert
     Error(Error),
+    /// Message shown over the current scene, the scene is paused until the player closes it.
+    /// The message is markup, see Text::from_markup
+    Alert { title: String, message: String },
     Log(String),
     Batch(Vec<Signal>),
     Sequence(Vec<Signal>),
@@ -60,9 +67,25 @@ pub enum SceneSignal {
 #[derive(Debug)]
 pub enum RenderSignal {
     Insert(Arc<RenderUnitId>, ObjectData),
+    /// Inserts a unit whose position, here and in later Updates, is relative to the parent.
+    /// Moving, hiding, changing the layer of or removing the parent applies to the whole group
+    InsertChild {
+        parent: Arc<RenderUnitId>,
+        id: Arc<RenderUnitId>,
+        data: ObjectData,
+    },
+    /// Attaches an existing unit to a new parent or detaches it with None, it stays where it is on screen
+    SetParent(Arc<RenderUnitId>, Option<Arc<RenderUnitId>>),
     Remove(Arc<RenderUnitId>),
     Move(Arc<RenderUnitId>, Position3D<i32>),
     MoveLayer(Arc<RenderUnitId>, Layer),
+    /// Hidden units keep their slot and animation state, children of a hidden unit are hidden too
+    Show(Arc<RenderUnitId>),
+    Hide(Arc<RenderUnitId>),
+    /// 1.0 draws the unit as is, lower values darken it, children multiply their own opacity with it
+    SetOpacity(Arc<RenderUnitId>, f32),
+    /// Draws the unit and its children over everything else on its layer, used by dialogs
+    Raise(Arc<RenderUnitId>),
     TermSizeChange(u32, u32),
     Foreground(Foreground),
     Background(Background),
@@ -72,7 +95,59 @@ pub enum RenderSignal {
     ShiftUI(i32),
     SetCamera(Position3D<i32>),
     Update(Arc<RenderUnitId>, ObjectData),
+    SetCells(Arc<RenderUnitId>, Vec<Cells>),
+    PauseAnimation(Arc<RenderUnitId>),
+    ResumeAnimation(Arc<RenderUnitId>),
+    RestartAnimation(Arc<RenderUnitId>),
+    Tween(Arc<RenderUnitId>, Tween),
+    /// Replaces the sprite templates with the ones found in the directory
+    LoadTemplates(String),
+    InsertTemplate {
+        id: Arc<RenderUnitId>,
+        name: String,
+        pos: Position3D<i32>,
+    },
+    CancelTween(Arc<RenderUnitId>),
+    /// Replaces the particle templates with the ones found in the directory
+    LoadEmitters(String),
+    /// Starts the named particle effect at pos. The id is never inserted, it only names the emitter
+    /// and its layer decides what the particles are drawn over. Move works on it like any other id
+    StartEmitter {
+        id: Arc<RenderUnitId>,
+        name: String,
+        pos: Position3D<i32>,
+    },
+    /// Stops spawning, particles that are already out live out their lifetime
+    StopEmitter(Arc<RenderUnitId>),
+    /// Inserts a unit at pos that shows what the target sees, redrawn with every frame
+    InsertTarget {
+        id: Arc<RenderUnitId>,
+        target: RenderTarget,
+        pos: Position3D<i32>,
+    },
+    UpdateTarget(Arc<RenderUnitId>, RenderTarget),
+    /// Draws the target once and hands back the cells, for previews and map exports
+    RenderOffscreen(RenderTarget, Reply<Screen>),
     Redraw,
+    /// Saves the last frame to ./screenshots/ as text, ANSI and HTML
+    Screenshot,
+    /// Puts the text on the terminal's clipboard through OSC 52
+    SetClipboard(String),
+    /// Asks the terminal for its clipboard, the answer comes back as a Paste event if it allows it
+    RequestClipboard,
+    QueryBounds(Arc<RenderUnitId>, Reply<Option<Bounds>>),
+    QueryCamera(Reply<CameraInfo>),
+    /// screen cell, x and y start at 0
+    HitTest(u32, u32, Reply<Option<Hit>>),
+    QueryStats(Reply<FrameStats>),
+    /// Caps how often the render thread draws a frame
+    SetMaxFps(u32),
+    ToggleDebugOverlay,
+    DebugOverlay(bool),
+    /// Records the render output as an asciicast file, None picks a name in ./recordings/
+    StartRecording(Option<String>),
+    StopRecording,
+    ToggleRecording,
     Clear,
     Batch(Vec<RenderSignal>),
     Sequence(Vec<RenderSignal>),
@@ -15,7 +15,11 @@ limitations under the License.
 */
 use super::super::{
     Context,
-    input::{CLEAR_BUFFER, Event, InputBuffer, poll_event},
+    enums::RenderSignal,
+    input::{
+        CLEAR_BUFFER, Event, InputBuffer, KeyEvent, MAX_PASTE_LEN, OtherEvent, complete_len, poll_event,
+    },
+    render::RenderQueue,
 };
 use chrono::Local;
 use std::{
@@ -23,46 +27,66 @@ use std::{
     sync::mpsc,
 };
 
-pub fn event_thread(ctx: Context, tx: mpsc::Sender<Event>) {
+/// Keys handled by the engine no matter what scene is active, they are not passed on
+fn global_hotkey(event: &Event) -> Option<RenderSignal> {
+    match event {
+        Event::Keyboard(KeyEvent::F(11)) => Some(RenderSignal::ToggleRecording),
+        Event::Keyboard(KeyEvent::F(12)) => Some(RenderSignal::Screenshot),
+        // the render thread resizes first and then passes the event on to the scenes
+        Event::Other(OtherEvent::ScreenSizeChange { width, height }) => {
+            Some(RenderSignal::TermSizeChange(*width, *height))
+        }
+        _ => None,
+    }
+}
+
+fn send_event(event: Event, tx: &mpsc::Sender<Event>, render_tx: &RenderQueue) {
+    if let Some(sig) = global_hotkey(&event) {
+        if let Err(_err) = render_tx.send(sig) {
+            // log this and continue
+        }
+        return;
+    }
+    if let Err(_err) = tx.send(event) {
+        // log this and continue
+    }
+}
+
+/// Turns bytes read from stdin into events
+fn handle_input(seq: &[u8], tx: &mpsc::Sender<Event>, render_tx: &RenderQueue) {
+    if seq[0] == b'\x1b' {
+        match poll_event(seq) {
+            Some(event) => send_event(event, tx, render_tx),
+            None => {} // log that data form stdin couldn't be transformed into an event by poll_event
+        }
+    } else {
+        for (i, _) in seq.iter().enumerate() {
+            match poll_event(&seq[i..i + 1]) {
+                Some(e) => send_event(e, tx, render_tx),
+                None => {}
+            }
+        }
+    }
+}
+
+pub fn event_thread(ctx: Context, tx: mpsc::Sender<Event>, render_tx: RenderQueue) {
+    // bytes read but not handled yet, a long paste arrives over several reads
+    let mut pending: Vec<u8> = Vec::new();
     // Read the events comming from Stdin
     while ctx.is_alive() {
         let mut buf: InputBuffer = CLEAR_BUFFER;
         match stdin().read(&mut buf) {
             Err(e) => {}
             Ok(n) => {
-                let mut seq: &[u8] = &buf[0..n];
-                /*
-                                if buf[0] == b'\x1b' {
-                                    for (index, each) in buf.iter().enumerate() {
-                                        if *each == b'\0' {
-                                            seq = &buf[0..index + 1];
-                                            break;
-                                        }
-                                    }
-                                }
-                */
-                if seq[0] == b'\x1b' {
-                    match poll_event(&seq) {
-                        Some(event) => {
-                            if let Err(err) = tx.send(event) {
-                                // log this and continue
-                                continue;
-                            }
-                        }
-                        None => {} // log that data form stdin couldn't be transformed into an event by poll_event
-                    }
-                } else {
-                    for (i, _) in seq.iter().enumerate() {
-                        match poll_event(&seq[i..i + 1]) {
-                            Some(e) => {
-                                if let Err(err) = tx.send(e) {
-                                    //logging
-                                    continue;
-                                }
-                            }
-                            None => {}
-                        }
-                    }
+                pending.extend_from_slice(&buf[0..n]);
+                while !pending.is_empty() {
+                    let len = match complete_len(&pending) {
+                        Some(len) => len,
+                        None if pending.len() > MAX_PASTE_LEN => pending.len(),
+                        None => break,
+                    };
+                    let seq: Vec<u8> = pending.drain(..len).collect();
+                    handle_input(&seq, &tx, &render_tx);
                 }
             }
         }
@@ -70,3 +94,16 @@ pub fn event_thread(ctx: Context, tx: mpsc::Sender<Event>) {
     #[cfg(debug_assertions)]
     print!("Event Handling Thread has finished\r\n");
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn resize_reaches_render_test() {
+        // the recorder only sees a resize when the render thread is told about it
+        let resize = Event::Other(OtherEvent::ScreenSizeChange { width: 100, height: 30 });
+        assert!(matches!(global_hotkey(&resize), Some(RenderSignal::TermSizeChange(100, 30))));
+        assert!(global_hotkey(&Event::Keyboard(KeyEvent::Char('a'))).is_none());
+    }
+}
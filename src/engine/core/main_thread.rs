@@ -18,11 +18,12 @@ use crate::{
     engine::{
         enums::{RenderSignal, SceneInitSignals, SceneSignal, Signal as EngineSignal},
         consts::DEFAULT_CANVAS,
-        render::{render_thread, Canvas},
+        render::{render_thread, escape_markup, Canvas},
         traits::Scene,
         core::{audio_thread,event_thread},
-        input::Event,
+        input::{BRACKETED_PASTE_OFF, BRACKETED_PASTE_ON, Event},
         types::{Instance, InstanceConfig},
+        ui::{Dialog, Widget},
         AudioMsg,
         Context,
         Error,
@@ -31,8 +32,8 @@ use crate::{
 use std::{
     ops::Deref,
     thread::{JoinHandle, spawn},
-    io::{Read, stdin},
-    sync::{Arc, mpsc},
+    io::{Read, Write, stdin},
+    sync::{Arc, mpsc, atomic::{AtomicU64, Ordering}},
     time::{Duration, Instant},
 };
 
@@ -51,6 +52,9 @@ pub fn start(config: InstanceConfig) -> Result<(), Error> {
     let (render_tx, render_rx) = mpsc::channel();
     let (audio_tx, audio_rx) = mpsc::channel();
     let (event_tx, event_rx) = mpsc::channel();
+    let record = config.record();
+    let debug_overlay = config.debug_overlay();
+    let max_fps = config.max_fps();
 
     ////////////////////////////////////
     // Setting up the instance object //
@@ -66,19 +70,30 @@ pub fn start(config: InstanceConfig) -> Result<(), Error> {
     ins.term_orig = my_term::set_raw();
     ins.term_orig.toggle_alt_buffer();
     ins.term_orig.toggle_cursor_visable();
+    print!("{BRACKETED_PASTE_ON}");
 
     ////////////////////////////////////////////////////////////////////////////
     // Starting Seperate processing threads: Audio, Rendering, Event Handling //
     ////////////////////////////////////////////////////////////////////////////
     let audio_handle = start_audio_thread(ins.ctx.child(), audio_rx);
-    let event_handle = start_event_thread(ins.ctx.child(), event_tx.clone());
+    let event_handle = start_event_thread(ins.ctx.child(), event_tx.clone(), render_tx.clone());
     let render_handle = start_render_thread(
         ins.ctx.child(),
         ins.canvas.clone(),
         event_tx.clone(),
         render_rx,
+        ins.tick_time.clone(),
         ins.logger.clone(),
     );
+    if debug_overlay {
+        let _ = render_tx.send(RenderSignal::DebugOverlay(true));
+    }
+    if let Some(fps) = max_fps {
+        let _ = render_tx.send(RenderSignal::SetMaxFps(fps));
+    }
+    if let Some(path) = record {
+        let _ = render_tx.send(RenderSignal::StartRecording(path));
+    }
 
     /////////////////////////////////////////////////////////
     // Initializing the First Scene of the game (MainMenu) //
@@ -112,24 +127,54 @@ fn main_loop(mut ins: Instance, mut stack: Vec<Box<dyn Scene>>) -> Result<(), Er
     //////////////////////////////////////////////////////////////////////////
     // Main Game Loop (while context alive and there is a scene to process) //
     //////////////////////////////////////////////////////////////////////////
+    let mut alert: Option<Dialog> = None;
     while ins.ctx.is_alive() && stack.len() > 0 {
-        //////////////////////////////
-        // Update the current scene //
-        //////////////////////////////
-        let index = stack.len() - 1;
-        let sig = stack.get_mut(index).unwrap().update(&mut ins, dt);
-
-        ////////////////////////////////////////////
-        // Handle any returned signals from scene //
-        ////////////////////////////////////////////
-        match dispatch(&mut ins, &mut stack, sig) {
-            Ok(_) => {}
-            Err(e) => return Err(e),
+        ///////////////////////////////////////////////////////////////
+        // An open alert takes all input until the player closes it //
+        ///////////////////////////////////////////////////////////////
+        if alert.is_none() {
+            alert = ins.alerts.pop_front();
+            if let Some(dialog) = alert.as_mut() {
+                let _ = dialog.output(&ins.render_queue, &ins.canvas);
+            }
+        }
+        if let Some(mut dialog) = alert.take() {
+            let mut closed = false;
+            let mut events = ins.events().into_iter();
+            for e in events.by_ref() {
+                if dialog.handle_event(&e, &ins.render_queue, &ins.canvas).is_some() {
+                    closed = true;
+                    break;
+                }
+            }
+            // whatever came after the key that closed the alert is for the scene
+            ins.unread(events.collect());
+            if closed {
+                let _ = dialog.remove(&ins.render_queue);
+            } else {
+                alert = Some(dialog);
+            }
+        } else {
+            //////////////////////////////
+            // Update the current scene //
+            //////////////////////////////
+            let index = stack.len() - 1;
+            let sig = stack.get_mut(index).unwrap().update(&mut ins, dt);
+
+            ////////////////////////////////////////////
+            // Handle any returned signals from scene //
+            ////////////////////////////////////////////
+            match dispatch(&mut ins, &mut stack, sig) {
+                Ok(_) => {}
+                Err(e) => return Err(e),
+            }
         }
         //////////////////////////
         // Calculate Delta Time //
         //////////////////////////
-        dt = (Instant::now() - end_frame).as_secs_f32();
+        let tick = Instant::now() - end_frame;
+        ins.tick_time.store(tick.as_nanos() as u64, Ordering::Relaxed);
+        dt = tick.as_secs_f32();
         end_frame = Instant::now();
     }
 
@@ -185,7 +230,12 @@ fn dispatch(ins: &mut Instance, stack: &mut Vec<Box<dyn Scene>>, sig: EngineSign
                 }
             }
         }
-        EngineSignal::Error(e) => return Err(e),
+        EngineSignal::Error(e) if e.is_fatal() => return Err(e),
+        EngineSignal::Error(e) => {
+            let _ = ins.logger.write(logging::LogLevel::Error, e.to_string());
+            ins.alerts.push_back(Dialog::alert("Error", escape_markup(&e.to_string())));
+        }
+        EngineSignal::Alert { title, message } => ins.alerts.push_back(Dialog::alert(title, message)),
         EngineSignal::Log(msg) => {}
     }
     Ok(())
@@ -195,8 +245,12 @@ fn start_audio_thread(ctx: Context, rx: mpsc::Receiver<AudioMsg>) -> JoinHandle<
     spawn(move || audio_thread(ctx, rx))
 }
 
-fn start_event_thread(ctx: Context, tx: mpsc::Sender<Event>) -> JoinHandle<()> {
-    spawn(move || event_thread(ctx, tx))
+fn start_event_thread(
+    ctx: Context,
+    tx: mpsc::Sender<Event>,
+    render_tx: mpsc::Sender<RenderSignal>,
+) -> JoinHandle<()> {
+    spawn(move || event_thread(ctx, tx, render_tx))
 }
 
 fn start_render_thread(
@@ -204,14 +258,17 @@ fn start_render_thread(
     canvas: Canvas,
     event_tx: mpsc::Sender<Event>,
     rx: mpsc::Receiver<RenderSignal>,
+    tick_time: Arc<AtomicU64>,
     lg: Arc<logging::Logger>,
 ) -> JoinHandle<()> {
-    spawn(move || render_thread(ctx, canvas, rx, event_tx, lg))
+    spawn(move || render_thread(ctx, canvas, rx, event_tx, tick_time, lg))
 }
 
 fn exit_engine(mut t: my_term::Terminal) -> Result<(), Error> {
     t.toggle_alt_buffer();
     t.toggle_cursor_visable();
+    print!("{BRACKETED_PASTE_OFF}");
+    let _ = std::io::stdout().flush();
     my_term::set_term(t);
     Ok(())
 }
@@ -149,4 +149,8 @@ impl Numeric for isize {
 pub trait Storeable: for<'de> serde::Deserialize<'de> + serde::Serialize {
     type Key: serde::Serialize + for<'de> serde::Deserialize<'de> + Eq + Hash + Clone;
     fn key(&self) -> Self::Key;
+    /// Called on every entry loaded from disk, invalid entries are skipped
+    fn validate(&self) -> Result<(), super::super::Error> {
+        Ok(())
+    }
 }
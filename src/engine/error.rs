@@ -29,6 +29,7 @@ pub enum ErrorKind {
     NotFound,
     Context,
     ContextDead,
+    Timeout,
 }
 
 impl std::fmt::Display for ErrorKind {
@@ -41,6 +42,7 @@ impl std::fmt::Display for ErrorKind {
             Self::NotFound => write!(f, "Not Found"),
             Self::Context => write!(f, "Context"),
             Self::ContextDead => write!(f, "Context Dead"),
+            Self::Timeout => write!(f, "Timeout"),
         }
     }
 }
@@ -90,4 +92,13 @@ impl Error {
             source: Some(Box::new(err)),
         }
     }
-}
\ No newline at end of file
+
+    pub fn kind(&self) -> &ErrorKind {
+        &self.kind
+    }
+
+    /// Errors the engine can not keep running after, anything else is shown to the player and play goes on
+    pub fn is_fatal(&self) -> bool {
+        matches!(self.kind, ErrorKind::Context | ErrorKind::ContextDead)
+    }
+}
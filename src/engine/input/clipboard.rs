@@ -0,0 +1,131 @@
+/*
+Copyright 2025 Luke Maple
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+you may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+//! OSC 52 lets a program set and read the clipboard of the terminal it runs in, even over ssh.
+//! The text travels base64 encoded inside the escape sequence
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Asks the terminal for its clipboard, terminals that allow it answer with the same sequence
+/// holding the text, which comes back as a Paste event
+pub const OSC52_QUERY: &str = "\x1b]52;c;?\x07";
+/// Pasted text arrives between these while bracketed paste is on
+pub const PASTE_START: &[u8] = b"\x1b[200~";
+pub const PASTE_END: &[u8] = b"\x1b[201~";
+pub const BRACKETED_PASTE_ON: &str = "\x1b[?2004h";
+pub const BRACKETED_PASTE_OFF: &str = "\x1b[?2004l";
+
+const OSC52_START: &[u8] = b"\x1b]52;";
+/// A paste or clipboard reply that has not ended after this many bytes is handed on as it is
+pub const MAX_PASTE_LEN: usize = 1 << 20;
+
+/// Bytes at the start of buf that can be parsed on their own. A paste or clipboard reply can be
+/// split over several reads, None means the rest of it has not arrived yet
+pub fn complete_len(buf: &[u8]) -> Option<usize> {
+    let find = |needle: &[u8]| buf.windows(needle.len()).position(|w| w == needle);
+    if buf.starts_with(PASTE_START) {
+        return find(PASTE_END).map(|i| i + PASTE_END.len());
+    }
+    if buf.starts_with(OSC52_START) {
+        let bel = buf.iter().position(|b| *b == b'\x07').map(|i| i + 1);
+        let st = find(b"\x1b\\").map(|i| i + 2);
+        return match (bel, st) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+    }
+    // whatever comes before a paste is handled first
+    let next = [find(PASTE_START), find(OSC52_START)].into_iter().flatten().min();
+    Some(next.unwrap_or(buf.len()))
+}
+
+/// Sequence that puts the text on the terminal's clipboard
+pub fn osc52_set(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", encode(text.as_bytes()))
+}
+
+/// Text of a clipboard reply, None for anything else
+pub fn parse_osc52(seq: &[u8]) -> Option<String> {
+    let body = seq.strip_prefix(b"\x1b]52;")?;
+    let body = body
+        .strip_suffix(b"\x07")
+        .or_else(|| body.strip_suffix(b"\x1b\\"))?;
+    // the selection letters come before the first ;
+    let start = body.iter().position(|b| *b == b';')? + 1;
+    let data = decode(&body[start..])?;
+    String::from_utf8(data).ok()
+}
+
+/// Text between the bracketed paste markers
+pub fn parse_bracketed_paste(seq: &[u8]) -> Option<String> {
+    let body = seq.strip_prefix(PASTE_START)?;
+    let body = body.strip_suffix(PASTE_END).unwrap_or(body);
+    Some(String::from_utf8_lossy(body).replace("\r\n", "\n").replace('\r', "\n"))
+}
+
+fn encode(data: &[u8]) -> String {
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+fn decode(data: &[u8]) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(data.len() / 4 * 3);
+    let (mut acc, mut bits) = (0u32, 0);
+    for b in data.iter().filter(|b| **b != b'=') {
+        let v = ALPHABET.iter().position(|a| a == b)? as u32;
+        acc = acc << 6 | v;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((acc >> bits) as u8);
+        }
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn osc52_round_trip_test() {
+        assert_eq!(osc52_set("Urist"), "\x1b]52;c;VXJpc3Q=\x07");
+        for text in ["", "a", "ab", "abc", "the goblin\nflees"] {
+            let seq = osc52_set(text);
+            assert_eq!(parse_osc52(seq.as_bytes()).as_deref(), Some(text));
+        }
+        assert_eq!(parse_osc52(b"\x1b]52;c;aGk=\x1b\\").as_deref(), Some("hi"));
+        assert_eq!(parse_bracketed_paste(b"\x1b[200~one\r\ntwo\x1b[201~").as_deref(), Some("one\ntwo"));
+
+        let paste = b"ab\x1b[200~one\x1b[201~c";
+        assert_eq!(complete_len(paste), Some(2));
+        assert_eq!(complete_len(&paste[2..]), Some(15));
+        assert_eq!(complete_len(&paste[2..9]), None);
+        assert_eq!(complete_len(b"\x1b]52;c;aGk="), None);
+        assert_eq!(complete_len(b"\x1b]52;c;aGk=\x07x"), Some(12));
+    }
+}
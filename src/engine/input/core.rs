@@ -15,7 +15,11 @@ limitations under the License.
 */
 use chrono::Local;
 use std::io::Write;
+use std::sync::Weak;
 
+use crate::engine::render::RenderUnitId;
+
+use super::clipboard::{PASTE_START, parse_bracketed_paste, parse_osc52};
 use super::consts::*;
 
 pub type InputBuffer = [u8; MAX_INPUT_LEN];
@@ -27,12 +31,26 @@ pub enum KeyEvent {
     Alt(char),     // Alt + printable Value
     Enter,
     Tab,
+    BackTab, // Shift + Tab
     Backspace,
     Escape,
     Up,
     Down,
     Left,
     Right,
+    Home,
+    End,
+    Delete,
+    ShiftUp,
+    ShiftDown,
+    ShiftLeft,
+    ShiftRight,
+    ShiftHome,
+    ShiftEnd,
+    CtrlLeft,
+    CtrlRight,
+    CtrlShiftLeft,
+    CtrlShiftRight,
     F(u8), //F1-12
 }
 
@@ -41,6 +59,10 @@ pub enum OtherEvent {
     EnterFocus,
     LeaveFocus,
     ScreenSizeChange { width: u32, height: u32 },
+    /// A one shot animation reached its last frame
+    AnimationFinished(Weak<RenderUnitId>),
+    /// Text from a bracketed paste or the terminal's answer to a clipboard request
+    Paste(String),
     Unknown(String),
 }
 
@@ -66,6 +88,22 @@ pub struct MouseData {
     pos: MousePos,
 }
 
+impl MouseData {
+    pub fn button(&self) -> MouseButton {
+        self.button
+    }
+
+    /// Screen column, starts at 0 like hit_test
+    pub fn x(&self) -> u32 {
+        self.pos.x.saturating_sub(1) as u32
+    }
+
+    /// Screen row, starts at 0 like hit_test
+    pub fn y(&self) -> u32 {
+        self.pos.y.saturating_sub(1) as u32
+    }
+}
+
 #[derive(Clone, Copy)]
 pub struct MousePos {
     x: u16,
@@ -93,6 +131,20 @@ pub fn poll_event(mut seq: &[u8]) -> Option<Event> {
         b"\x1b[B" => Some(Event::Keyboard(KeyEvent::Down)),
         b"\x1b[C" => Some(Event::Keyboard(KeyEvent::Right)),
         b"\x1b[D" => Some(Event::Keyboard(KeyEvent::Left)),
+        b"\x1b[Z" => Some(Event::Keyboard(KeyEvent::BackTab)),
+        b"\x1b[H" | b"\x1bOH" | b"\x1b[1~" | b"\x1b[7~" => Some(Event::Keyboard(KeyEvent::Home)),
+        b"\x1b[F" | b"\x1bOF" | b"\x1b[4~" | b"\x1b[8~" => Some(Event::Keyboard(KeyEvent::End)),
+        b"\x1b[3~" => Some(Event::Keyboard(KeyEvent::Delete)),
+        b"\x1b[1;2A" => Some(Event::Keyboard(KeyEvent::ShiftUp)),
+        b"\x1b[1;2B" => Some(Event::Keyboard(KeyEvent::ShiftDown)),
+        b"\x1b[1;2C" => Some(Event::Keyboard(KeyEvent::ShiftRight)),
+        b"\x1b[1;2D" => Some(Event::Keyboard(KeyEvent::ShiftLeft)),
+        b"\x1b[1;2H" => Some(Event::Keyboard(KeyEvent::ShiftHome)),
+        b"\x1b[1;2F" => Some(Event::Keyboard(KeyEvent::ShiftEnd)),
+        b"\x1b[1;5C" => Some(Event::Keyboard(KeyEvent::CtrlRight)),
+        b"\x1b[1;5D" => Some(Event::Keyboard(KeyEvent::CtrlLeft)),
+        b"\x1b[1;6C" => Some(Event::Keyboard(KeyEvent::CtrlShiftRight)),
+        b"\x1b[1;6D" => Some(Event::Keyboard(KeyEvent::CtrlShiftLeft)),
         b"\x1bOP" | b"\x1b[11~" | b"\x1b[[A" | b"\x1b[M" => Some(Event::Keyboard(KeyEvent::F(1))),
         b"\x1bOQ" | b"\x1b[12~" | b"\x1b[[B" | b"\x1b[N" => Some(Event::Keyboard(KeyEvent::F(2))),
         b"\x1bOR" | b"\x1b[13~" | b"\x1b[[C" | b"\x1b[O" => Some(Event::Keyboard(KeyEvent::F(3))),
@@ -112,6 +164,12 @@ pub fn poll_event(mut seq: &[u8]) -> Option<Event> {
         b"\x1b" => Some(Event::Keyboard(KeyEvent::Escape)),
         [8] | [127] => Some(Event::Keyboard(KeyEvent::Backspace)),
         b"\t" => Some(Event::Keyboard(KeyEvent::Tab)),
+        // Ctrl+letter comes in as 1 to 26, minus the ones that already mean something
+        [b @ 1..=26] if !matches!(b, 8 | 9 | 10 | 13) => {
+            Some(Event::Keyboard(KeyEvent::Control((b'a' + b - 1) as char)))
+        }
+        seq if seq.starts_with(PASTE_START) => Some(Event::Other(OtherEvent::Paste(parse_bracketed_paste(seq)?))),
+        seq if seq.starts_with(b"\x1b]52;") => Some(Event::Other(OtherEvent::Paste(parse_osc52(seq)?))),
         seq if seq.starts_with(b"\x1b[M") || seq.starts_with(b"\x1b[<") => {
             Some(Event::Mouse(parse_mouse_event(seq)?))
         }
@@ -240,11 +298,25 @@ impl std::fmt::Debug for Event {
                 KeyEvent::Enter => write!(f, "Enter"),
                 KeyEvent::Backspace => write!(f, "Backspace"),
                 KeyEvent::Tab => write!(f, "Tab"),
+                KeyEvent::BackTab => write!(f, "Shift-Tab"),
                 KeyEvent::Escape => write!(f, "Escape"),
                 KeyEvent::Up => write!(f, "Up Arrow"),
                 KeyEvent::Down => write!(f, "Down Arrow"),
                 KeyEvent::Right => write!(f, "Right Arrow"),
                 KeyEvent::Left => write!(f, "Left Arrow"),
+                KeyEvent::Home => write!(f, "Home"),
+                KeyEvent::End => write!(f, "End"),
+                KeyEvent::Delete => write!(f, "Delete"),
+                KeyEvent::ShiftUp => write!(f, "Shift-Up Arrow"),
+                KeyEvent::ShiftDown => write!(f, "Shift-Down Arrow"),
+                KeyEvent::ShiftLeft => write!(f, "Shift-Left Arrow"),
+                KeyEvent::ShiftRight => write!(f, "Shift-Right Arrow"),
+                KeyEvent::ShiftHome => write!(f, "Shift-Home"),
+                KeyEvent::ShiftEnd => write!(f, "Shift-End"),
+                KeyEvent::CtrlLeft => write!(f, "Ctrl-Left Arrow"),
+                KeyEvent::CtrlRight => write!(f, "Ctrl-Right Arrow"),
+                KeyEvent::CtrlShiftLeft => write!(f, "Ctrl-Shift-Left Arrow"),
+                KeyEvent::CtrlShiftRight => write!(f, "Ctrl-Shift-Right Arrow"),
             },
             Event::Mouse(mouse_event) => {
                 let btn = |data: &MouseData| -> &str {
@@ -279,6 +351,11 @@ impl std::fmt::Debug for Event {
                 OtherEvent::ScreenSizeChange { width, height } => {
                     write!(f, "Screen Size changed to ({},{})", width, height)
                 }
+                OtherEvent::AnimationFinished(id) => match id.upgrade() {
+                    Some(id) => write!(f, "Animation Finished ({:?})", id),
+                    None => write!(f, "Animation Finished (Removed)"),
+                },
+                OtherEvent::Paste(s) => write!(f, "Paste ({} chars)", s.chars().count()),
                 OtherEvent::Unknown(s) => write!(f, "Unknown ({})", s),
             },
             _ => write!(f, "Unhandled Input Event"),
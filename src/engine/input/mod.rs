@@ -13,6 +13,9 @@ WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 See the License for the specific language governing permissions and
 limitations under the License.
 */
+mod clipboard;
+pub use clipboard::*;
+
 mod consts;
 pub use consts::*;
 
@@ -0,0 +1,259 @@
+/*
+Copyright 2025 Luke Maple
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+you may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PlayMode {
+    Loop,
+    PingPong,
+    Once,
+}
+
+/// Result of advancing an animation
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Step {
+    Idle,
+    Frame,
+    Finished,
+}
+
+/// Frame timing for dynamic sprites and textboxes.
+/// Each frame has its own duration, the cursor is the index of the frame to display.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Animation {
+    durations: Vec<Duration>,
+    mode: PlayMode,
+    cursor: usize,
+    reverse: bool,
+    paused: bool,
+    finished: bool,
+    #[serde(skip)]
+    last_tick: Option<Instant>,
+}
+
+impl Animation {
+    pub fn new(durations: Vec<Duration>, mode: PlayMode) -> Self {
+        Self {
+            durations,
+            mode,
+            cursor: 0,
+            reverse: false,
+            paused: false,
+            finished: false,
+            last_tick: None,
+        }
+    }
+
+    /// Every frame is shown for the same amount of time
+    pub fn uniform(frames: usize, tick_rate: Duration, mode: PlayMode) -> Self {
+        Self::new(vec![tick_rate; frames], mode)
+    }
+
+    pub fn looping(frames: usize, tick_rate: Duration) -> Self {
+        Self::uniform(frames, tick_rate, PlayMode::Loop)
+    }
+
+    pub fn once(frames: usize, tick_rate: Duration) -> Self {
+        Self::uniform(frames, tick_rate, PlayMode::Once)
+    }
+
+    /// Trims or pads the durations to one per frame, padding repeats the last duration.
+    /// Sprites and textboxes call it so a mismatched animation can never index past their frames
+    pub fn fit(mut self, frames: usize) -> Self {
+        let last = self.durations.last().copied().unwrap_or(Duration::from_millis(500));
+        self.durations.resize(frames, last);
+        self.cursor = self.cursor.min(frames.saturating_sub(1));
+        self
+    }
+
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    pub fn len(&self) -> usize {
+        self.durations.len()
+    }
+
+    pub fn mode(&self) -> PlayMode {
+        self.mode
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Resumes from the current frame, the time spent paused does not count towards it
+    pub fn resume(&mut self) {
+        if self.paused {
+            self.paused = false;
+            self.last_tick = None;
+        }
+    }
+
+    pub fn restart(&mut self) {
+        self.cursor = 0;
+        self.reverse = false;
+        self.finished = false;
+        self.paused = false;
+        self.last_tick = None;
+    }
+
+    /// When the current frame should be replaced, None when the animation will not change on its own
+    pub fn next_due(&self) -> Option<Instant> {
+        if self.paused || self.finished || (self.durations.len() < 2 && self.mode != PlayMode::Once) {
+            return None;
+        }
+        match self.last_tick {
+            Some(t) => Some(t + self.durations[self.cursor]),
+            // the clock starts on the first update
+            None => Some(Instant::now()),
+        }
+    }
+
+    pub fn update(&mut self) -> Step {
+        if self.paused || self.finished || self.durations.is_empty() {
+            return Step::Idle;
+        }
+        let now = Instant::now();
+        let mut last = match self.last_tick {
+            Some(t) => t,
+            None => {
+                self.last_tick = Some(now);
+                return Step::Idle;
+            }
+        };
+
+        let mut step = Step::Idle;
+        // catch up on every frame that expired since the last update
+        while now.duration_since(last) >= self.durations[self.cursor] {
+            last += self.durations[self.cursor];
+            if !self.advance() {
+                self.finished = true;
+                step = Step::Finished;
+                break;
+            }
+            step = Step::Frame;
+            if self.durations[self.cursor].is_zero() {
+                break;
+            }
+        }
+        self.last_tick = Some(last);
+        step
+    }
+
+    /// moves the cursor to the next frame, returns false when a one shot animation is done
+    fn advance(&mut self) -> bool {
+        let len = self.durations.len();
+        match self.mode {
+            PlayMode::Loop => {
+                self.cursor = (self.cursor + 1) % len;
+                true
+            }
+            PlayMode::Once => {
+                if self.cursor + 1 < len {
+                    self.cursor += 1;
+                    true
+                } else {
+                    false
+                }
+            }
+            PlayMode::PingPong => {
+                if len < 2 {
+                    return true;
+                }
+                if self.reverse {
+                    if self.cursor == 0 {
+                        self.reverse = false;
+                        self.cursor = 1;
+                    } else {
+                        self.cursor -= 1;
+                    }
+                } else if self.cursor + 1 == len {
+                    self.reverse = true;
+                    self.cursor -= 1;
+                } else {
+                    self.cursor += 1;
+                }
+                true
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn step(a: &mut Animation) {
+        a.last_tick = Some(Instant::now() - a.durations[a.cursor]);
+        a.update();
+    }
+
+    #[test]
+    fn animation_loop_test() {
+        let mut a = Animation::looping(3, Duration::from_millis(100));
+        let mut seen = vec![];
+        for _ in 0..4 {
+            step(&mut a);
+            seen.push(a.cursor());
+        }
+        assert_eq!(seen, vec![1, 2, 0, 1]);
+    }
+
+    #[test]
+    fn animation_ping_pong_test() {
+        let mut a = Animation::uniform(3, Duration::from_millis(100), PlayMode::PingPong);
+        let mut seen = vec![];
+        for _ in 0..5 {
+            step(&mut a);
+            seen.push(a.cursor());
+        }
+        assert_eq!(seen, vec![1, 2, 1, 0, 1]);
+    }
+
+    #[test]
+    fn animation_fit_test() {
+        let mut a = Animation::new(vec![Duration::from_millis(50)], PlayMode::Loop).fit(3);
+        assert_eq!(a.len(), 3);
+        assert_eq!(a.durations[2], Duration::from_millis(50));
+        step(&mut a);
+        step(&mut a);
+        a = a.fit(1);
+        assert_eq!((a.len(), a.cursor()), (1, 0));
+    }
+
+    #[test]
+    fn animation_once_test() {
+        let mut a = Animation::once(2, Duration::from_millis(100));
+        step(&mut a);
+        assert_eq!(a.cursor(), 1);
+        a.last_tick = Some(Instant::now() - Duration::from_millis(100));
+        assert_eq!(a.update(), Step::Finished);
+        assert!(a.is_finished());
+        assert_eq!(a.update(), Step::Idle);
+        assert_eq!(a.cursor(), 1);
+    }
+}
@@ -0,0 +1,281 @@
+/*
+Copyright 2025 Luke Maple
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+you may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+use super::{Char, palette::palette_rgb};
+use my_term::color::{BLACK, WHITE};
+use std::{
+    fmt::Write,
+    path::{Path, PathBuf},
+};
+
+pub const SCREENSHOT_DIR: &str = "./screenshots/";
+
+/// Grid of cells rebuilt from the escape sequences the renderer writes for a frame.
+/// The renderer never keeps a cell buffer of its own, so this is what gets exported.
+#[derive(Clone, Debug)]
+pub struct Screen {
+    width: usize,
+    height: usize,
+    cells: Vec<Char>,
+}
+
+impl Screen {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![Char::default(); width * height],
+        }
+    }
+
+    /// Replays a frame the way a terminal would, anything written outside the screen is dropped
+    pub fn from_ansi(frame: &[u8], width: usize, height: usize) -> Self {
+        let mut screen = Self::new(width, height);
+        let text = String::from_utf8_lossy(frame);
+        let mut chars = text.chars().peekable();
+        let (mut x, mut y) = (0usize, 0usize);
+        let (mut fg, mut bg) = (WHITE, BLACK);
+
+        while let Some(c) = chars.next() {
+            match c {
+                '\x1b' => {
+                    if chars.peek() != Some(&'[') {
+                        continue;
+                    }
+                    chars.next();
+                    let mut params = String::new();
+                    let mut cmd = '\0';
+                    for p in chars.by_ref() {
+                        if p.is_ascii_digit() || p == ';' || p == '?' {
+                            params.push(p);
+                        } else {
+                            cmd = p;
+                            break;
+                        }
+                    }
+                    let args: Vec<usize> = params
+                        .trim_start_matches('?')
+                        .split(';')
+                        .map(|a| a.parse().unwrap_or(0))
+                        .collect();
+                    let arg = |i: usize, default: usize| -> usize {
+                        match args.get(i) {
+                            Some(0) | None => default,
+                            Some(v) => *v,
+                        }
+                    };
+                    match cmd {
+                        'H' | 'f' => {
+                            y = arg(0, 1) - 1;
+                            x = arg(1, 1) - 1;
+                        }
+                        'A' => y = y.saturating_sub(arg(0, 1)),
+                        'B' => y += arg(0, 1),
+                        'C' => x += arg(0, 1),
+                        'D' => x = x.saturating_sub(arg(0, 1)),
+                        'J' if arg(0, 0) == 2 => screen.clear(),
+                        'm' => apply_sgr(&args, &mut fg, &mut bg),
+                        _ => {}
+                    }
+                }
+                '\r' => x = 0,
+                '\n' => y += 1,
+                c if c.is_control() => {}
+                c => {
+                    screen.set(x, y, Char::new(c, fg, bg));
+                    x += 1;
+                }
+            }
+        }
+        screen
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&Char> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        self.cells.get(y * self.width + x)
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, c: Char) {
+        if x < self.width && y < self.height {
+            self.cells[y * self.width + x] = c;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.fill(Char::default());
+    }
+
+    pub fn row(&self, y: usize) -> &[Char] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    /// Characters only, trailing spaces on each line are trimmed
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        for y in 0..self.height {
+            let line: String = self.row(y).iter().map(|c| c.as_char()).collect();
+            out.push_str(line.trim_end());
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Characters with 256 color escape codes, can be viewed with cat
+    pub fn to_ansi(&self) -> String {
+        let mut out = String::new();
+        for y in 0..self.height {
+            let mut prev: Option<&Char> = None;
+            for c in self.row(y) {
+                match prev {
+                    Some(p) if p.same_colors(c) => out.push(c.as_char()),
+                    _ => {
+                        let _ = write!(
+                            out,
+                            "\x1b[38;5;{}m\x1b[48;5;{}m{}",
+                            u8::from(c.fg()),
+                            u8::from(c.bg()),
+                            c.as_char()
+                        );
+                    }
+                }
+                prev = Some(c);
+            }
+            out.push_str("\x1b[0m\n");
+        }
+        out
+    }
+
+    /// Standalone page, every run of same colored cells becomes a span with inline styles
+    pub fn to_html(&self) -> String {
+        let mut out = String::from(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>SSHColony screenshot</title>\n</head>\n<body style=\"background:#000;margin:0\">\n<pre style=\"font-family:monospace;line-height:1.1;margin:0\">",
+        );
+        for y in 0..self.height {
+            let row = self.row(y);
+            let mut i = 0;
+            while i < row.len() {
+                let start = &row[i];
+                let (fr, fg, fb) = palette_rgb(start.fg().into());
+                let (br, bg, bb) = palette_rgb(start.bg().into());
+                let _ = write!(
+                    out,
+                    "<span style=\"color:#{fr:02x}{fg:02x}{fb:02x};background:#{br:02x}{bg:02x}{bb:02x}\">"
+                );
+                while i < row.len() && row[i].same_colors(start) {
+                    match row[i].as_char() {
+                        '<' => out.push_str("&lt;"),
+                        '>' => out.push_str("&gt;"),
+                        '&' => out.push_str("&amp;"),
+                        '"' => out.push_str("&quot;"),
+                        c => out.push(c),
+                    }
+                    i += 1;
+                }
+                out.push_str("</span>");
+            }
+            out.push('\n');
+        }
+        out.push_str("</pre>\n</body>\n</html>\n");
+        out
+    }
+
+    /// Writes name.txt, name.ans and name.html into dir, returns the path without an extension
+    pub fn save(&self, dir: impl AsRef<Path>, name: &str) -> std::io::Result<PathBuf> {
+        std::fs::create_dir_all(dir.as_ref())?;
+        let base = dir.as_ref().join(name);
+        std::fs::write(base.with_extension("txt"), self.to_text())?;
+        std::fs::write(base.with_extension("ans"), self.to_ansi())?;
+        std::fs::write(base.with_extension("html"), self.to_html())?;
+        Ok(base)
+    }
+}
+
+fn apply_sgr(args: &[usize], fg: &mut u8, bg: &mut u8) {
+    let mut i = 0;
+    while i < args.len() {
+        match args[i] {
+            0 => {
+                *fg = WHITE;
+                *bg = BLACK;
+            }
+            38 | 48 if args.get(i + 1) == Some(&5) => {
+                let c = args.get(i + 2).copied().unwrap_or(0) as u8;
+                if args[i] == 38 {
+                    *fg = c;
+                } else {
+                    *bg = c;
+                }
+                i += 2;
+            }
+            v @ 30..=37 => *fg = (v - 30) as u8,
+            v @ 90..=97 => *fg = (v - 90 + 8) as u8,
+            v @ 40..=47 => *bg = (v - 40) as u8,
+            v @ 100..=107 => *bg = (v - 100 + 8) as u8,
+            39 => *fg = WHITE,
+            49 => *bg = BLACK,
+            _ => {}
+        }
+        i += 1;
+    }
+}
+
+/// Name for a new screenshot, sortable by time
+pub fn screenshot_name() -> String {
+    chrono::Local::now().format("%Y%m%d-%H%M%S-%3f").to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn screen_from_ansi_test() {
+        let frame = b"\x1b[2J\x1b[2;3f\x1b[38;5;2m\x1b[48;5;0mab\x1b[1B\x1b[2Dc<";
+        let s = Screen::from_ansi(frame, 6, 4);
+        assert_eq!(s.to_text(), "\n  ab\n  c<\n\n");
+        assert_eq!(u8::from(s.get(2, 1).unwrap().fg()), 2);
+        assert!(s.to_html().contains("c&lt;"));
+        assert!(s.to_ansi().contains("\x1b[38;5;2m"));
+    }
+
+    #[test]
+    fn screen_headless_draw_test() {
+        use super::super::{Camera, Canvas, Object};
+        use crate::engine::types::Position3D;
+
+        let can = Canvas::new(20, 10);
+        let cam = Camera::new(20, 10);
+        let mut obj = Object::new_sprite_static(
+            super::super::Glyph::small(Char::new('@', 3u8, 0u8)),
+            Position3D::new(4, 2, 0),
+        );
+        let mut frame: Vec<u8> = Vec::new();
+        obj.draw(&can, &cam, &mut frame, "");
+        let s = Screen::from_ansi(&frame, can.width, can.height);
+        let at = s.get(4, 2).unwrap();
+        assert_eq!(at.as_char(), '@');
+    }
+}
@@ -3,14 +3,72 @@ use serde::Deserialize;
 use serde::Serialize;
 use std::fmt::Display;
 use std::fmt::Write;
+use std::ops::{BitOr, BitOrAssign};
 
 use crate::engine::render::Text;
 
+/// Text attributes besides color, combined with |
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Attributes(u8);
+
+impl Attributes {
+    pub const NONE: Self = Self(0);
+    pub const BOLD: Self = Self(1);
+    pub const DIM: Self = Self(1 << 1);
+    pub const ITALIC: Self = Self(1 << 2);
+    pub const UNDERLINE: Self = Self(1 << 3);
+    pub const BLINK: Self = Self(1 << 4);
+    pub const REVERSE: Self = Self(1 << 5);
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Escape sequence turning the attributes on
+    fn sgr(&self) -> String {
+        let codes: Vec<&str> = [
+            (Self::BOLD, "1"),
+            (Self::DIM, "2"),
+            (Self::ITALIC, "3"),
+            (Self::UNDERLINE, "4"),
+            (Self::BLINK, "5"),
+            (Self::REVERSE, "7"),
+        ]
+        .into_iter()
+        .filter(|(a, _)| self.contains(*a))
+        .map(|(_, code)| code)
+        .collect();
+        format!("\x1b[{}m", codes.join(";"))
+    }
+}
+
+impl BitOr for Attributes {
+    type Output = Self;
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for Attributes {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// Turns every attribute off again and leaves the colors alone
+const ATTRIBUTES_OFF: &str = "\x1b[22;23;24;25;27m";
+
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub struct Character {
     fg: Foreground,
     bg: Background,
     sym: char,
+    #[serde(default)]
+    attrs: Attributes,
 }
 
 impl Character {
@@ -19,6 +77,7 @@ impl Character {
             fg: Foreground::new(fg.into()),
             bg: Background::new(bg.into()),
             sym: c,
+            attrs: Attributes::NONE,
         }
     }
     pub fn set_fg(mut self, fg: u8) -> Self {
@@ -36,13 +95,35 @@ impl Character {
         self
     }
 
+    pub fn set_attrs(mut self, attrs: Attributes) -> Self {
+        self.attrs = attrs;
+        self
+    }
+
     pub fn same_colors(&self, other: &Character) -> bool {
         self.fg == other.fg && self.bg == other.bg
     }
 
+    /// Same colors and no attributes on either, so the character can follow the other without escapes
+    pub fn same_style(&self, other: &Character) -> bool {
+        self.same_colors(other) && self.attrs.is_empty() && other.attrs.is_empty()
+    }
+
     pub fn as_char(&self) -> char {
         self.sym
     }
+
+    pub fn fg(&self) -> Foreground {
+        self.fg
+    }
+
+    pub fn bg(&self) -> Background {
+        self.bg
+    }
+
+    pub fn attrs(&self) -> Attributes {
+        self.attrs
+    }
 }
 
 impl Default for Character {
@@ -51,13 +132,19 @@ impl Default for Character {
             fg: Foreground::new(WHITE),
             bg: Background::new(BLACK),
             sym: ' ',
+            attrs: Attributes::NONE,
         }
     }
 }
 
+/// Attributes are turned off again right after the character so they never leak into what follows
 impl Display for Character {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}{}{}", self.fg, self.bg, self.sym)
+        if self.attrs.is_empty() {
+            write!(f, "{}{}{}", self.fg, self.bg, self.sym)
+        } else {
+            write!(f, "{}{}{}{}{}", self.fg, self.bg, self.attrs.sgr(), self.sym, ATTRIBUTES_OFF)
+        }
     }
 }
 
@@ -77,6 +164,6 @@ pub trait PushChar {
 
 impl PushChar for String {
     fn push_char(&mut self, c: &Character) {
-        write!(self, "{}{}{}", c.fg, c.bg, c.sym);
+        write!(self, "{c}");
     }
 }
@@ -4,6 +4,7 @@ use crate::engine::render::drawable::text::TextSlice;
 
 use super::Text;
 use super::Character as Char;
+use my_term::color::Foreground;
 
 #[derive(Clone, Debug)]
 pub struct GlyphSlice<'a> {
@@ -47,6 +48,17 @@ impl Glyph {
         Self::Block(b)
     }
 
+    pub fn set_fg(&mut self, fg: Foreground) {
+        match self {
+            Self::Small(c) => *c = c.set_fg(fg.into()),
+            Self::Block(b) => {
+                for l in b.iter_mut() {
+                    l.set_fg(fg);
+                }
+            }
+        }
+    }
+
     pub fn is_block(&self) -> bool {
         match self {
             Self::Block(_) => true,
@@ -0,0 +1,203 @@
+/*
+Copyright 2025 Luke Maple
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+you may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+use super::{Attributes, Character as Char, Text};
+use crate::engine::{Error, ErrorKind, render::palette::nearest_palette};
+
+#[derive(Clone, Copy)]
+struct Span {
+    fg: u8,
+    bg: u8,
+    attrs: Attributes,
+}
+
+const NAMED: [(&str, u8); 17] = [
+    ("black", 0),
+    ("red", 1),
+    ("green", 2),
+    ("yellow", 3),
+    ("blue", 4),
+    ("magenta", 5),
+    ("cyan", 6),
+    ("white", 7),
+    ("gray", 8),
+    ("bright_red", 9),
+    ("bright_green", 10),
+    ("bright_yellow", 11),
+    ("bright_blue", 12),
+    ("bright_magenta", 13),
+    ("bright_cyan", 14),
+    ("bright_white", 15),
+    ("grey", 8),
+];
+
+fn invalid(msg: String) -> Error {
+    Error::new(msg, ErrorKind::InvalidData)
+}
+
+/// Palette index from a name, a number or #rrggbb
+fn color(token: &str) -> Option<u8> {
+    if let Some((_, c)) = NAMED.iter().find(|(name, _)| *name == token) {
+        return Some(*c);
+    }
+    if let Some(hex) = token.strip_prefix('#') {
+        if hex.len() != 6 {
+            return None;
+        }
+        let rgb = u32::from_str_radix(hex, 16).ok()?;
+        return Some(nearest_palette((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8));
+    }
+    token.parse().ok()
+}
+
+fn attribute(token: &str) -> Option<Attributes> {
+    match token {
+        "b" | "bold" => Some(Attributes::BOLD),
+        "dim" => Some(Attributes::DIM),
+        "i" | "italic" => Some(Attributes::ITALIC),
+        "u" | "underline" => Some(Attributes::UNDERLINE),
+        "blink" => Some(Attributes::BLINK),
+        "reverse" => Some(Attributes::REVERSE),
+        _ => None,
+    }
+}
+
+/// Style of a tag's contents, anything the tag does not mention is taken from the outer span
+fn open_tag(tag: &str, outer: Span) -> Result<Span, Error> {
+    let mut span = outer;
+    let mut tokens = tag.split_whitespace();
+    if tag.trim().is_empty() {
+        return Err(invalid("empty markup tag []".to_string()));
+    }
+    while let Some(token) = tokens.next() {
+        if token == "on" {
+            let bg = tokens.next().ok_or_else(|| invalid(format!("[{}] has no color after on", tag)))?;
+            span.bg = color(bg).ok_or_else(|| invalid(format!("unknown color {} in [{}]", bg, tag)))?;
+        } else if let Some(a) = attribute(token) {
+            span.attrs |= a;
+        } else if let Some(c) = color(token) {
+            span.fg = c;
+        } else {
+            return Err(invalid(format!("unknown style {} in [{}]", token, tag)));
+        }
+    }
+    Ok(span)
+}
+
+impl Text {
+    /// Text from inline markup such as `[green]Urist[/] strikes the [b red on black]goblin[/]`.
+    /// A tag holds attributes (b, dim, i, u, blink, reverse), a foreground color and `on`
+    /// followed by a background color. Colors are palette names, numbers or #rrggbb.
+    /// `[/]`, or `[/anything]`, closes the last open tag, tags still open at the end are closed
+    /// there. `\[` is a literal bracket and `\\` a literal backslash
+    pub fn from_markup(s: &str, fg: impl Into<u8>, bg: impl Into<u8>) -> Result<Text, Error> {
+        let mut out = Text::new();
+        let mut stack = vec![Span {
+            fg: fg.into(),
+            bg: bg.into(),
+            attrs: Attributes::NONE,
+        }];
+        let mut chars = s.chars();
+        while let Some(c) = chars.next() {
+            let c = match c {
+                '\\' => chars.next().unwrap_or('\\'),
+                '[' => {
+                    let mut tag = String::new();
+                    loop {
+                        match chars.next() {
+                            Some(']') => break,
+                            Some(t) => tag.push(t),
+                            None => return Err(invalid(format!("markup tag [{} is never closed", tag))),
+                        }
+                    }
+                    if tag.starts_with('/') {
+                        if stack.len() == 1 {
+                            return Err(invalid(format!("[{}] has no open tag to close", tag)));
+                        }
+                        stack.pop();
+                    } else {
+                        let outer = *stack.last().unwrap();
+                        stack.push(open_tag(&tag, outer)?);
+                    }
+                    continue;
+                }
+                c => c,
+            };
+            let span = stack.last().unwrap();
+            out.push(Char::new(c, span.fg, span.bg).set_attrs(span.attrs));
+        }
+        Ok(out)
+    }
+}
+
+/// Markup split into one Text per line, a tag left open carries on to the next line
+pub fn markup_lines(s: &str, fg: impl Into<u8>, bg: impl Into<u8>) -> Result<Vec<Text>, Error> {
+    let text = Text::from_markup(s, fg, bg)?;
+    let mut lines = vec![Text::new()];
+    for c in text {
+        if c.as_char() == '\n' {
+            lines.push(Text::new());
+        } else {
+            lines.last_mut().unwrap().push(c);
+        }
+    }
+    Ok(lines)
+}
+
+/// Escapes brackets and backslashes so the string shows as is when put into markup
+pub fn escape_markup(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        if c == '[' || c == '\\' {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn markup_test() {
+        let t = Text::from_markup("[green]Urist[/] strikes the [b red]goblin[/]", 7u8, 0u8).unwrap();
+        assert_eq!(t.to_string(), "Urist strikes the goblin");
+        let chars: Vec<&Char> = (&t).into_iter().collect();
+        assert_eq!(u8::from(chars[0].fg()), 2);
+        assert_eq!(u8::from(chars[5].fg()), 7);
+        assert!(chars[18].attrs().contains(Attributes::BOLD));
+        assert_eq!(u8::from(chars[18].fg()), 1);
+
+        let nested = Text::from_markup("[on blue][#ff0000]a[/]b", 7u8, 0u8).unwrap();
+        let chars: Vec<&Char> = (&nested).into_iter().collect();
+        assert_eq!((u8::from(chars[0].fg()), u8::from(chars[0].bg())), (196, 4));
+        assert_eq!((u8::from(chars[1].fg()), u8::from(chars[1].bg())), (7, 4));
+
+        let raw = "[not a tag] \\o/";
+        let escaped = Text::from_markup(&escape_markup(raw), 7u8, 0u8).unwrap();
+        assert_eq!(escaped.to_string(), raw);
+
+        assert!(Text::from_markup("[sparkly]x", 7u8, 0u8).is_err());
+        assert!(Text::from_markup("x[/]", 7u8, 0u8).is_err());
+        assert!(Text::from_markup("[red", 7u8, 0u8).is_err());
+        let lines = markup_lines("[red]one\ntwo", 7u8, 0u8).unwrap();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1].to_string(), "two");
+        assert_eq!(u8::from((&lines[1]).into_iter().next().unwrap().fg()), 1);
+    }
+}
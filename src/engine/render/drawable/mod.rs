@@ -1,9 +1,11 @@
 mod character;
 mod glyph;
 mod line;
+mod markup;
 mod text;
 
-pub use character::{Character, PushChar};
+pub use character::{Attributes, Character, PushChar};
 pub use glyph::Glyph;
 pub use line::Line;
+pub use markup::{escape_markup, markup_lines};
 pub use text::{PushText, Text, TextSlice};
@@ -1,4 +1,4 @@
-use super::Character as Char;
+use super::{Attributes, Character as Char};
 use my_term::color::{Background, Foreground};
 use serde::Deserialize;
 use serde::Serialize;
@@ -18,7 +18,7 @@ impl<'a> TextSlice<'a> {
 impl<'a> std::fmt::Display for TextSlice<'a> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         for (i, c) in self.data.iter().enumerate() {
-            if i == 0 || !c.same_colors(&self.data[i - 1]) {
+            if i == 0 || !c.same_style(&self.data[i - 1]) {
                 if let Err(e) = write!(f, "{c}") {
                     return std::fmt::Result::Err(e);
                 }
@@ -131,6 +131,24 @@ impl Text {
         self.data.len()
     }
 
+    pub fn set_fg(&mut self, fg: Foreground) {
+        for c in self.data.iter_mut() {
+            *c = c.set_fg(fg.into());
+        }
+    }
+
+    pub fn set_bg(&mut self, bg: Background) {
+        for c in self.data.iter_mut() {
+            *c = c.set_bg(bg.into());
+        }
+    }
+
+    pub fn set_attrs(&mut self, attrs: Attributes) {
+        for c in self.data.iter_mut() {
+            *c = c.set_attrs(attrs);
+        }
+    }
+
     pub fn as_slice(&self) -> TextSlice {
         TextSlice { data: &self.data }
     }
@@ -146,7 +164,7 @@ impl Text {
 impl std::fmt::Display for Text {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         for (i, c) in self.data.iter().enumerate() {
-            if i == 0 || !c.same_colors(&self.data[i - 1]) {
+            if i == 0 || !c.same_style(&self.data[i - 1]) {
                 if let Err(e) = write!(f, "{c}") {
                     return std::fmt::Result::Err(e);
                 }
@@ -22,11 +22,10 @@ use std::io::Write as iowrite;
 
 use super::{
     super::{
-        traits::Storeable,
         types::{self as enginetypes, Position, Position3D},
         ui::Border,
     },
-    Camera, Canvas, Glyph, Sprite, Textbox, TextboxSlice,
+    Animation, Camera, Canvas, Cells, Glyph, Sprite, Step, Textbox, TextboxSlice, TileMap,
 };
 use my_term::color::{Background, Foreground};
 use serde::{Deserialize, Serialize};
@@ -54,6 +53,10 @@ pub enum GlyphType {
         frames: Vec<Glyph>,
         tick_rate: Duration,
     },
+    Animated {
+        frames: Vec<Glyph>,
+        anim: Animation,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -63,6 +66,10 @@ pub enum TextType {
         frames: Vec<Base>,
         tick_rate: Duration,
     },
+    Animated {
+        frames: Vec<Base>,
+        anim: Animation,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -76,12 +83,18 @@ pub enum ObjectData {
         data: TextType,
         style: Style,
     },
+    TileMap {
+        pos: Position3D<i32>,
+        size: Position3D<usize>,
+        chunk_size: usize,
+    },
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum Object {
     Sprite(Sprite),
     Text(Textbox),
+    TileMap(TileMap),
 }
 
 impl Object {
@@ -110,6 +123,10 @@ impl Object {
         Self::Text(Textbox::new_dynamic(pos, frames, tick_rate))
     }
 
+    pub fn new_tilemap(pos: Position3D<i32>, size: Position3D<usize>, chunk_size: usize) -> Self {
+        Self::TileMap(TileMap::new(pos, size, chunk_size))
+    }
+
     pub fn from_data(data: impl Into<ObjectData>, canvas: &Canvas) -> Self {
         match data.into() {
             ObjectData::Sprite { pos, glyph } => match glyph {
@@ -117,13 +134,24 @@ impl Object {
                 GlyphType::Multi { frames, tick_rate } => {
                     Self::Sprite(Sprite::new_dynamic(frames, pos, tick_rate))
                 }
+                GlyphType::Animated { frames, anim } => {
+                    Self::Sprite(Sprite::new_animated(frames, pos, anim))
+                }
             },
             ObjectData::Text { pos, data, style } => match data {
                 TextType::Single(t) => Self::Text(Textbox::new_static(pos, t, style, canvas)),
                 TextType::Multi { frames, tick_rate } => {
                     Self::Text(Textbox::new_dynamic(pos, frames, tick_rate))
                 }
+                TextType::Animated { frames, anim } => {
+                    Self::Text(Textbox::new_animated(pos, frames, anim))
+                }
             },
+            ObjectData::TileMap {
+                pos,
+                size,
+                chunk_size,
+            } => Self::TileMap(TileMap::new(pos, size, chunk_size)),
         }
     }
 
@@ -131,6 +159,7 @@ impl Object {
         match self {
             Self::Sprite(s) => s.pos(),
             Self::Text(t) => t.pos(),
+            Self::TileMap(m) => m.pos(),
         }
     }
 
@@ -138,6 +167,7 @@ impl Object {
         match self {
             Self::Sprite(s) => s.width(),
             Self::Text(t) => t.width(can),
+            Self::TileMap(m) => m.width(),
         }
     }
 
@@ -145,13 +175,19 @@ impl Object {
         match self {
             Self::Sprite(s) => s.height(),
             Self::Text(t) => t.height(can),
+            Self::TileMap(m) => m.height(),
         }
     }
 
+    /// Returns false when the object was culled by the camera
     #[deny(unused)]
-    pub fn draw(&mut self, can: &Canvas, cam: &Camera, stream: &mut std::io::Stdout, _reset: &str) {
+    pub fn draw(&mut self, can: &Canvas, cam: &Camera, stream: &mut impl iowrite, _reset: &str) -> bool {
+        // Tile maps span several z levels and clip per cell so they skip the single box path
+        if let Self::TileMap(m) = self {
+            return m.draw(cam, stream) > 0;
+        }
         if !cam.in_view(self, can) {
-            return;
+            return false;
         }
         let width: i32 = self.width(can) as i32;
         let height: i32 = self.height(can) as i32;
@@ -191,44 +227,10 @@ impl Object {
             0
         };
 
-        let _ = write!(
-            stream,
-            "\x1b[{};{}fObject Edges (l,r,t,b): {},{},{},{} | Cam Edges (l,r,t,b): {},{},{},{}  |",
-            can.height - 3,
-            0,
-            l_edge,
-            r_edge,
-            t_edge,
-            b_edge,
-            cam_l_edge,
-            cam_r_edge,
-            cam_t_edge,
-            cam_b_edge
-        );
-        let _ = write!(
-            stream,
-            "\x1b[{};{}fOffsets (l,r,t,b): {},{},{},{} | screen pos (x,y): {},{} | world pos (x,y): {},{}     ",
-            can.height - 2,
-            0,
-            l_delta,
-            r_delta,
-            t_delta,
-            b_delta,
-            scr_pos.x,
-            scr_pos.y,
-            virt_pos.x,
-            virt_pos.y
-        );
         let cursor_pos: Position<i32> = Position {
             x: scr_pos.x + l_delta as i32,
             y: scr_pos.y + t_delta as i32,
         };
-        let _ = write!(
-            stream,
-            "move cursor (x, y): {},{} |",
-            cursor_pos.x, cursor_pos.y,
-        );
-
         let _ = write!(stream, "\x1b[{};{}f", cursor_pos.y, cursor_pos.x,);
         match self {
             Self::Sprite(s) => {
@@ -237,7 +239,9 @@ impl Object {
             Self::Text(t) => {
                 let _ = write!(stream, "{}", t.slice(l_delta, r_delta, t_delta, b_delta));
             }
+            Self::TileMap(_) => {}
         }
+        true
     }
 
     pub fn is_sprite(&self) -> bool {
@@ -254,10 +258,26 @@ impl Object {
         }
     }
 
+    pub fn set_fg(&mut self, fg: Foreground) {
+        match self {
+            Self::Sprite(s) => s.set_fg(fg),
+            Self::Text(t) => t.set_fg(fg),
+            Self::TileMap(_) => {}
+        }
+    }
+
+    pub fn is_tilemap(&self) -> bool {
+        match self {
+            Self::TileMap(_) => true,
+            _ => false,
+        }
+    }
+
     pub fn move_pos(&mut self, pos: Position3D<i32>) {
         match self {
             Self::Sprite(s) => s.move_pos(pos),
             Self::Text(t) => t.move_pos(pos),
+            Self::TileMap(m) => m.move_pos(pos),
         }
     }
 
@@ -265,6 +285,14 @@ impl Object {
         match self {
             Self::Sprite(s) => s.is_dynamic(),
             Self::Text(t) => t.is_dynamic(),
+            Self::TileMap(_) => false,
+        }
+    }
+
+    /// Text is laid out against the canvas so it has to be redone when the canvas changes size
+    pub fn rebuild(&mut self, canvas: &Canvas) {
+        if let Self::Text(t) = self {
+            t.rebuild(canvas);
         }
     }
 
@@ -272,27 +300,39 @@ impl Object {
         match self {
             Self::Sprite(s) => s.is_static(),
             Self::Text(t) => t.is_static(),
+            Self::TileMap(_) => true,
         }
     }
 
-    pub fn update(&mut self) -> bool {
+    pub fn update(&mut self) -> Step {
         match self {
             Self::Sprite(s) => s.update(),
             Self::Text(t) => t.update(),
+            Self::TileMap(_) => Step::Idle,
         }
     }
-}
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ObjectTemplate {
-    name: String,
-    data: Object,
-}
+    pub fn animation(&self) -> Option<&Animation> {
+        match self {
+            Self::Sprite(s) => s.animation(),
+            Self::Text(t) => t.animation(),
+            Self::TileMap(_) => None,
+        }
+    }
 
-impl Storeable for ObjectTemplate {
-    type Key = String;
-    fn key(&self) -> Self::Key {
-        self.name.clone()
+    pub fn animation_mut(&mut self) -> Option<&mut Animation> {
+        match self {
+            Self::Sprite(s) => s.animation_mut(),
+            Self::Text(t) => t.animation_mut(),
+            Self::TileMap(_) => None,
+        }
+    }
+
+    pub fn set_cells(&mut self, cells: &Cells) -> bool {
+        match self {
+            Self::TileMap(m) => m.set_cells(cells),
+            _ => false,
+        }
     }
 }
 
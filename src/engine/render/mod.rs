@@ -14,24 +14,50 @@ See the License for the specific language governing permissions and
 limitations under the License.
 */
 
+mod animation;
 mod camera;
+mod capture;
 mod canvas;
 mod drawable;
 mod enums;
 mod functions;
+mod offscreen;
+mod overlay;
+mod query;
+mod palette;
+mod particle;
 mod render_thread;
+mod recorder;
 mod render_unit;
 mod sprite;
+mod template;
 mod text;
+mod tilemap;
+mod tween;
 
+pub use animation::{Animation, PlayMode, Step};
 pub use camera::Camera;
+pub use capture::{SCREENSHOT_DIR, Screen, screenshot_name};
 pub use canvas::Canvas;
-pub use drawable::{Character as Char, Glyph, PushChar, PushText, Text};
+pub use drawable::{
+    Attributes, Character as Char, Glyph, PushChar, PushText, Text, escape_markup, markup_lines,
+};
 pub use enums::*;
 pub use functions::*;
-pub use render_thread::{RenderQueue, render_thread};
+pub use offscreen::{RenderTarget, downsample, screen_glyph};
+pub use overlay::{DebugOverlay, FrameStats};
+pub use particle::{Emitter, EmitterTemplate, PARTICLE_DIR};
+pub use query::{
+    Bounds, CameraInfo, DEFAULT_QUERY_TIMEOUT, Hit, Reply, hit_test, query_bounds, query_camera,
+    query_stats,
+};
+pub use recorder::{RECORDING_DIR, Recorder};
+pub use render_thread::{DEFAULT_MAX_FPS, RenderQueue, render_thread};
 pub use render_unit::RenderUnitId;
 pub use sprite::Sprite;
-pub use text::{Textbox, TextboxSlice};
+pub use template::{CellColor, FrameTemplate, SpriteTemplate};
+pub use text::{Textbox, TextboxSlice, flow};
+pub use tilemap::{Cells, TileMap};
+pub use tween::{Easing, Interrupt, Tween, TweenKind};
 
 use render_unit::RenderUnit;
@@ -0,0 +1,207 @@
+/*
+Copyright 2025 Luke Maple
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+you may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+use super::{super::types::Position3D, Camera, Char, Glyph, Layer, Text, capture::Screen};
+use my_term::color::YELLOW;
+
+/// Size and camera for drawing part of the world into a buffer instead of the terminal.
+/// The result is either shown as a render unit or handed back as a Screen
+#[derive(Clone, Debug)]
+pub struct RenderTarget {
+    pub width: u32,
+    pub height: u32,
+    /// world cells per target cell, every scale x scale block of the world becomes one cell
+    pub scale: u32,
+    /// top left corner in world cells, None keeps the main camera in the middle
+    pub pos: Option<Position3D<i32>>,
+    pub layers: Vec<Layer>,
+    /// background color drawn along the edge of the main camera's view
+    pub highlight: Option<u8>,
+}
+
+impl RenderTarget {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width: width.max(1),
+            height: height.max(1),
+            scale: 1,
+            pos: None,
+            layers: vec![Layer::Background, Layer::Middleground, Layer::Foreground],
+            highlight: None,
+        }
+    }
+
+    /// Downsampled view that follows the main camera and marks what it can see
+    pub fn minimap(width: u32, height: u32, scale: u32) -> Self {
+        Self::new(width, height)
+            .set_scale(scale)
+            .set_highlight(Some(YELLOW))
+    }
+
+    pub fn set_scale(mut self, scale: u32) -> Self {
+        self.scale = scale.max(1);
+        self
+    }
+
+    pub fn set_pos(mut self, pos: Option<Position3D<i32>>) -> Self {
+        self.pos = pos;
+        self
+    }
+
+    pub fn set_layers(mut self, layers: Vec<Layer>) -> Self {
+        self.layers = layers;
+        self
+    }
+
+    pub fn set_highlight(mut self, color: Option<u8>) -> Self {
+        self.highlight = color;
+        self
+    }
+
+    /// Camera over the world area the target covers, before downsampling
+    pub fn camera(&self, main: &Camera) -> Camera {
+        let w = self.width * self.scale;
+        let h = self.height * self.scale;
+        let pos = self.pos.unwrap_or_else(|| {
+            Position3D::new(
+                main.x() + main.width() as i32 / 2 - w as i32 / 2,
+                main.y() + main.height() as i32 / 2 - h as i32 / 2,
+                main.z(),
+            )
+        });
+        Camera::new(w, h)
+            .startingz_x(pos.x)
+            .startingz_y(pos.y)
+            .startingz_z(pos.z)
+    }
+
+    /// Turns a frame drawn through camera() into the cells of the target
+    pub fn compose(&self, frame: &[u8], cam: &Camera, main: &Camera) -> Screen {
+        let full = Screen::from_ansi(frame, cam.width() as usize, cam.height() as usize);
+        let mut screen = downsample(&full, self.scale as usize);
+        if let Some(color) = self.highlight {
+            mark_view(&mut screen, cam, main, self.scale as i32, color);
+        }
+        screen
+    }
+}
+
+/// Every scale x scale block becomes the character seen most often in it, blanks only win an empty block
+pub fn downsample(src: &Screen, scale: usize) -> Screen {
+    if scale <= 1 {
+        return src.clone();
+    }
+    let mut out = Screen::new(src.width() / scale, src.height() / scale);
+    for y in 0..out.height() {
+        for x in 0..out.width() {
+            let mut seen: Vec<(Char, usize)> = Vec::new();
+            for sy in y * scale..(y + 1) * scale {
+                for sx in x * scale..(x + 1) * scale {
+                    let Some(c) = src.get(sx, sy) else {
+                        continue;
+                    };
+                    if c.as_char() == ' ' {
+                        continue;
+                    }
+                    match seen.iter_mut().find(|(s, _)| s.as_char() == c.as_char()) {
+                        Some((_, n)) => *n += 1,
+                        None => seen.push((*c, 1)),
+                    }
+                }
+            }
+            // max_by_key keeps the last of equal counts so the first seen is found by reversing
+            if let Some((c, _)) = seen.iter().rev().max_by_key(|(_, n)| *n) {
+                out.set(x, y, *c);
+            } else if let Some(c) = src.get(x * scale, y * scale) {
+                out.set(x, y, *c);
+            }
+        }
+    }
+    out
+}
+
+/// Colors the background of the cells along the edge of what the main camera sees
+fn mark_view(screen: &mut Screen, cam: &Camera, main: &Camera, scale: i32, color: u8) {
+    if main.z() != cam.z() || main.width() == 0 || main.height() == 0 {
+        return;
+    }
+    let left = (main.x() - cam.x()).div_euclid(scale);
+    let right = (main.x() + main.width() as i32 - 1 - cam.x()).div_euclid(scale);
+    let top = (main.y() - cam.y()).div_euclid(scale);
+    let bot = (main.y() + main.height() as i32 - 1 - cam.y()).div_euclid(scale);
+    for y in top..=bot {
+        for x in left..=right {
+            if x != left && x != right && y != top && y != bot {
+                continue;
+            }
+            if x < 0 || y < 0 {
+                continue;
+            }
+            let (x, y) = (x as usize, y as usize);
+            if let Some(c) = screen.get(x, y) {
+                let marked = Char::new(c.as_char(), u8::from(c.fg()), color);
+                screen.set(x, y, marked);
+            }
+        }
+    }
+}
+
+/// Block glyph with the same cells as the screen, used to show a target as a render unit
+pub fn screen_glyph(screen: &Screen) -> Glyph {
+    let mut lines = Vec::with_capacity(screen.height());
+    for y in 0..screen.height() {
+        let mut line = Text::new();
+        for c in screen.row(y) {
+            line.push(*c);
+        }
+        lines.push(line);
+    }
+    Glyph::block(lines)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn downsample_test() {
+        let mut src = Screen::new(4, 2);
+        for (x, c) in ['.', '.', '#', ' '].into_iter().enumerate() {
+            src.set(x, 0, Char::new(c, 7u8, 0u8));
+        }
+        src.set(0, 1, Char::new('#', 7u8, 0u8));
+        let out = downsample(&src, 2);
+        assert_eq!((out.width(), out.height()), (2, 1));
+        // the first block has two dots and one wall
+        assert_eq!(out.get(0, 0).unwrap().as_char(), '.');
+        assert_eq!(out.get(1, 0).unwrap().as_char(), '#');
+    }
+
+    #[test]
+    fn minimap_view_test() {
+        let main = Camera::new(8, 4).startingz_x(4).startingz_y(4);
+        let target = RenderTarget::minimap(8, 6, 2).set_pos(Some(Position3D::new(0, 0, 0)));
+        let cam = target.camera(&main);
+        assert_eq!((cam.width(), cam.height()), (16, 12));
+        let screen = target.compose(b"", &cam, &main);
+        // the main camera covers world 4..12 x 4..8 which is cells 2..=5 x 2..=3
+        assert_eq!(u8::from(screen.get(2, 2).unwrap().bg()), YELLOW);
+        assert_eq!(u8::from(screen.get(5, 3).unwrap().bg()), YELLOW);
+        assert_ne!(u8::from(screen.get(1, 1).unwrap().bg()), YELLOW);
+        let centered = RenderTarget::minimap(8, 6, 2).camera(&main);
+        assert_eq!((centered.x(), centered.y()), (0, 0));
+    }
+}
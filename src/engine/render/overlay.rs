@@ -0,0 +1,167 @@
+/*
+Copyright 2025 Luke Maple
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+you may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+use super::Canvas;
+use std::{
+    collections::VecDeque,
+    io::Write,
+    time::{Duration, Instant},
+};
+
+const OVERLAY_WIDTH: usize = 28;
+const OVERLAY_FG: u8 = 16;
+const OVERLAY_BG: u8 = 250;
+
+/// Numbers collected while the render thread builds a frame
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FrameStats {
+    pub frame_time: Duration,
+    /// most signals found waiting in one wake of the render thread
+    pub backlog: usize,
+    pub signals: usize,
+    pub drawn: usize,
+    pub culled: usize,
+    pub bytes: usize,
+}
+
+/// Performance readout drawn above every other layer in the top right corner
+#[derive(Debug)]
+pub struct DebugOverlay {
+    enabled: bool,
+    frames: VecDeque<Instant>,
+    last: FrameStats,
+    current: FrameStats,
+}
+
+impl DebugOverlay {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            frames: VecDeque::new(),
+            last: FrameStats::default(),
+            current: FrameStats::default(),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    pub fn toggle(&mut self) {
+        self.enabled = !self.enabled;
+    }
+
+    pub fn count_signal(&mut self) {
+        self.current.signals += 1;
+    }
+
+    /// Count of signals taken off the queue in one go, the largest one in the frame is kept
+    pub fn drained(&mut self, count: usize) {
+        self.current.backlog = self.current.backlog.max(count);
+    }
+
+    pub fn count_object(&mut self, drawn: bool) {
+        if drawn {
+            self.current.drawn += 1;
+        } else {
+            self.current.culled += 1;
+        }
+    }
+
+    /// Closes the current frame, the counters start over for the next one
+    pub fn end_frame(&mut self, started: Instant, bytes: usize) {
+        let now = Instant::now();
+        self.current.frame_time = now - started;
+        self.current.bytes = bytes;
+        self.last = self.current;
+        self.current = FrameStats::default();
+        self.frames.push_back(now);
+        while let Some(f) = self.frames.front() {
+            if now.duration_since(*f) > Duration::from_secs(1) {
+                self.frames.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+
+    pub fn fps(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn last(&self) -> &FrameStats {
+        &self.last
+    }
+
+    pub fn lines(&self, tick_time: Duration) -> Vec<String> {
+        let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+        vec![
+            format!("fps     {}", self.fps()),
+            format!("frame   {:.2}ms", ms(self.last.frame_time)),
+            format!("tick    {:.2}ms", ms(tick_time)),
+            format!("backlog {}", self.last.backlog),
+            format!("signals {}", self.last.signals),
+            format!("objects {}/{}", self.last.drawn, self.last.drawn + self.last.culled),
+            format!("bytes   {}", self.last.bytes),
+        ]
+    }
+
+    pub fn draw(&self, out: &mut impl Write, can: &Canvas, tick_time: Duration) {
+        if !self.enabled || can.width < OVERLAY_WIDTH {
+            return;
+        }
+        let x = can.width - OVERLAY_WIDTH + 1;
+        for (i, line) in self.lines(tick_time).iter().enumerate() {
+            if i + 1 > can.height {
+                break;
+            }
+            let _ = write!(
+                out,
+                "\x1b[{};{}f\x1b[38;5;{OVERLAY_FG}m\x1b[48;5;{OVERLAY_BG}m {:<w$}\x1b[0m",
+                i + 1,
+                x,
+                line,
+                w = OVERLAY_WIDTH - 1
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn overlay_counts_test() {
+        let mut o = DebugOverlay::new(true);
+        o.count_signal();
+        o.count_signal();
+        o.drained(3);
+        o.drained(1);
+        o.count_object(true);
+        o.count_object(false);
+        o.end_frame(Instant::now(), 120);
+        assert_eq!(o.last().signals, 2);
+        assert_eq!(o.last().backlog, 3);
+        assert_eq!((o.last().drawn, o.last().culled), (1, 1));
+        assert_eq!(o.fps(), 1);
+        assert!(o.lines(Duration::ZERO)[5].ends_with("1/2"));
+    }
+}
@@ -0,0 +1,164 @@
+/*
+Copyright 2025 Luke Maple
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+you may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+// Helpers for working with the xterm 256 color palette that Foreground/Background index into
+
+const SYSTEM_COLORS: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (128, 0, 0),
+    (0, 128, 0),
+    (128, 128, 0),
+    (0, 0, 128),
+    (128, 0, 128),
+    (0, 128, 128),
+    (192, 192, 192),
+    (128, 128, 128),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (0, 0, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+pub fn palette_rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => SYSTEM_COLORS[index as usize],
+        16..=231 => {
+            let i = index - 16;
+            (
+                CUBE_LEVELS[(i / 36) as usize],
+                CUBE_LEVELS[((i / 6) % 6) as usize],
+                CUBE_LEVELS[(i % 6) as usize],
+            )
+        }
+        _ => {
+            let v = 8 + (index - 232) * 10;
+            (v, v, v)
+        }
+    }
+}
+
+/// Closest palette entry from the color cube or gray ramp
+pub fn nearest_palette(r: u8, g: u8, b: u8) -> u8 {
+    let level = |v: u8| -> usize {
+        let mut best = 0;
+        for (i, l) in CUBE_LEVELS.iter().enumerate() {
+            if (*l as i32 - v as i32).abs() < (CUBE_LEVELS[best] as i32 - v as i32).abs() {
+                best = i;
+            }
+        }
+        best
+    };
+    let dist = |a: (u8, u8, u8)| -> i32 {
+        let dr = a.0 as i32 - r as i32;
+        let dg = a.1 as i32 - g as i32;
+        let db = a.2 as i32 - b as i32;
+        dr * dr + dg * dg + db * db
+    };
+    let cube = (16 + 36 * level(r) + 6 * level(g) + level(b)) as u8;
+    let avg = ((r as u32 + g as u32 + b as u32) / 3) as i32;
+    let gray = (232 + ((avg - 8).max(0) / 10).min(23)) as u8;
+    if dist(palette_rgb(gray)) < dist(palette_rgb(cube)) {
+        gray
+    } else {
+        cube
+    }
+}
+
+/// Linear blend between two palette entries, t is clamped to 0..1
+pub fn blend(from: u8, to: u8, t: f32) -> u8 {
+    let t = t.clamp(0.0, 1.0);
+    if t <= 0.0 {
+        return from;
+    } else if t >= 1.0 {
+        return to;
+    }
+    let a = palette_rgb(from);
+    let b = palette_rgb(to);
+    let mix = |x: u8, y: u8| -> u8 { (x as f32 + (y as f32 - x as f32) * t).round() as u8 };
+    nearest_palette(mix(a.0, b.0), mix(a.1, b.1), mix(a.2, b.2))
+}
+
+/// Darkens a palette entry towards black, opacity 1.0 leaves it as is
+pub fn dim(index: u8, opacity: f32) -> u8 {
+    blend(16, index, opacity)
+}
+
+/// Rewrites every 256 color escape code in bytes with the dimmed color, everything else is copied
+pub fn dim_escapes(bytes: &[u8], opacity: f32) -> Vec<u8> {
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != 0x1b || bytes.get(i + 1) != Some(&b'[') {
+            out.push(bytes[i]);
+            i += 1;
+            continue;
+        }
+        let start = i + 2;
+        let mut end = start;
+        while end < bytes.len() && (bytes[end].is_ascii_digit() || bytes[end] == b';') {
+            end += 1;
+        }
+        if bytes.get(end) != Some(&b'm') {
+            out.extend_from_slice(&bytes[i..start]);
+            i = start;
+            continue;
+        }
+        let params: Vec<&str> = std::str::from_utf8(&bytes[start..end])
+            .unwrap_or_default()
+            .split(';')
+            .collect();
+        let mut rewritten: Vec<String> = Vec::with_capacity(params.len());
+        let mut p = 0;
+        while p < params.len() {
+            rewritten.push(params[p].to_string());
+            if (params[p] == "38" || params[p] == "48")
+                && params.get(p + 1) == Some(&"5")
+                && let Some(Ok(c)) = params.get(p + 2).map(|c| c.parse::<u8>())
+            {
+                rewritten.push("5".to_string());
+                rewritten.push(dim(c, opacity).to_string());
+                p += 2;
+            }
+            p += 1;
+        }
+        out.extend_from_slice(b"\x1b[");
+        out.extend_from_slice(rewritten.join(";").as_bytes());
+        out.push(b'm');
+        i = end + 1;
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn dim_escapes_test() {
+        assert_eq!(dim(196, 1.0), 196);
+        assert_eq!(dim(196, 0.0), 16);
+        let dimmed = dim_escapes(b"\x1b[2J\x1b[38;5;231m\x1b[48;5;16m@", 0.0);
+        assert_eq!(dimmed, b"\x1b[2J\x1b[38;5;16m\x1b[48;5;16m@");
+        let half = dim_escapes(b"\x1b[38;5;231mx", 0.5);
+        assert_ne!(half, b"\x1b[38;5;231mx");
+        assert!(half.ends_with(b"mx"));
+    }
+}
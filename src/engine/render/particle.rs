@@ -0,0 +1,309 @@
+/*
+Copyright 2025 Luke Maple
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+you may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+use super::{
+    super::{Error, ErrorKind, traits::Storeable, types::Position3D},
+    Camera, Char,
+    palette::blend,
+};
+use my_term::color::BLACK;
+use serde::{Deserialize, Serialize};
+use std::{
+    io::Write,
+    time::{Duration, Instant},
+};
+
+pub const PARTICLE_DIR: &str = "./data/particles/";
+
+fn default_bg() -> u8 {
+    BLACK
+}
+
+fn default_max() -> usize {
+    256
+}
+
+/// Named particle effect loaded from data/particles
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EmitterTemplate {
+    pub name: String,
+    /// particles spawned per second while the emitter runs
+    #[serde(default)]
+    pub rate: f32,
+    /// particles spawned as soon as the emitter starts
+    #[serde(default)]
+    pub burst: u32,
+    pub lifetime: Duration,
+    /// glyph for each part of a particle's life, the first is used when it spawns
+    pub glyphs: Vec<char>,
+    /// color stops spread evenly over a particle's life, blended in between
+    pub colors: Vec<u8>,
+    #[serde(default = "default_bg")]
+    pub bg: u8,
+    /// cells per second
+    #[serde(default)]
+    pub velocity: (f32, f32),
+    /// every particle gets a random extra velocity up to this much either way
+    #[serde(default)]
+    pub spread: (f32, f32),
+    /// added to the vertical velocity every second, positive falls down the screen
+    #[serde(default)]
+    pub gravity: f32,
+    #[serde(default = "default_max")]
+    pub max: usize,
+    /// stops spawning by itself after this long, None runs until stopped
+    #[serde(default)]
+    pub duration: Option<Duration>,
+}
+
+impl Storeable for EmitterTemplate {
+    type Key = String;
+    fn key(&self) -> Self::Key {
+        self.name.clone()
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        let invalid = |msg: String| Err(Error::new(msg, ErrorKind::InvalidData));
+        if self.name.is_empty() {
+            return invalid("emitter template has no name".to_string());
+        }
+        if self.glyphs.is_empty() || self.colors.is_empty() {
+            return invalid(format!("{}: needs at least one glyph and one color", self.name));
+        }
+        if self.lifetime.is_zero() {
+            return invalid(format!("{}: particles have no lifetime", self.name));
+        }
+        if self.rate.is_nan() || self.rate < 0.0 {
+            return invalid(format!("{}: rate can not be negative", self.name));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Particle {
+    origin: (f32, f32),
+    velocity: (f32, f32),
+    born: Instant,
+}
+
+/// Running instance of an EmitterTemplate, lives only in the render thread
+#[derive(Debug)]
+pub struct Emitter {
+    template: EmitterTemplate,
+    pos: Position3D<i32>,
+    particles: Vec<Particle>,
+    started: Option<Instant>,
+    last_step: Instant,
+    owed: f32,
+    emitting: bool,
+    seed: u64,
+    last: Vec<(i32, i32, char, u8)>,
+}
+
+impl Emitter {
+    pub fn new(template: EmitterTemplate, pos: Position3D<i32>) -> Self {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or_default();
+        let seed = nanos ^ ((pos.x as u64) << 32) ^ pos.y as u64;
+        Self {
+            template,
+            pos,
+            particles: Vec::new(),
+            started: None,
+            last_step: Instant::now(),
+            owed: 0.0,
+            emitting: true,
+            seed,
+            last: Vec::new(),
+        }
+    }
+
+    pub fn pos(&self) -> Position3D<i32> {
+        self.pos
+    }
+
+    /// New particles spawn from the new position, the ones already out keep going
+    pub fn move_pos(&mut self, delta: Position3D<i32>) {
+        self.pos += delta;
+    }
+
+    /// No new particles are spawned, the emitter is finished once the last one dies
+    pub fn stop(&mut self) {
+        self.emitting = false;
+    }
+
+    pub fn is_finished(&self) -> bool {
+        !self.emitting && self.particles.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.particles.len()
+    }
+
+    /// random value between -1 and 1
+    fn random(&mut self) -> f32 {
+        self.seed = self.seed.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.seed;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^= z >> 31;
+        (z >> 40) as f32 / (1u64 << 23) as f32 - 1.0
+    }
+
+    fn spawn(&mut self, now: Instant) {
+        if self.particles.len() >= self.template.max {
+            return;
+        }
+        let (vx, vy) = self.template.velocity;
+        let (sx, sy) = self.template.spread;
+        let velocity = (vx + sx * self.random(), vy + sy * self.random());
+        self.particles.push(Particle {
+            origin: (self.pos.x as f32, self.pos.y as f32),
+            velocity,
+            born: now,
+        });
+    }
+
+    /// Spawns and ages particles, returns true when the drawn cells changed
+    pub fn step(&mut self, now: Instant) -> bool {
+        let started = match self.started {
+            Some(s) => s,
+            None => {
+                self.started = Some(now);
+                self.last_step = now;
+                for _ in 0..self.template.burst {
+                    self.spawn(now);
+                }
+                now
+            }
+        };
+        if let Some(duration) = self.template.duration
+            && now.duration_since(started) >= duration
+        {
+            self.emitting = false;
+        }
+        let lifetime = self.template.lifetime;
+        self.particles.retain(|p| now.duration_since(p.born) < lifetime);
+        if self.emitting {
+            let dt = now.duration_since(self.last_step).as_secs_f32();
+            // a long stall should not spawn everything it missed at once
+            self.owed = (self.owed + self.template.rate * dt).min(self.template.max as f32);
+            while self.owed >= 1.0 {
+                self.owed -= 1.0;
+                self.spawn(now);
+            }
+        }
+        self.last_step = now;
+
+        let cells = self.cells(now);
+        let changed = cells != self.last;
+        self.last = cells;
+        changed
+    }
+
+    /// (x, y, glyph, color) of every live particle
+    fn cells(&self, now: Instant) -> Vec<(i32, i32, char, u8)> {
+        let t = &self.template;
+        self.particles
+            .iter()
+            .map(|p| {
+                let age = now.duration_since(p.born).as_secs_f32();
+                let life = (age / t.lifetime.as_secs_f32()).clamp(0.0, 1.0);
+                let x = p.origin.0 + p.velocity.0 * age;
+                let y = p.origin.1 + p.velocity.1 * age + 0.5 * t.gravity * age * age;
+                let stage = (life * t.glyphs.len() as f32) as usize;
+                let glyph = t.glyphs[stage.min(t.glyphs.len() - 1)];
+                (x.round() as i32, y.round() as i32, glyph, gradient(&t.colors, life))
+            })
+            .collect()
+    }
+
+    /// Draws the cells from the last step that the camera can see, returns how many were drawn
+    pub fn draw(&self, cam: &Camera, stream: &mut impl Write) -> usize {
+        if cam.z() != self.pos.z {
+            return 0;
+        }
+        let mut count = 0;
+        for (x, y, glyph, color) in self.last.iter() {
+            if *x < cam.x()
+                || *y < cam.y()
+                || *x >= cam.x() + cam.width() as i32
+                || *y >= cam.y() + cam.height() as i32
+            {
+                continue;
+            }
+            let scr = cam.get_screen_pos(Position3D::new(*x, *y, 0));
+            let c = Char::new(*glyph, *color, self.template.bg);
+            let _ = write!(stream, "\x1b[{};{}f{c}", scr.y, scr.x);
+            count += 1;
+        }
+        count
+    }
+}
+
+/// Color at life, 0 is the first stop and 1 the last
+fn gradient(stops: &[u8], life: f32) -> u8 {
+    if stops.len() == 1 {
+        return stops[0];
+    }
+    let scaled = life.clamp(0.0, 1.0) * (stops.len() - 1) as f32;
+    let i = (scaled as usize).min(stops.len() - 2);
+    blend(stops[i], stops[i + 1], scaled - i as f32)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::engine::types::Store;
+
+    fn spark() -> EmitterTemplate {
+        ron::de::from_str(
+            r#"(name: "spark", burst: 3, lifetime: (secs: 1, nanos: 0), glyphs: ['*', '.'], colors: [226, 196], velocity: (2.0, 0.0))"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn emitter_step_test() {
+        let template = spark();
+        assert!(template.validate().is_ok());
+        let mut e = Emitter::new(template, Position3D::new(5, 5, 0));
+        let start = Instant::now();
+        assert!(e.step(start));
+        assert_eq!(e.len(), 3);
+        assert_eq!(e.last[0], (5, 5, '*', 226));
+        // half way through its life the particle has moved a cell and changed glyph
+        e.step(start + Duration::from_millis(500));
+        assert_eq!(e.last[0].0, 6);
+        assert_eq!(e.last[0].2, '.');
+        e.stop();
+        e.step(start + Duration::from_secs(1));
+        assert!(e.is_finished());
+        assert_eq!(gradient(&[16, 231], 1.0), 231);
+    }
+
+    #[test]
+    fn emitter_data_dir_test() {
+        let store = Store::<EmitterTemplate>::from_dir(PARTICLE_DIR).unwrap();
+        assert!(store.len() > 0);
+        for each in store.iter() {
+            assert!(each.validate().is_ok());
+        }
+    }
+}
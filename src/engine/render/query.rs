@@ -0,0 +1,134 @@
+/*
+Copyright 2025 Luke Maple
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+you may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+use super::{
+    super::{Error, ErrorKind, enums::RenderSignal, types::Position3D},
+    FrameStats, Layer, RenderQueue, RenderUnitId,
+};
+use std::{
+    sync::{Arc, mpsc},
+    time::Duration,
+};
+
+/// Channel the render thread answers a query on, it is used once and dropped
+pub type Reply<T> = mpsc::SyncSender<T>;
+
+pub const DEFAULT_QUERY_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// Where a unit is in its layer's space and how much room it takes after layout
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Bounds {
+    pub pos: Position3D<i32>,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Bounds {
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.pos.x
+            && y >= self.pos.y
+            && x < self.pos.x + self.width as i32
+            && y < self.pos.y + self.height as i32
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CameraInfo {
+    pub pos: Position3D<i32>,
+    pub ui_pos: Position3D<i32>,
+    pub width: u32,
+    pub height: u32,
+    pub canvas_width: usize,
+    pub canvas_height: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct Hit {
+    pub id: Arc<RenderUnitId>,
+    pub layer: Layer,
+}
+
+fn ask<T>(
+    tx: &RenderQueue,
+    timeout: Duration,
+    sig: impl FnOnce(Reply<T>) -> RenderSignal,
+) -> Result<T, Error> {
+    let (reply, answer) = mpsc::sync_channel(1);
+    if let Err(e) = tx.send(sig(reply)) {
+        return Err(Error::new(
+            format!("render thread is gone: {e}"),
+            ErrorKind::ContextDead,
+        ));
+    }
+    match answer.recv_timeout(timeout) {
+        Ok(v) => Ok(v),
+        Err(mpsc::RecvTimeoutError::Timeout) => Err(Error::new(
+            "render thread did not answer in time",
+            ErrorKind::Timeout,
+        )),
+        Err(mpsc::RecvTimeoutError::Disconnected) => Err(Error::new(
+            "render thread dropped the query",
+            ErrorKind::NotFound,
+        )),
+    }
+}
+
+/// None when the unit is not in the renderer
+pub fn query_bounds(
+    tx: &RenderQueue,
+    id: &Arc<RenderUnitId>,
+    timeout: Duration,
+) -> Result<Option<Bounds>, Error> {
+    ask(tx, timeout, |r| RenderSignal::QueryBounds(id.clone(), r))
+}
+
+pub fn query_camera(tx: &RenderQueue, timeout: Duration) -> Result<CameraInfo, Error> {
+    ask(tx, timeout, RenderSignal::QueryCamera)
+}
+
+/// Topmost visible unit drawn at the screen cell, x and y start at 0
+pub fn hit_test(tx: &RenderQueue, x: u32, y: u32, timeout: Duration) -> Result<Option<Hit>, Error> {
+    ask(tx, timeout, |r| RenderSignal::HitTest(x, y, r))
+}
+
+/// Numbers from the last frame the render thread drew
+pub fn query_stats(tx: &RenderQueue, timeout: Duration) -> Result<FrameStats, Error> {
+    ask(tx, timeout, RenderSignal::QueryStats)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn query_reply_test() {
+        let (tx, rx) = mpsc::channel::<RenderSignal>();
+        let handle = std::thread::spawn(move || {
+            if let Ok(RenderSignal::QueryStats(reply)) = rx.recv() {
+                let _ = reply.send(FrameStats {
+                    drawn: 4,
+                    ..FrameStats::default()
+                });
+            }
+            // hold the channel open so the second query times out
+            let _ = rx.recv();
+        });
+        assert_eq!(query_stats(&tx, DEFAULT_QUERY_TIMEOUT).unwrap().drawn, 4);
+        assert!(query_camera(&tx, Duration::from_millis(10)).is_err());
+        drop(tx);
+        let _ = handle.join();
+    }
+}
@@ -0,0 +1,182 @@
+/*
+Copyright 2025 Luke Maple
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+you may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+use std::{
+    fs::File,
+    io::{BufWriter, Stdout, Write},
+    path::{Path, PathBuf},
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+pub const RECORDING_DIR: &str = "./recordings/";
+
+/// Writes terminal output as an asciicast v2 file, https://docs.asciinema.org/manual/asciicast/v2/
+pub struct Recorder {
+    file: BufWriter<File>,
+    path: PathBuf,
+    start: Instant,
+}
+
+impl Recorder {
+    pub fn start(path: impl AsRef<Path>, width: usize, height: usize) -> std::io::Result<Self> {
+        if let Some(dir) = path.as_ref().parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut file = BufWriter::new(File::create(path.as_ref())?);
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let term = std::env::var("TERM").unwrap_or_else(|_| "xterm-256color".to_string());
+        writeln!(
+            file,
+            "{{\"version\": 2, \"width\": {width}, \"height\": {height}, \"timestamp\": {timestamp}, \"env\": {{\"TERM\": \"{}\"}}}}",
+            escape_json(&term)
+        )?;
+        Ok(Self {
+            file,
+            path: path.as_ref().to_path_buf(),
+            start: Instant::now(),
+        })
+    }
+
+    /// Recording in the default directory named after the current time
+    pub fn start_default(width: usize, height: usize) -> std::io::Result<Self> {
+        let name = super::screenshot_name() + ".cast";
+        Self::start(Path::new(RECORDING_DIR).join(name), width, height)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn output(&mut self, data: &[u8]) -> std::io::Result<()> {
+        let t = self.start.elapsed().as_secs_f64();
+        writeln!(
+            self.file,
+            "[{t:.6}, \"o\", \"{}\"]",
+            escape_json(&String::from_utf8_lossy(data))
+        )
+    }
+
+    pub fn resize(&mut self, width: usize, height: usize) -> std::io::Result<()> {
+        let t = self.start.elapsed().as_secs_f64();
+        writeln!(self.file, "[{t:.6}, \"r\", \"{width}x{height}\"]")
+    }
+
+    pub fn finish(mut self) -> std::io::Result<PathBuf> {
+        self.file.flush()?;
+        Ok(self.path)
+    }
+}
+
+/// Terminal output of the render thread, everything written here is also recorded when a recording is running
+pub struct Output {
+    stdout: Stdout,
+    recorder: Option<Recorder>,
+}
+
+impl Output {
+    pub fn new() -> Self {
+        Self {
+            stdout: std::io::stdout(),
+            recorder: None,
+        }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recorder.is_some()
+    }
+
+    /// Starts recording, a running recording is finished first
+    pub fn record(&mut self, rec: Recorder) -> Option<std::io::Result<PathBuf>> {
+        let old = self.stop();
+        self.recorder = Some(rec);
+        old
+    }
+
+    pub fn stop(&mut self) -> Option<std::io::Result<PathBuf>> {
+        self.recorder.take().map(|r| r.finish())
+    }
+
+    /// Goes to the terminal only, for sequences that must not be saved like the clipboard
+    pub fn write_unrecorded(&mut self, buf: &[u8]) -> std::io::Result<()> {
+        self.stdout.write_all(buf)?;
+        self.stdout.flush()
+    }
+
+    pub fn resize(&mut self, width: usize, height: usize) {
+        if let Some(rec) = self.recorder.as_mut()
+            && rec.resize(width, height).is_err()
+        {
+            self.recorder = None;
+        }
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.stdout.write_all(buf)?;
+        if let Some(rec) = self.recorder.as_mut() {
+            // a broken recording should never take the screen down with it
+            if rec.output(buf).is_err() {
+                self.recorder = None;
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.stdout.flush()
+    }
+}
+
+fn escape_json(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn recorder_cast_test() {
+        let path = std::env::temp_dir().join("sshcolony_recorder_test.cast");
+        let mut rec = Recorder::start(&path, 80, 24).unwrap();
+        rec.output(b"\x1b[2J\"hi\"\r\n").unwrap();
+        rec.resize(100, 30).unwrap();
+        let path = rec.finish().unwrap();
+        let data = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = data.lines().collect();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("{\"version\": 2, \"width\": 80, \"height\": 24"));
+        assert!(lines[1].ends_with(", \"o\", \"\\u001b[2J\\\"hi\\\"\\r\\n\"]"));
+        assert!(lines[2].ends_with(", \"r\", \"100x30\"]"));
+    }
+}
@@ -16,33 +16,61 @@ limitations under the License.
 
 #![deny(unused)]
 
-use crate::engine::render::{Camera, GlyphType, ObjectData, TextType};
+use crate::engine::render::{
+    Camera, Cells, GlyphType, ObjectData, SpriteTemplate, Step, TextType, Tween,
+};
 #[allow(unused)]
 use std::fmt::Write;
-use std::io::{Stdout, Write as iowrite};
+use std::io::Write as iowrite;
 
 use super::super::ui::style::CLEAR_COLORS;
 use super::super::{
     Context,
     enums::RenderSignal,
-    input::{Event, OtherEvent},
-    types::{Position3D, SparseSet},
+    input::{Event, OSC52_QUERY, OtherEvent, osc52_set},
+    types::{Position3D, SparseSet, Store},
+};
+use super::{
+    Canvas, Interrupt, Layer, Object,
+    capture::{SCREENSHOT_DIR, Screen, screenshot_name},
+    offscreen::{RenderTarget, screen_glyph},
+    particle::{Emitter, EmitterTemplate},
+    overlay::DebugOverlay,
+    palette::dim_escapes,
+    query::{Bounds, CameraInfo, Hit},
+    recorder::{Output, Recorder},
+    render_unit::*,
+    tween::{Deferred, TweenFrame},
 };
-use super::{Canvas, Layer, Object, render_unit::*};
 use my_term::color::{BLACK, Background, Foreground, WHITE};
 use std::{
     cell::RefCell,
     rc::{Rc, Weak},
-    sync::{Arc, mpsc},
+    sync::{
+        Arc, mpsc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
 };
 
 // ##################
 // ## Type Aliases ##
 // ##################
 type Grid = SparseSet<RenderUnit>;
-type DynRefList = Vec<Weak<RefCell<Object>>>;
+type DynRefList = Vec<(std::sync::Weak<RenderUnitId>, Weak<RefCell<Object>>)>;
+type TweenList = Vec<std::sync::Weak<RenderUnitId>>;
+type TargetList = Vec<(std::sync::Weak<RenderUnitId>, RenderTarget)>;
+type EmitterList = Vec<(Arc<RenderUnitId>, Emitter)>;
 pub type RenderQueue = mpsc::Sender<RenderSignal>;
 
+// ###############
+// ## Constants ##
+// ###############
+pub const DEFAULT_MAX_FPS: u32 = 60;
+// longest the loop sleeps without a reason so shutdown is noticed quickly
+const MAX_IDLE_WAIT: Duration = Duration::from_millis(100);
+const OVERLAY_REFRESH: Duration = Duration::from_millis(500);
+
 // ############################
 // ## Main Loop For Renderer ##
 // ############################
@@ -51,12 +79,11 @@ pub fn render_thread(
     mut canvas: Canvas,
     rx: mpsc::Receiver<RenderSignal>,
     event_tx: mpsc::Sender<Event>,
+    tick_time: Arc<AtomicU64>,
     lg: Arc<logging::Logger>,
 ) {
-    #[allow(unused)]
-    let mut force_refresh: std::time::Instant = std::time::Instant::now();
-    #[allow(unused)]
-    let tick_rate: std::time::Duration = std::time::Duration::from_millis(10);
+    let mut frame_interval: Duration = frame_interval_for(DEFAULT_MAX_FPS);
+    let mut last_print: Instant = Instant::now() - frame_interval;
     let mut bg_counter: usize = 1;
     let mut mg_counter: usize = 1;
     let mut fg_counter: usize = 1;
@@ -70,20 +97,52 @@ pub fn render_thread(
     let mut foreground: Grid = SparseSet::new(1000);
     let mut ui: Grid = SparseSet::new(1000);
     let mut dynamics_list: DynRefList = Vec::new();
+    let mut tween_list: TweenList = Vec::new();
+    let mut templates: Store<SpriteTemplate> = Store::default();
+    let mut targets: TargetList = Vec::new();
+    let mut emitter_templates: Store<EmitterTemplate> = Store::default();
+    let mut emitters: EmitterList = Vec::new();
     let mut foreground_color: Foreground = Foreground::new(WHITE);
     let mut background_color: Background = Background::new(BLACK);
     let mut dirty: bool = true;
+    // last frame written to the terminal, kept for screenshots
+    let mut last_frame: Vec<u8> = Vec::new();
+    let mut output: Output = Output::new();
+    let mut overlay: DebugOverlay = DebugOverlay::new(false);
+    let mut overlay_refresh: Instant = Instant::now();
     let mut camera: Camera = Camera::new(canvas.width as u32, canvas.height as u32);
     let mut ui_camera: Camera = Camera::new(canvas.width as u32, canvas.height as u32);
 
     // Main Loop
     while ctx.is_alive() {
+        // Sleep until a signal arrives or something on screen is due to change
+        let wake = next_wake(
+            &dynamics_list,
+            &tween_list,
+            &emitters,
+            dirty,
+            last_print,
+            frame_interval,
+            &overlay,
+            overlay_refresh,
+        );
+        let first = match rx.recv_timeout(wake.saturating_duration_since(Instant::now())) {
+            Ok(msg) => Some(msg),
+            Err(mpsc::RecvTimeoutError::Timeout) => None,
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        };
         check_for_signals(
+            first,
             &mut foreground,
             &mut middleground,
             &mut background,
             &mut ui,
             &mut dynamics_list,
+            &mut tween_list,
+            &mut templates,
+            &mut targets,
+            &mut emitter_templates,
+            &mut emitters,
             &mut fg_counter,
             &mut mg_counter,
             &mut bg_counter,
@@ -100,26 +159,59 @@ pub fn render_thread(
             &mut background_color,
             &mut camera,
             &mut ui_camera,
+            &last_frame,
+            &mut output,
+            &mut overlay,
+            &mut frame_interval,
             lg.clone(),
         );
 
         // Managing Dynamic Sprites //
         clear_invalid_weak_refs(&mut dynamics_list, &mut dirty);
-        update_dynamic_objects(&mut dynamics_list, &mut dirty);
+        update_dynamic_objects(&mut dynamics_list, &event_tx, &mut dirty);
 
-        /*
-        if force_refresh.elapsed() >= tick_rate {
+        // Managing Tweens //
+        update_tweens(
+            &mut tween_list,
+            &mut foreground,
+            &mut middleground,
+            &mut background,
+            &mut ui,
+            &mut dynamics_list,
+            &canvas,
+            &mut dirty,
+        );
+
+        // Managing Particles //
+        update_emitters(&mut emitters, &mut dirty);
+
+        // the overlay numbers go stale when nothing else changes on screen
+        if overlay.is_enabled() && overlay_refresh.elapsed() >= OVERLAY_REFRESH {
             dirty = true;
-            force_refresh = std::time::Instant::now();
+            overlay_refresh = Instant::now();
         }
-        */
-        // Print State to Terminal Screen //
-        if dirty {
-            print(
+
+        // Print State to Terminal Screen, everything that changed since the last frame is drawn at once //
+        if dirty && last_print.elapsed() >= frame_interval {
+            last_print = Instant::now();
+            refresh_targets(
+                &mut targets,
+                &mut foreground,
+                &mut middleground,
+                &mut background,
+                &mut ui,
+                &canvas,
+                &camera,
+            );
+            last_frame = print(
+                &mut output,
+                &mut overlay,
+                Duration::from_nanos(tick_time.load(Ordering::Relaxed)),
                 &background,
                 &middleground,
                 &foreground,
                 &ui,
+                &emitters,
                 &canvas,
                 &foreground_color,
                 &background_color,
@@ -133,11 +225,17 @@ pub fn render_thread(
 }
 
 fn check_for_signals(
+    first: Option<RenderSignal>,
     fg: &mut Grid,
     mg: &mut Grid,
     bg: &mut Grid,
     ui: &mut Grid,
     dyn_list: &mut DynRefList,
+    tween_list: &mut TweenList,
+    templates: &mut Store<SpriteTemplate>,
+    targets: &mut TargetList,
+    emitter_templates: &mut Store<EmitterTemplate>,
+    emitters: &mut EmitterList,
     fg_counter: &mut usize,
     mg_counter: &mut usize,
     bg_counter: &mut usize,
@@ -154,12 +252,18 @@ fn check_for_signals(
     bg_color: &mut Background,
     camera: &mut Camera,
     ui_camera: &mut Camera,
+    last_frame: &[u8],
+    output: &mut Output,
+    overlay: &mut DebugOverlay,
+    frame_interval: &mut Duration,
     _lg: Arc<logging::Logger>,
 ) {
-    for (i, msg) in rx.try_iter().enumerate() {
+    let mut drained = 0;
+    for (i, msg) in first.into_iter().chain(rx.try_iter()).enumerate() {
         if i == 0 {
             *dirty = true;
         }
+        drained = i + 1;
         dispatch_msg(
             msg,
             fg,
@@ -167,6 +271,11 @@ fn check_for_signals(
             bg,
             ui,
             dyn_list,
+            tween_list,
+            templates,
+            targets,
+            emitter_templates,
+            emitters,
             fg_counter,
             mg_counter,
             bg_counter,
@@ -181,9 +290,14 @@ fn check_for_signals(
             bg_color,
             camera,
             ui_camera,
+            last_frame,
+            output,
+            overlay,
+            frame_interval,
             _lg.clone(),
         );
     }
+    overlay.drained(drained);
 }
 fn dispatch_msg(
     msg: RenderSignal,
@@ -192,6 +306,11 @@ fn dispatch_msg(
     bg: &mut Grid,
     ui: &mut Grid,
     dyn_list: &mut DynRefList,
+    tween_list: &mut TweenList,
+    templates: &mut Store<SpriteTemplate>,
+    targets: &mut TargetList,
+    emitter_templates: &mut Store<EmitterTemplate>,
+    emitters: &mut EmitterList,
     fg_counter: &mut usize,
     mg_counter: &mut usize,
     bg_counter: &mut usize,
@@ -206,36 +325,247 @@ fn dispatch_msg(
     bg_color: &mut Background,
     camera: &mut Camera,
     ui_camera: &mut Camera,
+    last_frame: &[u8],
+    output: &mut Output,
+    overlay: &mut DebugOverlay,
+    frame_interval: &mut Duration,
     _lg: Arc<logging::Logger>,
 ) {
+    overlay.count_signal();
     match msg {
         RenderSignal::Batch(mut batch) => batch_msg(
-            &mut batch, fg, mg, bg, ui, dyn_list, fg_counter, mg_counter, bg_counter, ui_counter,
-            free_fg, free_mg, free_bg, free_ui, event_tx, canvas, fg_color, bg_color, camera,
-            ui_camera, _lg,
+            &mut batch, fg, mg, bg, ui, dyn_list, tween_list, templates, targets, emitter_templates, emitters, fg_counter, mg_counter,
+            bg_counter, ui_counter, free_fg, free_mg, free_bg, free_ui, event_tx, canvas, fg_color, bg_color,
+            camera, ui_camera, last_frame, output, overlay, frame_interval, _lg,
         ),
         RenderSignal::Sequence(mut seq) => sequence_msg(
-            &mut seq, fg, mg, bg, ui, dyn_list, fg_counter, mg_counter, bg_counter, ui_counter,
-            free_fg, free_mg, free_bg, free_ui, event_tx, canvas, fg_color, bg_color, camera,
-            ui_camera, _lg,
+            &mut seq, fg, mg, bg, ui, dyn_list, tween_list, templates, targets, emitter_templates, emitters, fg_counter, mg_counter,
+            bg_counter, ui_counter, free_fg, free_mg, free_bg, free_ui, event_tx, canvas, fg_color, bg_color,
+            camera, ui_camera, last_frame, output, overlay, frame_interval, _lg,
         ),
-        RenderSignal::TermSizeChange(c, r) => term_size_change_msg(c, r, canvas, event_tx),
+        RenderSignal::TermSizeChange(c, r) => {
+            output.resize(c as usize, r as usize);
+            camera.resize(c, r, 1);
+            ui_camera.resize(c, r, 1);
+            term_size_change_msg(c, r, canvas, event_tx);
+            reflow(canvas, [&*fg, &*mg, &*bg, &*ui]);
+        }
+        RenderSignal::StartRecording(path) => {
+            let rec = match path {
+                Some(p) => Recorder::start(p, canvas.width, canvas.height),
+                None => Recorder::start_default(canvas.width, canvas.height),
+            };
+            match rec {
+                Ok(rec) => {
+                    let _ = _lg.write(
+                        logging::LogLevel::Info,
+                        format!("recording session to {}", rec.path().display()),
+                    );
+                    log_recording(output.record(rec), &_lg);
+                    // the recording has to start from a full frame
+                    output_frame(output, last_frame);
+                }
+                Err(e) => {
+                    let _ = _lg.write(
+                        logging::LogLevel::Error,
+                        format!("failed to start recording: {e}"),
+                    );
+                }
+            }
+        }
+        RenderSignal::StopRecording => log_recording(output.stop(), &_lg),
+        RenderSignal::ToggleRecording => {
+            if output.is_recording() {
+                log_recording(output.stop(), &_lg);
+            } else {
+                match Recorder::start_default(canvas.width, canvas.height) {
+                    Ok(rec) => {
+                        log_recording(output.record(rec), &_lg);
+                        output_frame(output, last_frame);
+                    }
+                    Err(e) => {
+                        let _ = _lg.write(
+                            logging::LogLevel::Error,
+                            format!("failed to start recording: {e}"),
+                        );
+                    }
+                }
+            }
+        }
         RenderSignal::Insert(id_holder, new_obj) => insert(
             id_holder, new_obj, fg_counter, mg_counter, bg_counter, ui_counter, free_fg, free_mg,
             free_bg, free_ui, fg, mg, bg, ui, dyn_list, canvas,
         ),
+        RenderSignal::InsertChild { parent, id, data } => {
+            match get_unit_mut(&parent, fg, mg, bg, ui).map(|p| p.object.borrow().pos()) {
+                Some(origin) => {
+                    insert(
+                        id.clone(),
+                        data,
+                        fg_counter,
+                        mg_counter,
+                        bg_counter,
+                        ui_counter,
+                        free_fg,
+                        free_mg,
+                        free_bg,
+                        free_ui,
+                        fg,
+                        mg,
+                        bg,
+                        ui,
+                        dyn_list,
+                        canvas,
+                    );
+                    if let Some(child) = get_unit_mut(&id, fg, mg, bg, ui) {
+                        child.object.borrow_mut().move_pos(origin);
+                    }
+                    link(&parent, &id, fg, mg, bg, ui);
+                }
+                None => {
+                    let _ = _lg.write(
+                        logging::LogLevel::Warn,
+                        format!("parent of {:?} is not in the renderer", id),
+                    );
+                }
+            }
+        }
+        RenderSignal::SetParent(id, parent) => match parent {
+            Some(parent) => {
+                if !link(&parent, &id, fg, mg, bg, ui) {
+                    let _ = _lg.write(
+                        logging::LogLevel::Warn,
+                        format!("could not attach {:?} to {:?}", id, parent),
+                    );
+                }
+            }
+            None => unlink(&id, fg, mg, bg, ui),
+        },
+        RenderSignal::LoadTemplates(dir) => match Store::<SpriteTemplate>::load_dir(&dir) {
+            Ok((store, problems)) => {
+                for e in problems {
+                    let _ = _lg.write(logging::LogLevel::Warn, format!("sprite templates in {dir}: {e}"));
+                }
+                *templates = store;
+            }
+            Err(e) => {
+                let _ = _lg.write(
+                    logging::LogLevel::Error,
+                    format!("failed to load sprite templates from {dir}: {e}"),
+                );
+            }
+        },
+        RenderSignal::InsertTemplate { id, name, pos } => match templates.get(name.clone()) {
+            Ok(Some(template)) => insert(
+                id,
+                template.to_object_data(pos),
+                fg_counter,
+                mg_counter,
+                bg_counter,
+                ui_counter,
+                free_fg,
+                free_mg,
+                free_bg,
+                free_ui,
+                fg,
+                mg,
+                bg,
+                ui,
+                dyn_list,
+                canvas,
+            ),
+            _ => {
+                let _ = _lg.write(
+                    logging::LogLevel::Warn,
+                    format!("no sprite template named {name}"),
+                );
+            }
+        },
         RenderSignal::Background(bg) => change_bg(bg, bg_color),
         RenderSignal::Foreground(fg) => change_fg(fg, fg_color),
-        RenderSignal::Remove(key) => match key.as_ref() {
-            RenderUnitId::Background(_) => remove(key, bg),
-            RenderUnitId::Middleground(_) => remove(key, mg),
-            RenderUnitId::Foreground(_) => remove(key, fg),
-            RenderUnitId::Ui(_) => remove(key, ui),
-        },
-        RenderSignal::Clear => clear_msg(fg, mg, bg, ui, dyn_list),
+        RenderSignal::Remove(key) => remove_tree(key, fg, mg, bg, ui),
+        RenderSignal::Clear => {
+            emitters.clear();
+            clear_msg(fg, mg, bg, ui, dyn_list, output)
+        }
         RenderSignal::Redraw => {} // Used to mark display as dirty
-        RenderSignal::Move(id, pos) => move_object(id, pos, fg, mg, bg, ui),
-        RenderSignal::MoveLayer(id, layer) => move_layer(id, layer, fg, mg, bg, ui),
+        // a dropped reply channel only means the asker stopped waiting
+        RenderSignal::QueryBounds(id, reply) => {
+            let _ = reply.send(get_unit(&id, fg, mg, bg, ui).map(|u| unit_bounds(u, canvas)));
+        }
+        RenderSignal::QueryCamera(reply) => {
+            let _ = reply.send(CameraInfo {
+                pos: Position3D::new(camera.x(), camera.y(), camera.z()),
+                ui_pos: Position3D::new(ui_camera.x(), ui_camera.y(), ui_camera.z()),
+                width: camera.width(),
+                height: camera.height(),
+                canvas_width: canvas.width,
+                canvas_height: canvas.height,
+            });
+        }
+        RenderSignal::HitTest(x, y, reply) => {
+            let _ = reply.send(hit_test(x, y, fg, mg, bg, ui, canvas, camera, ui_camera));
+        }
+        RenderSignal::QueryStats(reply) => {
+            let _ = reply.send(*overlay.last());
+        }
+        RenderSignal::SetMaxFps(fps) => *frame_interval = frame_interval_for(fps),
+        RenderSignal::ToggleDebugOverlay => overlay.toggle(),
+        RenderSignal::DebugOverlay(enabled) => overlay.set_enabled(enabled),
+        // kept out of the recording so the clipboard never ends up in a cast file
+        RenderSignal::SetClipboard(text) => {
+            let _ = output.write_unrecorded(osc52_set(&text).as_bytes());
+        }
+        RenderSignal::RequestClipboard => {
+            let _ = output.write_unrecorded(OSC52_QUERY.as_bytes());
+        }
+        RenderSignal::Screenshot => {
+            let screen = Screen::from_ansi(last_frame, canvas.width, canvas.height);
+            match screen.save(SCREENSHOT_DIR, &screenshot_name()) {
+                Ok(path) => {
+                    let _ = _lg.write(
+                        logging::LogLevel::Info,
+                        format!("saved screenshot {}", path.display()),
+                    );
+                }
+                Err(e) => {
+                    let _ = _lg.write(
+                        logging::LogLevel::Error,
+                        format!("failed to save screenshot: {e}"),
+                    );
+                }
+            }
+        }
+        RenderSignal::Move(id, pos) => {
+            match emitters.iter_mut().find(|(e, _)| Arc::ptr_eq(e, &id)) {
+                Some((_, emitter)) => emitter.move_pos(pos),
+                None => move_object(id, pos, fg, mg, bg, ui),
+            }
+        }
+        RenderSignal::MoveLayer(id, layer) => move_tree(
+            id, layer, fg_counter, mg_counter, bg_counter, ui_counter, free_fg, free_mg, free_bg,
+            free_ui, fg, mg, bg, ui,
+        ),
+        RenderSignal::Show(id) => {
+            if let Some(unit) = get_unit_mut(&id, fg, mg, bg, ui) {
+                unit.hidden = false;
+            }
+        }
+        RenderSignal::Hide(id) => {
+            if let Some(unit) = get_unit_mut(&id, fg, mg, bg, ui) {
+                unit.hidden = true;
+            }
+        }
+        RenderSignal::SetOpacity(id, opacity) => {
+            if let Some(unit) = get_unit_mut(&id, fg, mg, bg, ui) {
+                unit.opacity = opacity.clamp(0.0, 1.0);
+            }
+        }
+        RenderSignal::Raise(id) => {
+            if let Some(unit) = get_unit_mut(&id, fg, mg, bg, ui) {
+                unit.raised = true;
+            }
+        }
         RenderSignal::MoveCamera(pos) => camera.shift(pos.x, pos.y, pos.z),
         RenderSignal::PageUI(delta) => ui_camera.shift(0, (ui_camera.height() as i32) * delta, 0),
         RenderSignal::ScrollUI(delta) => {
@@ -253,51 +583,126 @@ fn dispatch_msg(
             ui_camera.shift(delta, 0, 0);
         }
         RenderSignal::SetCamera(pos) => camera.set_pos(pos.x, pos.y, pos.z),
-        RenderSignal::Update(id, obj) => update_object(id, obj, fg, mg, bg, ui, canvas),
+        RenderSignal::Update(id, obj) => update_object(id, obj, fg, mg, bg, ui, dyn_list, canvas),
+        RenderSignal::Tween(id, tween) => start_tween(id, tween, fg, mg, bg, ui, tween_list),
+        RenderSignal::InsertTarget { id, target, pos } => {
+            let blank = Screen::new(target.width as usize, target.height as usize);
+            insert(
+                id.clone(),
+                ObjectData::Sprite {
+                    pos,
+                    glyph: GlyphType::Single(screen_glyph(&blank)),
+                },
+                fg_counter,
+                mg_counter,
+                bg_counter,
+                ui_counter,
+                free_fg,
+                free_mg,
+                free_bg,
+                free_ui,
+                fg,
+                mg,
+                bg,
+                ui,
+                dyn_list,
+                canvas,
+            );
+            targets.push((Arc::downgrade(&id), target));
+        }
+        RenderSignal::UpdateTarget(id, target) => {
+            if let Some((_, t)) = targets.iter_mut().find(|(w, _)| w.as_ptr() == Arc::as_ptr(&id)) {
+                *t = target;
+            }
+        }
+        RenderSignal::RenderOffscreen(target, reply) => {
+            let _ = reply.send(render_target(&target, None, fg, mg, bg, ui, canvas, camera));
+        }
+        RenderSignal::LoadEmitters(dir) => match Store::<EmitterTemplate>::load_dir(&dir) {
+            Ok((store, problems)) => {
+                for e in problems {
+                    let _ = _lg.write(logging::LogLevel::Warn, format!("particle templates in {dir}: {e}"));
+                }
+                *emitter_templates = store;
+            }
+            Err(e) => {
+                let _ = _lg.write(
+                    logging::LogLevel::Error,
+                    format!("failed to load particle templates from {dir}: {e}"),
+                );
+            }
+        },
+        RenderSignal::StartEmitter { id, name, pos } => match emitter_templates.get(name.clone()) {
+            Ok(Some(template)) => {
+                // starting an id that is already running restarts it
+                emitters.retain(|(e, _)| !Arc::ptr_eq(e, &id));
+                emitters.push((id, Emitter::new(template.clone(), pos)));
+            }
+            _ => {
+                let _ = _lg.write(
+                    logging::LogLevel::Warn,
+                    format!("no particle template named {name}"),
+                );
+            }
+        },
+        RenderSignal::StopEmitter(id) => {
+            if let Some((_, emitter)) = emitters.iter_mut().find(|(e, _)| Arc::ptr_eq(e, &id)) {
+                emitter.stop();
+            }
+        }
+        RenderSignal::CancelTween(id) => {
+            if let Some(unit) = get_unit_mut(&id, fg, mg, bg, ui) {
+                unit.cancel_tweens();
+            }
+        }
+        RenderSignal::SetCells(id, cells) => set_cells(id, cells, fg, mg, bg, ui),
+        RenderSignal::PauseAnimation(id) => {
+            control_animation(id, fg, mg, bg, ui, |a| a.pause())
+        }
+        RenderSignal::ResumeAnimation(id) => {
+            control_animation(id, fg, mg, bg, ui, |a| a.resume())
+        }
+        RenderSignal::RestartAnimation(id) => {
+            control_animation(id, fg, mg, bg, ui, |a| a.restart())
+        }
     }
 }
 
 // ! TODO: Need to add camera object to the renderer so that I can make sure that all the things are where they need to be
 fn print(
+    out: &mut Output,
+    overlay: &mut DebugOverlay,
+    tick_time: Duration,
     bg: &Grid,
     mg: &Grid,
     fg: &Grid,
     ui: &Grid,
+    emitters: &EmitterList,
     can: &Canvas,
     fg_col: &Foreground,
     bg_col: &Background,
     cam: &Camera,
     ui_cam: &Camera,
     _lg: Arc<logging::Logger>,
-) {
-    let mut count = 0;
-    let mut stdout = std::io::stdout();
+) -> Vec<u8> {
+    let started = Instant::now();
+    // the whole frame is composed first so it reaches the terminal in one write
+    let mut frame: Vec<u8> = Vec::new();
     let col: String = format!("{}{}", &fg_col, &bg_col);
-    let _ = write!(stdout, "{CLEAR_COLORS}\x1b[2J");
-    let _ = stdout.flush();
-    print_layer(&mut stdout, bg, cam, can, &col, &mut count);
-    let _ = stdout.flush();
-    print_layer(&mut stdout, mg, cam, can, &col, &mut count);
-    let _ = stdout.flush();
-    print_layer(&mut stdout, fg, cam, can, &col, &mut count);
-    let _ = stdout.flush();
-    print_layer(&mut stdout, ui, ui_cam, can, &col, &mut count);
-    let _ = stdout.flush();
-    let _ = write!(
-        stdout,
-        "\x1b[{};0f|ui_cam:{},{},{},{} | cam:{},{},{},{} | Objects Rendered {}                   |",
-        can.height - 1,
-        ui_cam.x(),
-        ui_cam.y(),
-        ui_cam.width(),
-        ui_cam.height(),
-        cam.x(),
-        cam.y(),
-        cam.width(),
-        cam.height(),
-        count
-    );
-    let _ = stdout.flush();
+    let _ = write!(frame, "{CLEAR_COLORS}\x1b[2J");
+    print_layer(&mut frame, bg, cam, can, &col, overlay, fg, mg, bg, ui);
+    print_emitters(&mut frame, emitters, Layer::Background, cam, overlay);
+    print_layer(&mut frame, mg, cam, can, &col, overlay, fg, mg, bg, ui);
+    print_emitters(&mut frame, emitters, Layer::Middleground, cam, overlay);
+    print_layer(&mut frame, fg, cam, can, &col, overlay, fg, mg, bg, ui);
+    print_emitters(&mut frame, emitters, Layer::Foreground, cam, overlay);
+    print_layer(&mut frame, ui, ui_cam, can, &col, overlay, fg, mg, bg, ui);
+    print_emitters(&mut frame, emitters, Layer::Ui, ui_cam, overlay);
+    overlay.draw(&mut frame, can, tick_time);
+    let _ = out.write_all(&frame);
+    let _ = out.flush();
+    overlay.end_frame(started, frame.len());
+    frame
 }
 
 // #####################
@@ -305,20 +710,51 @@ fn print(
 // #####################
 //
 fn print_layer(
-    stream: &mut Stdout,
+    stream: &mut impl iowrite,
     g: &Grid,
     cam: &Camera,
     can: &Canvas,
     col: &str,
-    count: &mut i32,
+    overlay: &mut DebugOverlay,
+    fg: &Grid,
+    mg: &Grid,
+    bg: &Grid,
+    ui: &Grid,
+) {
+    // raised units go in a second pass so they end up on top
+    for pass in [false, true] {
+        for (k, _) in g.all_keys() {
+            let unit = g.get(*k).unwrap();
+            if is_hidden(unit, fg, mg, bg, ui) || is_raised(unit, fg, mg, bg, ui) != pass {
+                continue;
+            }
+            let opacity = effective_opacity(unit, fg, mg, bg, ui);
+            let drawn = if opacity < 1.0 {
+                // dimmed units are drawn on their own so only their colors get rewritten
+                let mut buf: Vec<u8> = Vec::new();
+                let drawn = unit.object.borrow_mut().draw(can, cam, &mut buf, col);
+                let _ = stream.write_all(&dim_escapes(&buf, opacity));
+                drawn
+            } else {
+                unit.object.borrow_mut().draw(can, cam, stream, col)
+            };
+            overlay.count_object(drawn);
+        }
+    }
+}
+
+/// Particles are drawn over the units of the layer their emitter id belongs to
+fn print_emitters(
+    stream: &mut impl iowrite,
+    emitters: &EmitterList,
+    layer: Layer,
+    cam: &Camera,
+    overlay: &mut DebugOverlay,
 ) {
-    for (k, _) in g.all_keys() {
-        g.get(*k)
-            .unwrap()
-            .object
-            .borrow_mut()
-            .draw(can, cam, stream, col);
-        *count += 1;
+    for (id, emitter) in emitters.iter() {
+        if id.layer() == layer {
+            overlay.count_object(emitter.draw(cam, stream) > 0);
+        }
     }
 }
 
@@ -332,6 +768,11 @@ fn batch_msg(
     bg: &mut Grid,
     ui: &mut Grid,
     dyn_list: &mut DynRefList,
+    tween_list: &mut TweenList,
+    templates: &mut Store<SpriteTemplate>,
+    targets: &mut TargetList,
+    emitter_templates: &mut Store<EmitterTemplate>,
+    emitters: &mut EmitterList,
     fg_counter: &mut usize,
     mg_counter: &mut usize,
     bg_counter: &mut usize,
@@ -346,6 +787,10 @@ fn batch_msg(
     bg_color: &mut Background,
     camera: &mut Camera,
     ui_camera: &mut Camera,
+    last_frame: &[u8],
+    output: &mut Output,
+    overlay: &mut DebugOverlay,
+    frame_interval: &mut Duration,
     _lg: Arc<logging::Logger>,
 ) {
     while messages.len() > 0 {
@@ -356,6 +801,11 @@ fn batch_msg(
             bg,
             ui,
             dyn_list,
+            tween_list,
+            templates,
+            targets,
+            emitter_templates,
+            emitters,
             fg_counter,
             mg_counter,
             bg_counter,
@@ -370,6 +820,10 @@ fn batch_msg(
             bg_color,
             camera,
             ui_camera,
+            last_frame,
+            output,
+            overlay,
+            frame_interval,
             _lg.clone(),
         );
     }
@@ -382,6 +836,11 @@ fn sequence_msg(
     bg: &mut Grid,
     ui: &mut Grid,
     dyn_list: &mut DynRefList,
+    tween_list: &mut TweenList,
+    templates: &mut Store<SpriteTemplate>,
+    targets: &mut TargetList,
+    emitter_templates: &mut Store<EmitterTemplate>,
+    emitters: &mut EmitterList,
     fg_counter: &mut usize,
     mg_counter: &mut usize,
     bg_counter: &mut usize,
@@ -396,6 +855,10 @@ fn sequence_msg(
     bg_color: &mut Background,
     camera: &mut Camera,
     ui_camera: &mut Camera,
+    last_frame: &[u8],
+    output: &mut Output,
+    overlay: &mut DebugOverlay,
+    frame_interval: &mut Duration,
     _lg: Arc<logging::Logger>,
 ) {
     while messages.len() > 0 {
@@ -406,6 +869,11 @@ fn sequence_msg(
             bg,
             ui,
             dyn_list,
+            tween_list,
+            templates,
+            targets,
+            emitter_templates,
+            emitters,
             fg_counter,
             mg_counter,
             bg_counter,
@@ -420,11 +888,122 @@ fn sequence_msg(
             bg_color,
             camera,
             ui_camera,
+            last_frame,
+            output,
+            overlay,
+            frame_interval,
             _lg.clone(),
         );
     }
 }
 
+fn unit_bounds(unit: &RenderUnit, canvas: &Canvas) -> Bounds {
+    let obj = unit.object.borrow();
+    Bounds {
+        pos: obj.pos(),
+        width: obj.width(canvas),
+        height: obj.height(canvas),
+    }
+}
+
+fn hit_test(
+    x: u32,
+    y: u32,
+    fg: &Grid,
+    mg: &Grid,
+    bg: &Grid,
+    ui: &Grid,
+    canvas: &Canvas,
+    camera: &Camera,
+    ui_camera: &Camera,
+) -> Option<Hit> {
+    let layers: [(&Grid, &Camera, Layer); 4] = [
+        (ui, ui_camera, Layer::Ui),
+        (fg, camera, Layer::Foreground),
+        (mg, camera, Layer::Middleground),
+        (bg, camera, Layer::Background),
+    ];
+    for (grid, cam, layer) in layers {
+        let wx = x as i32 + cam.x();
+        let wy = y as i32 + cam.y();
+        // raised units are on top, after that later inserts draw over earlier ones so the highest id wins
+        let mut best: Option<((bool, usize), &RenderUnit)> = None;
+        for (k, _) in grid.all_keys() {
+            let unit = grid.get(*k).unwrap();
+            if is_hidden(unit, fg, mg, bg, ui) || !unit_bounds(unit, canvas).contains(wx, wy) {
+                continue;
+            }
+            let rank = (is_raised(unit, fg, mg, bg, ui), *k);
+            if best.map_or(true, |(b, _)| rank > b) {
+                best = Some((rank, unit));
+            }
+        }
+        if let Some((_, unit)) = best {
+            return Some(Hit {
+                id: unit.id.clone(),
+                layer,
+            });
+        }
+    }
+    None
+}
+
+fn frame_interval_for(max_fps: u32) -> Duration {
+    Duration::from_secs(1) / max_fps.max(1)
+}
+
+/// Earliest time the loop has work to do without a new signal
+fn next_wake(
+    dyn_list: &DynRefList,
+    tween_list: &TweenList,
+    emitters: &EmitterList,
+    dirty: bool,
+    last_print: Instant,
+    frame_interval: Duration,
+    overlay: &DebugOverlay,
+    overlay_refresh: Instant,
+) -> Instant {
+    let next_frame = last_print + frame_interval;
+    if dirty || tween_list.len() > 0 || emitters.len() > 0 {
+        return next_frame;
+    }
+    let mut wake = Instant::now() + MAX_IDLE_WAIT;
+    if overlay.is_enabled() {
+        wake = wake.min(overlay_refresh + OVERLAY_REFRESH);
+    }
+    for (_, obj) in dyn_list.iter() {
+        if let Some(obj) = obj.upgrade() {
+            if let Some(due) = obj.borrow().animation().and_then(|a| a.next_due()) {
+                wake = wake.min(due);
+            }
+        }
+    }
+    wake.max(next_frame)
+}
+
+fn output_frame(output: &mut Output, frame: &[u8]) {
+    let _ = output.write_all(frame);
+    let _ = output.flush();
+}
+
+fn log_recording(finished: Option<std::io::Result<std::path::PathBuf>>, lg: &Arc<logging::Logger>) {
+    match finished {
+        None => {}
+        Some(Ok(path)) => {
+            let _ = lg.write(
+                logging::LogLevel::Info,
+                format!("saved recording {}", path.display()),
+            );
+        }
+        Some(Err(e)) => {
+            let _ = lg.write(
+                logging::LogLevel::Error,
+                format!("failed to save recording: {e}"),
+            );
+        }
+    }
+}
+
 fn change_bg(new: Background, bg: &mut Background) {
     *bg = new;
 }
@@ -444,6 +1023,15 @@ fn term_size_change_msg(cols: u32, rows: u32, canvas: &mut Canvas, event_tx: &mp
     }
 }
 
+/// Lays out every unit again for the new canvas, percent sizes and wrapped text depend on it
+fn reflow(canvas: &Canvas, grids: [&Grid; 4]) {
+    for g in grids {
+        for (k, _) in g.all_keys() {
+            g.get(*k).unwrap().object.borrow_mut().rebuild(canvas);
+        }
+    }
+}
+
 fn insert(
     id_holder: Arc<RenderUnitId>,
     data: ObjectData,
@@ -468,10 +1056,7 @@ fn insert(
                id_holder: Arc<RenderUnitId>,
                data: ObjectData,
                canvas: &Canvas| {
-        let new_unit: RenderUnit = RenderUnit {
-            id: id_holder,
-            object: Rc::new(RefCell::new(Object::from_data(data, canvas))),
-        };
+        let new_unit: RenderUnit = RenderUnit::new(id_holder, Object::from_data(data, canvas));
         new_unit.id.store(if free_ids.len() > 0 {
             free_ids.swap_remove(0)
         } else {
@@ -487,17 +1072,14 @@ fn insert(
                    id_holder: Arc<RenderUnitId>,
                    data: ObjectData,
                    canvas: &Canvas| {
-        let new_unit: RenderUnit = RenderUnit {
-            id: id_holder,
-            object: Rc::new(RefCell::new(Object::from_data(data, canvas))),
-        };
+        let new_unit: RenderUnit = RenderUnit::new(id_holder, Object::from_data(data, canvas));
         new_unit.id.store(if free_ids.len() > 0 {
             free_ids.swap_remove(0)
         } else {
             *id_counter += 1;
             *id_counter - 1
         });
-        dyn_list.push(Rc::downgrade(&new_unit.object));
+        dyn_list.push((Arc::downgrade(&new_unit.id), Rc::downgrade(&new_unit.object)));
         set.insert(new_unit.id.load(), new_unit);
     };
 
@@ -507,6 +1089,7 @@ fn insert(
                 frames: _,
                 tick_rate: _,
             } => true,
+            GlyphType::Animated { frames: _, anim: _ } => true,
             GlyphType::Single(_) => false,
         },
         ObjectData::Text {
@@ -518,8 +1101,10 @@ fn insert(
                 frames: _,
                 tick_rate: _,
             } => true,
+            TextType::Animated { frames: _, anim: _ } => true,
             TextType::Single(_) => false,
         },
+        ObjectData::TileMap { .. } => false,
     };
 
     if is_dyn {
@@ -549,14 +1134,29 @@ fn remove(key: Arc<RenderUnitId>, list: &mut Grid) {
     list.remove(key.load());
 }
 
+/// Removes the unit along with every unit below it
+fn remove_tree(key: Arc<RenderUnitId>, fg: &mut Grid, mg: &mut Grid, bg: &mut Grid, ui: &mut Grid) {
+    unlink(&key, fg, mg, bg, ui);
+    let below = subtree(&key, fg, mg, bg, ui);
+    for each in std::iter::once(key).chain(below) {
+        match each.layer() {
+            Layer::Background => remove(each, bg),
+            Layer::Middleground => remove(each, mg),
+            Layer::Foreground => remove(each, fg),
+            Layer::Ui => remove(each, ui),
+        }
+    }
+}
+
 fn clear_msg(
     fg: &mut Grid,
     mg: &mut Grid,
     bg: &mut Grid,
     ui: &mut Grid,
     dynamics_list: &mut DynRefList,
+    output: &mut Output,
 ) {
-    print!("\x1b[2J");
+    let _ = output.write_all(b"\x1b[2J");
     fg.clear();
     mg.clear();
     bg.clear();
@@ -564,20 +1164,43 @@ fn clear_msg(
     dynamics_list.clear();
 }
 
+fn update_emitters(emitters: &mut EmitterList, dirty: &mut bool) {
+    let now = Instant::now();
+    for (_, emitter) in emitters.iter_mut() {
+        if emitter.step(now) {
+            *dirty = true;
+        }
+    }
+    emitters.retain(|(_, e)| !e.is_finished());
+}
+
 fn clear_invalid_weak_refs(dynamics_list: &mut DynRefList, dirty: &mut bool) {
     let original_len = dynamics_list.len();
-    dynamics_list.retain(|x| x.upgrade().is_some());
+    dynamics_list.retain(|(_, x)| x.upgrade().is_some());
     if original_len != dynamics_list.len() {
         *dirty = true;
     }
 }
 
-fn update_dynamic_objects(dynamics_list: &mut DynRefList, dirty: &mut bool) {
-    for each in dynamics_list.iter() {
+fn update_dynamic_objects(
+    dynamics_list: &mut DynRefList,
+    event_tx: &mpsc::Sender<Event>,
+    dirty: &mut bool,
+) {
+    for (id, each) in dynamics_list.iter() {
         if let Some(arc) = each.upgrade() {
             let mut each = arc.borrow_mut();
-            if each.update() {
-                *dirty = true;
+            match each.update() {
+                Step::Idle => {}
+                Step::Frame => *dirty = true,
+                Step::Finished => {
+                    *dirty = true;
+                    if let Err(_e) =
+                        event_tx.send(Event::Other(OtherEvent::AnimationFinished(id.clone())))
+                    {
+                        // log that render could not send the animation event
+                    }
+                }
             }
         } else {
             // Log that something went wrong
@@ -593,125 +1216,601 @@ fn move_object(
     bg: &mut Grid,
     ui: &mut Grid,
 ) {
-    let (id, layer) = (id.load(), id.layer());
-    match layer {
-        Layer::Background => bg.get(id).unwrap(),
-        Layer::Middleground => mg.get(id).unwrap(),
-        Layer::Foreground => fg.get(id).unwrap(),
-        Layer::Ui => ui.get(id).unwrap(),
+    if let Some(unit) = get_unit_mut(&id, fg, mg, bg, ui) {
+        if unit.is_tweening() {
+            match unit.tweens[0].interrupt {
+                Interrupt::Queue => {
+                    unit.deferred.push(Deferred::Move(pos));
+                    return;
+                }
+                Interrupt::Cancel => unit.cancel_tweens(),
+            }
+        }
+        unit.object.borrow_mut().move_pos(pos);
+        shift_subtree(&id, pos, fg, mg, bg, ui);
+    } else {
+        // Log that there was a problem
+    }
+}
+
+/// Moves the unit along with every unit below it to the layer
+fn move_tree(
+    id: Arc<RenderUnitId>,
+    layer: Layer,
+    fg_counter: &mut usize,
+    mg_counter: &mut usize,
+    bg_counter: &mut usize,
+    ui_counter: &mut usize,
+    free_fg: &mut Vec<usize>,
+    free_mg: &mut Vec<usize>,
+    free_bg: &mut Vec<usize>,
+    free_ui: &mut Vec<usize>,
+    fg: &mut Grid,
+    mg: &mut Grid,
+    bg: &mut Grid,
+    ui: &mut Grid,
+) {
+    let below = subtree(&id, fg, mg, bg, ui);
+    for each in std::iter::once(id).chain(below) {
+        move_layer(
+            each, layer.clone(), fg_counter, mg_counter, bg_counter, ui_counter, free_fg, free_mg,
+            free_bg, free_ui, fg, mg, bg, ui,
+        );
     }
-    .object
-    .borrow_mut()
-    .move_pos(pos);
 }
 
+/// Moves the unit into the grid of the new layer, the slot is taken the same way insert takes one
 fn move_layer(
     id: Arc<RenderUnitId>,
     layer: Layer,
+    fg_counter: &mut usize,
+    mg_counter: &mut usize,
+    bg_counter: &mut usize,
+    ui_counter: &mut usize,
+    free_fg: &mut Vec<usize>,
+    free_mg: &mut Vec<usize>,
+    free_bg: &mut Vec<usize>,
+    free_ui: &mut Vec<usize>,
     fg: &mut Grid,
     mg: &mut Grid,
     bg: &mut Grid,
     ui: &mut Grid,
 ) {
-    let (id, current_layer) = (id.load(), id.layer());
-    let mut id = id;
-    let mut changed = false;
-    let val = match current_layer {
-        Layer::Background => bg.remove(id).unwrap(),
-        Layer::Middleground => mg.remove(id).unwrap(),
-        Layer::Foreground => fg.remove(id).unwrap(),
-        Layer::Ui => ui.remove(id).unwrap(),
+    if id.layer() == layer || get_unit(&id, fg, mg, bg, ui).is_none() {
+        return;
+    }
+    let unit = match id.layer() {
+        Layer::Background => bg.remove(id.load()),
+        Layer::Middleground => mg.remove(id.load()),
+        Layer::Foreground => fg.remove(id.load()),
+        Layer::Ui => ui.remove(id.load()),
     };
+    let Some(unit) = unit else {
+        return;
+    };
+    let (grid, id_counter, free_ids) = match layer {
+        Layer::Background => (bg, bg_counter, free_bg),
+        Layer::Middleground => (mg, mg_counter, free_mg),
+        Layer::Foreground => (fg, fg_counter, free_fg),
+        Layer::Ui => (ui, ui_counter, free_ui),
+    };
+    let slot = if !free_ids.is_empty() {
+        free_ids.swap_remove(0)
+    } else {
+        *id_counter += 1;
+        *id_counter - 1
+    };
+    id.set_layer(layer);
+    id.store(slot);
+    grid.insert(slot, unit);
+}
 
-    match layer {
-        Layer::Background => {
-            while bg.is_filled(id) {
-                id += 1;
-                if !changed {
-                    changed = true;
+fn update_object(
+    id: Arc<RenderUnitId>,
+    data: ObjectData,
+    fg: &mut Grid,
+    mg: &mut Grid,
+    bg: &mut Grid,
+    ui: &mut Grid,
+    dyn_list: &mut DynRefList,
+    canvas: &Canvas,
+) {
+    let origin = parent_origin(&id, fg, mg, bg, ui);
+    if let Some(unit) = get_unit_mut(&id, fg, mg, bg, ui) {
+        if unit.is_tweening() {
+            match unit.tweens[0].interrupt {
+                Interrupt::Queue => {
+                    unit.deferred.push(Deferred::Update(Box::new(data)));
+                    return;
                 }
+                Interrupt::Cancel => unit.cancel_tweens(),
             }
-            if changed {
-                val.id.store(id);
-            }
-            bg.insert(id, val);
         }
-        Layer::Middleground => {
-            while mg.is_filled(id) {
-                id += 1;
-                if !changed {
-                    changed = true;
-                }
-            }
-            if changed {
-                val.id.store(id);
-            }
-            mg.insert(id, val);
+        let delta = replace_object(unit, data, origin, dyn_list, canvas);
+        shift_subtree(&id, delta, fg, mg, bg, ui);
+    } else {
+        // Log that there was a problem
+    }
+}
+
+/// Returns how far the object moved so children can follow
+fn replace_object(
+    unit: &RenderUnit,
+    data: ObjectData,
+    origin: Position3D<i32>,
+    dyn_list: &mut DynRefList,
+    canvas: &Canvas,
+) -> Position3D<i32> {
+    let mut obj = Object::from_data(data, canvas);
+    obj.move_pos(origin);
+    let delta = obj.pos() - unit.object.borrow().pos();
+    let is_dyn = obj.is_dynamic();
+    *unit.object.borrow_mut() = obj;
+    // a static object can become animated through an update so it has to be tracked
+    let weak = Rc::downgrade(&unit.object);
+    if is_dyn && !dyn_list.iter().any(|(_, o)| o.ptr_eq(&weak)) {
+        dyn_list.push((Arc::downgrade(&unit.id), weak));
+    }
+    delta
+}
+
+fn set_cells(
+    id: Arc<RenderUnitId>,
+    cells: Vec<Cells>,
+    fg: &mut Grid,
+    mg: &mut Grid,
+    bg: &mut Grid,
+    ui: &mut Grid,
+) {
+    let unit = match id.layer() {
+        Layer::Background => bg.get(id.load()),
+        Layer::Middleground => mg.get(id.load()),
+        Layer::Foreground => fg.get(id.load()),
+        Layer::Ui => ui.get(id.load()),
+    };
+    if let Some(unit) = unit {
+        let mut obj = unit.object.borrow_mut();
+        for each in cells.iter() {
+            obj.set_cells(each);
         }
-        Layer::Foreground => {
-            while fg.is_filled(id) {
-                id += 1;
-                if !changed {
-                    changed = true;
-                }
-            }
-            if changed {
-                val.id.store(id);
-            }
-            fg.insert(id, val)
+    } else {
+        // Log that there was a problem
+    }
+}
+
+fn control_animation(
+    id: Arc<RenderUnitId>,
+    fg: &mut Grid,
+    mg: &mut Grid,
+    bg: &mut Grid,
+    ui: &mut Grid,
+    f: impl FnOnce(&mut super::Animation),
+) {
+    let unit = match id.layer() {
+        Layer::Background => bg.get(id.load()),
+        Layer::Middleground => mg.get(id.load()),
+        Layer::Foreground => fg.get(id.load()),
+        Layer::Ui => ui.get(id.load()),
+    };
+    if let Some(unit) = unit {
+        if let Some(anim) = unit.object.borrow_mut().animation_mut() {
+            f(anim);
         }
-        Layer::Ui => {
-            while ui.is_filled(id) {
-                id += 1;
-                if !changed {
-                    changed = true;
-                }
+    } else {
+        // Log that there was a problem
+    }
+}
+
+fn get_unit_mut<'a>(
+    id: &Arc<RenderUnitId>,
+    fg: &'a mut Grid,
+    mg: &'a mut Grid,
+    bg: &'a mut Grid,
+    ui: &'a mut Grid,
+) -> Option<&'a mut RenderUnit> {
+    match id.layer() {
+        Layer::Background => bg.get_mut(id.load()),
+        Layer::Middleground => mg.get_mut(id.load()),
+        Layer::Foreground => fg.get_mut(id.load()),
+        Layer::Ui => ui.get_mut(id.load()),
+    }
+}
+
+fn get_unit<'a>(
+    id: &Arc<RenderUnitId>,
+    fg: &'a Grid,
+    mg: &'a Grid,
+    bg: &'a Grid,
+    ui: &'a Grid,
+) -> Option<&'a RenderUnit> {
+    let unit = match id.layer() {
+        Layer::Background => bg.get(id.load()),
+        Layer::Middleground => mg.get(id.load()),
+        Layer::Foreground => fg.get(id.load()),
+        Layer::Ui => ui.get(id.load()),
+    }?;
+    // the slot may have been handed to another unit after a remove
+    Arc::ptr_eq(&unit.id, id).then_some(unit)
+}
+
+// ######################
+// ## Target Functions ##
+// ######################
+
+/// Draws the layers a target looks at into its own buffer, skip keeps a target from drawing itself
+fn render_target(
+    target: &RenderTarget,
+    skip: Option<&Arc<RenderUnitId>>,
+    fg: &Grid,
+    mg: &Grid,
+    bg: &Grid,
+    ui: &Grid,
+    canvas: &Canvas,
+    camera: &Camera,
+) -> Screen {
+    let cam = target.camera(camera);
+    let mut frame: Vec<u8> = Vec::new();
+    for layer in target.layers.iter() {
+        let grid = match layer {
+            Layer::Background => bg,
+            Layer::Middleground => mg,
+            Layer::Foreground => fg,
+            Layer::Ui => ui,
+        };
+        for (k, _) in grid.all_keys() {
+            let unit = grid.get(*k).unwrap();
+            if skip.is_some_and(|s| Arc::ptr_eq(s, &unit.id)) || is_hidden(unit, fg, mg, bg, ui) {
+                continue;
             }
-            if changed {
-                val.id.store(id);
-                ui.insert(id, val)
+            unit.object.borrow_mut().draw(canvas, &cam, &mut frame, "");
+        }
+    }
+    target.compose(&frame, &cam, camera)
+}
+
+/// Redraws every unit that shows a target, targets of removed units are dropped
+fn refresh_targets(
+    targets: &mut TargetList,
+    fg: &mut Grid,
+    mg: &mut Grid,
+    bg: &mut Grid,
+    ui: &mut Grid,
+    canvas: &Canvas,
+    camera: &Camera,
+) {
+    targets.retain(|(weak, _)| {
+        weak.upgrade()
+            .is_some_and(|id| get_unit(&id, fg, mg, bg, ui).is_some())
+    });
+    for (weak, target) in targets.iter() {
+        let Some(id) = weak.upgrade() else {
+            continue;
+        };
+        if get_unit(&id, fg, mg, bg, ui).is_some_and(|u| is_hidden(u, fg, mg, bg, ui)) {
+            continue;
+        }
+        let screen = render_target(target, Some(&id), fg, mg, bg, ui, canvas, camera);
+        if let Some(unit) = get_unit_mut(&id, fg, mg, bg, ui) {
+            let pos = unit.object.borrow().pos();
+            *unit.object.borrow_mut() = Object::new_sprite_static(screen_glyph(&screen), pos);
+        }
+    }
+}
+
+// #####################
+// ## Group Functions ##
+// #####################
+
+/// Every unit below id, a parent always comes before its children
+fn subtree(
+    id: &Arc<RenderUnitId>,
+    fg: &Grid,
+    mg: &Grid,
+    bg: &Grid,
+    ui: &Grid,
+) -> Vec<Arc<RenderUnitId>> {
+    let mut below = Vec::new();
+    let mut stack = vec![id.clone()];
+    while let Some(each) = stack.pop() {
+        let Some(unit) = get_unit(&each, fg, mg, bg, ui) else {
+            continue;
+        };
+        for child in unit.children.iter() {
+            if get_unit(child, fg, mg, bg, ui).is_some() {
+                below.push(child.clone());
+                stack.push(child.clone());
             }
         }
     }
+    below
 }
 
-fn update_object(
+/// Moves every unit below id by delta, id itself is left alone
+fn shift_subtree(
+    id: &Arc<RenderUnitId>,
+    delta: Position3D<i32>,
+    fg: &mut Grid,
+    mg: &mut Grid,
+    bg: &mut Grid,
+    ui: &mut Grid,
+) {
+    if delta == Position3D::new(0, 0, 0) {
+        return;
+    }
+    for child in subtree(id, fg, mg, bg, ui) {
+        if let Some(unit) = get_unit_mut(&child, fg, mg, bg, ui) {
+            unit.object.borrow_mut().move_pos(delta);
+        }
+    }
+}
+
+/// Absolute position that the data of a child is relative to, zero for units without a parent
+fn parent_origin(
+    id: &Arc<RenderUnitId>,
+    fg: &Grid,
+    mg: &Grid,
+    bg: &Grid,
+    ui: &Grid,
+) -> Position3D<i32> {
+    get_unit(id, fg, mg, bg, ui)
+        .and_then(|u| u.parent())
+        .and_then(|p| get_unit(&p, fg, mg, bg, ui))
+        .map(|p| p.object.borrow().pos())
+        .unwrap_or(Position3D::new(0, 0, 0))
+}
+
+/// A unit is hidden when it or any unit above it is hidden or blinked off
+fn is_hidden(unit: &RenderUnit, fg: &Grid, mg: &Grid, bg: &Grid, ui: &Grid) -> bool {
+    let mut current = unit;
+    loop {
+        if current.hidden || !current.visible {
+            return true;
+        }
+        match current.parent().and_then(|p| get_unit(&p, fg, mg, bg, ui)) {
+            Some(parent) => current = parent,
+            None => return false,
+        }
+    }
+}
+
+/// True when the unit or any unit above it was raised
+fn is_raised(unit: &RenderUnit, fg: &Grid, mg: &Grid, bg: &Grid, ui: &Grid) -> bool {
+    let mut current = unit;
+    loop {
+        if current.raised {
+            return true;
+        }
+        match current.parent().and_then(|p| get_unit(&p, fg, mg, bg, ui)) {
+            Some(parent) => current = parent,
+            None => return false,
+        }
+    }
+}
+
+/// Opacity of the unit multiplied by the opacity of every unit above it
+fn effective_opacity(unit: &RenderUnit, fg: &Grid, mg: &Grid, bg: &Grid, ui: &Grid) -> f32 {
+    let mut opacity = unit.opacity;
+    let mut current = unit;
+    while let Some(parent) = current.parent().and_then(|p| get_unit(&p, fg, mg, bg, ui)) {
+        opacity *= parent.opacity;
+        current = parent;
+    }
+    opacity
+}
+
+/// Makes id a child of parent, fails if either is missing or the link would make a loop
+fn link(
+    parent: &Arc<RenderUnitId>,
+    id: &Arc<RenderUnitId>,
+    fg: &mut Grid,
+    mg: &mut Grid,
+    bg: &mut Grid,
+    ui: &mut Grid,
+) -> bool {
+    if Arc::ptr_eq(parent, id)
+        || get_unit(parent, fg, mg, bg, ui).is_none()
+        || get_unit(id, fg, mg, bg, ui).is_none()
+        || subtree(id, fg, mg, bg, ui)
+            .iter()
+            .any(|c| Arc::ptr_eq(c, parent))
+    {
+        return false;
+    }
+    unlink(id, fg, mg, bg, ui);
+    if let Some(child) = get_unit_mut(id, fg, mg, bg, ui) {
+        child.parent = Some(Arc::downgrade(parent));
+    }
+    if let Some(unit) = get_unit_mut(parent, fg, mg, bg, ui) {
+        unit.children.push(id.clone());
+    }
+    true
+}
+
+/// Detaches id from its parent, the unit keeps its current position
+fn unlink(id: &Arc<RenderUnitId>, fg: &mut Grid, mg: &mut Grid, bg: &mut Grid, ui: &mut Grid) {
+    let Some(parent) = get_unit_mut(id, fg, mg, bg, ui)
+        .and_then(|u| u.parent.take())
+        .and_then(|p| p.upgrade())
+    else {
+        return;
+    };
+    if let Some(unit) = get_unit_mut(&parent, fg, mg, bg, ui) {
+        unit.children.retain(|c| !Arc::ptr_eq(c, id));
+    }
+}
+
+fn start_tween(
     id: Arc<RenderUnitId>,
-    data: ObjectData,
+    tween: Tween,
     fg: &mut Grid,
     mg: &mut Grid,
     bg: &mut Grid,
     ui: &mut Grid,
+    tween_list: &mut TweenList,
+) {
+    if let Some(unit) = get_unit_mut(&id, fg, mg, bg, ui) {
+        unit.tweens.push_back(tween);
+        if !tween_list.iter().any(|w| w.as_ptr() == Arc::as_ptr(&id)) {
+            tween_list.push(Arc::downgrade(&id));
+        }
+    } else {
+        // Log that there was a problem
+    }
+}
+
+fn update_tweens(
+    tween_list: &mut TweenList,
+    fg: &mut Grid,
+    mg: &mut Grid,
+    bg: &mut Grid,
+    ui: &mut Grid,
+    dyn_list: &mut DynRefList,
     canvas: &Canvas,
+    dirty: &mut bool,
 ) {
-    match id.layer() {
-        Layer::Background => {
-            if let Some(unit) = bg.get(id.load()) {
-                *unit.object.borrow_mut() = Object::from_data(data, canvas);
-            } else {
-                // Log that there was a problem
-            }
+    // children and deferred signals need the other units, so they are handled after the retain
+    let mut shifted: Vec<(Arc<RenderUnitId>, Position3D<i32>)> = Vec::new();
+    let mut pending: Vec<(Arc<RenderUnitId>, Vec<Deferred>)> = Vec::new();
+    tween_list.retain(|weak| {
+        let Some(id) = weak.upgrade() else {
+            return false;
+        };
+        let Some(unit) = get_unit_mut(&id, fg, mg, bg, ui) else {
+            return false;
+        };
+        // the slot may have been handed to another unit after a remove
+        if !Arc::ptr_eq(&unit.id, &id) {
+            return false;
         }
-        Layer::Middleground => {
-            if let Some(unit) = mg.get(id.load()) {
-                *unit.object.borrow_mut() = Object::from_data(data, canvas);
-            } else {
-                // Log that there was a problem
+        while let Some(tween) = unit.tweens.front_mut() {
+            if !tween.is_started() {
+                let pos = unit.object.borrow().pos();
+                tween.start(pos);
             }
-        }
-        Layer::Foreground => {
-            if let Some(unit) = fg.get(id.load()) {
-                *unit.object.borrow_mut() = Object::from_data(data, canvas);
-            } else {
-                // Log that there was a problem
+            let done = tween.is_done();
+            if let Some(frame) = tween.step() {
+                *dirty = true;
+                match frame {
+                    TweenFrame::Move(target) => {
+                        let mut obj = unit.object.borrow_mut();
+                        let current = obj.pos();
+                        obj.move_pos(target - current);
+                        shifted.push((id.clone(), target - current));
+                    }
+                    TweenFrame::Fade(c) => unit.object.borrow_mut().set_fg(Foreground::new(c)),
+                    TweenFrame::Visible(v) => unit.visible = v,
+                }
+            }
+            if !done {
+                break;
             }
+            unit.tweens.pop_front();
+            unit.visible = true;
         }
-        Layer::Ui => {
-            if let Some(unit) = ui.get(id.load()) {
-                *unit.object.borrow_mut() = Object::from_data(data, canvas);
-            } else {
-                // Log that there was a problem
+        if unit.is_tweening() {
+            return true;
+        }
+        if !unit.deferred.is_empty() {
+            *dirty = true;
+            pending.push((id.clone(), unit.deferred.drain(..).collect()));
+        }
+        false
+    });
+    for (id, delta) in shifted {
+        shift_subtree(&id, delta, fg, mg, bg, ui);
+    }
+    // signals held back by queued tweens are applied in the order they arrived
+    for (id, deferred) in pending {
+        for each in deferred {
+            match each {
+                Deferred::Move(pos) => move_object(id.clone(), pos, fg, mg, bg, ui),
+                Deferred::Update(data) => {
+                    update_object(id.clone(), *data, fg, mg, bg, ui, dyn_list, canvas)
+                }
             }
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::super::{Char, Glyph};
+    use super::*;
+
+    fn unit_at(grid: &mut Grid, slot: usize, x: i32, y: i32) -> Arc<RenderUnitId> {
+        let id = RenderUnitId::new(Layer::Foreground);
+        id.store(slot);
+        let obj = Object::new_sprite_static(
+            Glyph::small(Char::new('@', 3u8, 0u8)),
+            Position3D::new(x, y, 0),
+        );
+        grid.insert(slot, RenderUnit::new(id.clone(), obj));
+        id
+    }
+
+    #[test]
+    fn render_group_test() {
+        let (mut fg, mut mg, mut bg, mut ui): (Grid, Grid, Grid, Grid) = (
+            SparseSet::new(10),
+            SparseSet::new(10),
+            SparseSet::new(10),
+            SparseSet::new(10),
+        );
+        let parent = unit_at(&mut fg, 1, 5, 5);
+        let child = unit_at(&mut fg, 2, 6, 5);
+        let grandchild = unit_at(&mut fg, 3, 7, 5);
+        assert!(link(&parent, &child, &mut fg, &mut mg, &mut bg, &mut ui));
+        assert!(link(&child, &grandchild, &mut fg, &mut mg, &mut bg, &mut ui));
+        // a unit can not end up below itself
+        assert!(!link(&grandchild, &parent, &mut fg, &mut mg, &mut bg, &mut ui));
+        assert_eq!(subtree(&parent, &fg, &mg, &bg, &ui).len(), 2);
+
+        move_object(parent.clone(), Position3D::new(2, 1, 0), &mut fg, &mut mg, &mut bg, &mut ui);
+        let pos = |grid: &Grid, slot: usize| grid.get(slot).unwrap().object.borrow().pos();
+        assert_eq!(pos(&fg, 3), Position3D::new(9, 6, 0));
+        assert_eq!(parent_origin(&grandchild, &fg, &mg, &bg, &ui), Position3D::new(8, 6, 0));
+
+        fg.get_mut(1).unwrap().visible = false;
+        assert!(is_hidden(fg.get(3).unwrap(), &fg, &mg, &bg, &ui));
+
+        unlink(&child, &mut fg, &mut mg, &mut bg, &mut ui);
+        assert!(!is_hidden(fg.get(3).unwrap(), &fg, &mg, &bg, &ui));
+        remove_tree(child, &mut fg, &mut mg, &mut bg, &mut ui);
+        assert!(fg.get(3).is_none());
+        assert!(fg.get(1).is_some());
+    }
+
+    #[test]
+    fn group_layer_test() {
+        let (mut fg, mut mg, mut bg, mut ui): (Grid, Grid, Grid, Grid) = (
+            SparseSet::new(10),
+            SparseSet::new(10),
+            SparseSet::new(10),
+            SparseSet::new(10),
+        );
+        let (mut counters, mut free) = ([4, 1, 1, 1], [vec![], vec![], vec![], vec![]]);
+        let parent = unit_at(&mut fg, 1, 5, 5);
+        let child = unit_at(&mut fg, 2, 6, 5);
+        let grandchild = unit_at(&mut fg, 3, 7, 5);
+        assert!(link(&parent, &child, &mut fg, &mut mg, &mut bg, &mut ui));
+        assert!(link(&child, &grandchild, &mut fg, &mut mg, &mut bg, &mut ui));
+        // something already sits in the first ui slot
+        let taken = unit_at(&mut ui, 1, 0, 0);
+        taken.set_layer(Layer::Ui);
+        counters[3] = 2;
+
+        let [fg_c, mg_c, bg_c, ui_c] = &mut counters;
+        let [free_fg, free_mg, free_bg, free_ui] = &mut free;
+        move_tree(
+            parent.clone(), Layer::Ui, fg_c, mg_c, bg_c, ui_c, free_fg, free_mg, free_bg, free_ui,
+            &mut fg, &mut mg, &mut bg, &mut ui,
+        );
+        assert!(fg.all_keys().next().is_none());
+        for each in [&parent, &child, &grandchild, &taken] {
+            assert!(each.is_ui());
+            assert!(get_unit(each, &fg, &mg, &bg, &ui).is_some());
+        }
+        assert_eq!(subtree(&parent, &fg, &mg, &bg, &ui).len(), 2);
+        assert_eq!(parent_origin(&grandchild, &fg, &mg, &bg, &ui), Position3D::new(6, 5, 0));
+    }
+}
@@ -14,12 +14,19 @@ See the License for the specific language governing permissions and
 limitations under the License.
 */
 
-use super::{Layer, Object};
+use super::{
+    Layer, Object,
+    tween::{Deferred, Tween},
+};
 use std::{
     cell::RefCell,
+    collections::VecDeque,
     fmt::Display,
     rc::Rc,
-    sync::{Arc, atomic::AtomicUsize},
+    sync::{
+        Arc, Weak,
+        atomic::{AtomicU8, AtomicUsize, Ordering},
+    },
 };
 
 pub const UNINITIALIZED_RENDERUNITID_VALUE: usize = 0;
@@ -27,9 +34,37 @@ pub const UNINITIALIZED_RENDERUNITID_VALUE: usize = 0;
 pub struct RenderUnit {
     pub id: Arc<RenderUnitId>,
     pub object: Rc<RefCell<Object>>,
+    pub tweens: VecDeque<Tween>,
+    pub deferred: Vec<Deferred>,
+    /// blink state, driven by tweens
+    pub visible: bool,
+    /// set by Hide and Show, unlike visible no tween will change it
+    pub hidden: bool,
+    /// colors are darkened towards black below 1.0
+    pub opacity: f32,
+    /// drawn after the rest of its layer, set by Raise
+    pub raised: bool,
+    /// units inserted as a child follow the parent, their object still holds the absolute position
+    pub parent: Option<Weak<RenderUnitId>>,
+    pub children: Vec<Arc<RenderUnitId>>,
 }
 
 impl RenderUnit {
+    pub fn new(id: Arc<RenderUnitId>, object: Object) -> Self {
+        Self {
+            id,
+            object: Rc::new(RefCell::new(object)),
+            tweens: VecDeque::new(),
+            deferred: Vec::new(),
+            visible: true,
+            hidden: false,
+            opacity: 1.0,
+            raised: false,
+            parent: None,
+            children: Vec::new(),
+        }
+    }
+
     pub fn id(&self) -> usize {
         self.id.as_ref().load()
     }
@@ -41,104 +76,90 @@ impl RenderUnit {
     pub fn is_dynamic(&self) -> bool {
         self.object.borrow().is_dynamic()
     }
+
+    pub fn is_tweening(&self) -> bool {
+        self.tweens.len() > 0
+    }
+
+    pub fn parent(&self) -> Option<Arc<RenderUnitId>> {
+        self.parent.as_ref().and_then(|p| p.upgrade())
+    }
+
+    /// Drops every tween on the unit, whatever they were doing is left where it is
+    pub fn cancel_tweens(&mut self) {
+        self.tweens.clear();
+        self.visible = true;
+    }
 }
 
+/// Slot of a unit in the grid of its layer, both change when the unit is moved to another layer
 #[derive(Debug)]
-pub enum RenderUnitId {
-    Background(AtomicUsize),
-    Middleground(AtomicUsize),
-    Foreground(AtomicUsize),
-    Ui(AtomicUsize),
+pub struct RenderUnitId {
+    layer: AtomicU8,
+    slot: AtomicUsize,
 }
 
 impl RenderUnitId {
     pub fn new(layer: Layer) -> Arc<Self> {
-        Arc::new(match layer {
-            Layer::Background => {
-                RenderUnitId::Background(AtomicUsize::new(UNINITIALIZED_RENDERUNITID_VALUE))
-            }
-            Layer::Middleground => {
-                RenderUnitId::Middleground(AtomicUsize::new(UNINITIALIZED_RENDERUNITID_VALUE))
-            }
-            Layer::Foreground => {
-                RenderUnitId::Foreground(AtomicUsize::new(UNINITIALIZED_RENDERUNITID_VALUE))
-            }
-            Layer::Ui => RenderUnitId::Ui(AtomicUsize::new(UNINITIALIZED_RENDERUNITID_VALUE)),
-        })
+        Arc::new(Self::from_usize(UNINITIALIZED_RENDERUNITID_VALUE, layer))
     }
 
     pub fn from_usize(val: usize, layer: Layer) -> Self {
-        let val = AtomicUsize::new(val);
-        match layer {
-            Layer::Background => RenderUnitId::Background(val),
-            Layer::Middleground => RenderUnitId::Middleground(val),
-            Layer::Foreground => RenderUnitId::Foreground(val),
-            Layer::Ui => RenderUnitId::Ui(val),
-        }
+        Self::from_atomic(AtomicUsize::new(val), layer)
     }
 
     pub fn from_atomic(val: AtomicUsize, layer: Layer) -> Self {
-        match layer {
-            Layer::Background => RenderUnitId::Background(val),
-            Layer::Middleground => RenderUnitId::Middleground(val),
-            Layer::Foreground => RenderUnitId::Foreground(val),
-            Layer::Ui => RenderUnitId::Ui(val),
+        Self {
+            layer: AtomicU8::new(layer_to_u8(&layer)),
+            slot: val,
         }
     }
 
     pub fn layer(&self) -> Layer {
-        match self {
-            Self::Background(_) => Layer::Background,
-            Self::Middleground(_) => Layer::Middleground,
-            Self::Foreground(_) => Layer::Foreground,
-            Self::Ui(_) => Layer::Ui,
+        match self.layer.load(Ordering::SeqCst) {
+            0 => Layer::Background,
+            1 => Layer::Middleground,
+            2 => Layer::Foreground,
+            _ => Layer::Ui,
         }
     }
 
+    /// Only the render thread should call this, the unit has to be moved to the new grid with it
+    pub fn set_layer(&self, layer: Layer) {
+        self.layer.store(layer_to_u8(&layer), Ordering::SeqCst);
+    }
+
     pub fn is_bg(&self) -> bool {
-        match self {
-            RenderUnitId::Background(_) => true,
-            _ => false,
-        }
+        self.layer() == Layer::Background
     }
 
     pub fn is_mg(&self) -> bool {
-        match self {
-            RenderUnitId::Middleground(_) => true,
-            _ => false,
-        }
+        self.layer() == Layer::Middleground
     }
 
     pub fn is_fg(&self) -> bool {
-        match self {
-            RenderUnitId::Foreground(_) => true,
-            _ => false,
-        }
+        self.layer() == Layer::Foreground
     }
 
     pub fn is_ui(&self) -> bool {
-        match self {
-            RenderUnitId::Ui(_) => true,
-            _ => false,
-        }
+        self.layer() == Layer::Ui
     }
 
     pub fn load(&self) -> usize {
-        match self {
-            Self::Background(val) => val.load(std::sync::atomic::Ordering::SeqCst),
-            Self::Middleground(val) => val.load(std::sync::atomic::Ordering::SeqCst),
-            Self::Foreground(val) => val.load(std::sync::atomic::Ordering::SeqCst),
-            Self::Ui(val) => val.load(std::sync::atomic::Ordering::SeqCst),
-        }
+        self.slot.load(Ordering::SeqCst)
     }
 
     pub fn store(&self, val: usize) {
-        match self {
-            Self::Background(aval) => aval.store(val, std::sync::atomic::Ordering::SeqCst),
-            Self::Middleground(aval) => aval.store(val, std::sync::atomic::Ordering::SeqCst),
-            Self::Foreground(aval) => aval.store(val, std::sync::atomic::Ordering::SeqCst),
-            Self::Ui(aval) => aval.store(val, std::sync::atomic::Ordering::SeqCst),
-        }
+        self.slot.store(val, Ordering::SeqCst)
+    }
+}
+
+fn layer_to_u8(layer: &Layer) -> u8 {
+    match layer {
+        Layer::Background => 0,
+        Layer::Middleground => 1,
+        Layer::Foreground => 2,
+        Layer::Ui => 3,
     }
 }
 
@@ -4,7 +4,8 @@ use std::{fmt::Display, time};
 use my_term::color::{Background, Foreground};
 use serde::{Deserialize, Serialize};
 
-use crate::engine::render::Glyph;
+use crate::engine::render::{Char, Glyph};
+use crate::engine::render::animation::{Animation, Step};
 use crate::engine::types::Position3D;
 
 pub type Position = Position3D<i32>;
@@ -23,17 +24,21 @@ impl Sprite {
     }
 
     pub fn new_dynamic(sheet: Vec<Glyph>, pos: Position, tick_rate: Duration) -> Self {
+        let anim = Animation::looping(sheet.len(), tick_rate);
+        Self::new_animated(sheet, pos, anim)
+    }
+
+    /// The animation is fitted to the sheet, an empty sheet makes a blank static sprite
+    pub fn new_animated(sheet: Vec<Glyph>, pos: Position, anim: Animation) -> Self {
+        if sheet.is_empty() {
+            return Self::new_static(Glyph::small(Char::default()), pos);
+        }
+        let anim = anim.fit(sheet.len());
         let mut frames = Vec::new();
         for s in sheet {
             frames.push(Base { sprite: s });
         }
-        Self::Dynamic(Dynamic {
-            pos,
-            cursor: 0,
-            tick_rate,
-            last_tick: None,
-            frames,
-        })
+        Self::Dynamic(Dynamic { pos, anim, frames })
     }
 
     pub fn is_dynamic(&self) -> bool {
@@ -56,7 +61,7 @@ impl Sprite {
                 Glyph::Small(_) => 1,
                 Glyph::Block(b) => b.len(),
             },
-            Self::Dynamic(d) => match &d.frames[d.cursor].sprite {
+            Self::Dynamic(d) => match &d.frames[d.anim.cursor()].sprite {
                 Glyph::Small(_) => 1,
                 Glyph::Block(b) => b.len(),
             },
@@ -77,7 +82,7 @@ impl Sprite {
                     w
                 }
             },
-            Self::Dynamic(d) => match &d.frames[d.cursor].sprite {
+            Self::Dynamic(d) => match &d.frames[d.anim.cursor()].sprite {
                 Glyph::Small(_) => 1,
                 Glyph::Block(b) => {
                     let mut w = 0;
@@ -92,19 +97,33 @@ impl Sprite {
         }
     }
 
-    pub fn update(&mut self) -> bool {
+    pub fn update(&mut self) -> Step {
+        match self {
+            Self::Static(_) => Step::Idle,
+            Self::Dynamic(d) => d.anim.update(),
+        }
+    }
+
+    pub fn animation(&self) -> Option<&Animation> {
+        match self {
+            Self::Static(_) => None,
+            Self::Dynamic(d) => Some(&d.anim),
+        }
+    }
+
+    pub fn animation_mut(&mut self) -> Option<&mut Animation> {
+        match self {
+            Self::Static(_) => None,
+            Self::Dynamic(d) => Some(&mut d.anim),
+        }
+    }
+
+    pub fn set_fg(&mut self, fg: Foreground) {
         match self {
-            Self::Static(_) => false,
+            Self::Static(s) => s.base.sprite.set_fg(fg),
             Self::Dynamic(d) => {
-                if d.last_tick.is_some() && d.last_tick.unwrap().elapsed() > d.tick_rate {
-                    d.cursor += (d.cursor + 1) % d.frames.len();
-                    d.last_tick = Some(Instant::now());
-                    true
-                } else if d.last_tick.is_none() {
-                    d.last_tick = Some(Instant::now());
-                    false
-                } else {
-                    false
+                for f in d.frames.iter_mut() {
+                    f.sprite.set_fg(fg);
                 }
             }
         }
@@ -129,7 +148,7 @@ impl std::fmt::Display for Sprite {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Static(s) => write!(f, "{}", s.base.sprite),
-            Self::Dynamic(d) => write!(f, "{}", d.frames[d.cursor].sprite),
+            Self::Dynamic(d) => write!(f, "{}", d.frames[d.anim.cursor()].sprite),
         }
     }
 }
@@ -148,9 +167,6 @@ pub struct Static {
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Dynamic {
     pub pos: Position,
-    pub cursor: usize,
-    pub tick_rate: Duration,
-    #[serde(skip)]
-    pub last_tick: Option<std::time::Instant>,
+    pub anim: Animation,
     pub frames: Vec<Base>,
 }
@@ -0,0 +1,230 @@
+/*
+Copyright 2025 Luke Maple
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+you may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+use super::{
+    super::{
+        Error, ErrorKind,
+        traits::Storeable,
+        types::Position3D,
+    },
+    Animation, Char, Glyph, GlyphType, ObjectData, PlayMode, Text,
+};
+use my_term::color::{BLACK, WHITE};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, time::Duration};
+
+fn default_fg() -> u8 {
+    WHITE
+}
+
+fn default_bg() -> u8 {
+    BLACK
+}
+
+fn default_mode() -> PlayMode {
+    PlayMode::Loop
+}
+
+fn default_duration() -> Duration {
+    Duration::from_millis(500)
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct CellColor {
+    #[serde(default = "default_fg")]
+    pub fg: u8,
+    #[serde(default = "default_bg")]
+    pub bg: u8,
+}
+
+/// One frame of a sprite, every row must have the same number of chars.
+/// A single row with a single char becomes a Glyph::Small, anything else a Glyph::Block.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FrameTemplate {
+    pub rows: Vec<String>,
+    /// optional mask the same size as rows, each char is looked up in the template palette.
+    /// spaces and missing masks use the template colors
+    #[serde(default)]
+    pub colors: Vec<String>,
+    #[serde(default = "default_duration")]
+    pub duration: Duration,
+}
+
+/// Named sprite definition loaded from data/sprites
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SpriteTemplate {
+    pub name: String,
+    #[serde(default = "default_fg")]
+    pub fg: u8,
+    #[serde(default = "default_bg")]
+    pub bg: u8,
+    #[serde(default)]
+    pub palette: HashMap<char, CellColor>,
+    #[serde(default = "default_mode")]
+    pub mode: PlayMode,
+    pub frames: Vec<FrameTemplate>,
+}
+
+impl Storeable for SpriteTemplate {
+    type Key = String;
+    fn key(&self) -> Self::Key {
+        self.name.clone()
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        let invalid = |msg: String| Err(Error::new(msg, ErrorKind::InvalidData));
+        if self.name.is_empty() {
+            return invalid("sprite template has no name".to_string());
+        }
+        if self.frames.is_empty() {
+            return invalid(format!("{}: no frames", self.name));
+        }
+        let size = self.frames[0].size();
+        for (i, frame) in self.frames.iter().enumerate() {
+            if frame.rows.is_empty() || frame.rows[0].is_empty() {
+                return invalid(format!("{}: frame {i} is empty", self.name));
+            }
+            let width = frame.rows[0].chars().count();
+            if frame.rows.iter().any(|r| r.chars().count() != width) {
+                return invalid(format!("{}: frame {i} is not rectangular", self.name));
+            }
+            if frame.size() != size {
+                return invalid(format!(
+                    "{}: frame {i} is {:?} but the first frame is {:?}",
+                    self.name,
+                    frame.size(),
+                    size
+                ));
+            }
+            if self.frames.len() > 1 && frame.duration.is_zero() {
+                return invalid(format!("{}: frame {i} has no duration", self.name));
+            }
+            if frame.colors.is_empty() {
+                continue;
+            }
+            if frame.colors.len() != frame.rows.len()
+                || frame.colors.iter().any(|r| r.chars().count() != width)
+            {
+                return invalid(format!(
+                    "{}: frame {i} color mask does not match its rows",
+                    self.name
+                ));
+            }
+            for c in frame.colors.iter().flat_map(|r| r.chars()) {
+                if c != ' ' && !self.palette.contains_key(&c) {
+                    return invalid(format!(
+                        "{}: frame {i} uses '{c}' which is not in the palette",
+                        self.name
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FrameTemplate {
+    /// (width, height) in cells
+    pub fn size(&self) -> (usize, usize) {
+        (
+            self.rows.first().map(|r| r.chars().count()).unwrap_or(0),
+            self.rows.len(),
+        )
+    }
+}
+
+impl SpriteTemplate {
+    fn color_at(&self, frame: &FrameTemplate, row: usize, col: usize) -> (u8, u8) {
+        frame
+            .colors
+            .get(row)
+            .and_then(|r| r.chars().nth(col))
+            .and_then(|c| self.palette.get(&c))
+            .map(|c| (c.fg, c.bg))
+            .unwrap_or((self.fg, self.bg))
+    }
+
+    fn glyph(&self, frame: &FrameTemplate) -> Glyph {
+        if frame.size() == (1, 1) {
+            let (fg, bg) = self.color_at(frame, 0, 0);
+            return Glyph::small(Char::new(frame.rows[0].chars().next().unwrap(), fg, bg));
+        }
+        let mut lines = Vec::with_capacity(frame.rows.len());
+        for (y, row) in frame.rows.iter().enumerate() {
+            let mut line = Text::new();
+            for (x, c) in row.chars().enumerate() {
+                let (fg, bg) = self.color_at(frame, y, x);
+                line.push(Char::new(c, fg, bg));
+            }
+            lines.push(line);
+        }
+        Glyph::block(lines)
+    }
+
+    /** the template should be validated first, invalid blocks panic */
+    pub fn to_object_data(&self, pos: Position3D<i32>) -> ObjectData {
+        let mut frames: Vec<Glyph> = self.frames.iter().map(|f| self.glyph(f)).collect();
+        let glyph = if frames.len() == 1 {
+            GlyphType::Single(frames.swap_remove(0))
+        } else {
+            GlyphType::Animated {
+                frames,
+                anim: Animation::new(self.frames.iter().map(|f| f.duration).collect(), self.mode),
+            }
+        };
+        ObjectData::Sprite { pos, glyph }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::engine::types::Store;
+
+    fn parse(s: &str) -> SpriteTemplate {
+        ron::de::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn template_validate_test() {
+        let ok = parse(
+            r#"(name: "cart", palette: {'w': (fg: 3)}, frames: [(rows: ["[==]", "o  o"], colors: ["    ", "w  w"])])"#,
+        );
+        assert!(ok.validate().is_ok());
+        match ok.to_object_data(Position3D::new(0, 0, 0)) {
+            ObjectData::Sprite {
+                glyph: GlyphType::Single(g),
+                ..
+            } => assert!(g.is_block()),
+            _ => panic!("expected a single block glyph"),
+        }
+
+        let ragged = parse(r#"(name: "bad", frames: [(rows: ["ab", "c"])])"#);
+        assert!(ragged.validate().is_err());
+        let missing = parse(r#"(name: "bad", frames: [(rows: ["a"], colors: ["x"])])"#);
+        assert!(missing.validate().is_err());
+        let sizes = parse(r#"(name: "bad", frames: [(rows: ["a"]), (rows: ["ab"])])"#);
+        assert!(sizes.validate().is_err());
+    }
+
+    #[test]
+    fn template_data_dir_test() {
+        let store = Store::<SpriteTemplate>::from_dir("./data/sprites/").unwrap();
+        let idle = store.get("Dwarf_Idle".to_string()).unwrap();
+        assert!(idle.is_some());
+        assert!(idle.unwrap().validate().is_ok());
+    }
+}
@@ -1,8 +1,13 @@
 use crate::engine::render::drawable::Text;
 use crate::engine::{
-    render::{Canvas, Char, drawable::TextSlice, sprite::Position},
+    render::{
+        Canvas, Char,
+        animation::{Animation, Step},
+        drawable::TextSlice,
+        sprite::Position,
+    },
     types::Position3D,
-    ui::style::{Align, Alignment, Justify, Style},
+    ui::style::{Align, Alignment, Justify, Overflow, Style},
 };
 use my_term::color::{Background, Foreground};
 use serde::{Deserialize, Serialize};
@@ -48,26 +53,34 @@ impl Textbox {
     }
 
     pub fn new_dynamic(pos: Position3D<i32>, frames: Vec<Base>, tick_rate: Duration) -> Self {
-        Self::Dynamic(Dynamic {
-            pos,
-            frames,
-            cursor: 0,
-            tick_rate,
-            last_tick: None,
-        })
+        let anim = Animation::looping(frames.len(), tick_rate);
+        Self::new_animated(pos, frames, anim)
+    }
+
+    /// The animation is fitted to the frames, no frames makes one empty frame
+    pub fn new_animated(pos: Position3D<i32>, mut frames: Vec<Base>, anim: Animation) -> Self {
+        if frames.is_empty() {
+            frames.push(Base {
+                lines: Vec::new(),
+                style: Style::default(),
+                cache: None,
+            });
+        }
+        let anim = anim.fit(frames.len());
+        Self::Dynamic(Dynamic { pos, frames, anim })
     }
 
     pub fn width(&self, can: &Canvas) -> usize {
         match self {
             Self::Static(s) => s.base.width(can),
-            Self::Dynamic(d) => d.frames[d.cursor].width(can),
+            Self::Dynamic(d) => d.frames[d.anim.cursor()].width(can),
         }
     }
 
     pub fn height(&self, can: &Canvas) -> usize {
         match self {
             Self::Static(s) => s.base.height(can),
-            Self::Dynamic(d) => d.frames[d.cursor].height(can),
+            Self::Dynamic(d) => d.frames[d.anim.cursor()].height(can),
         }
     }
 
@@ -85,6 +98,17 @@ impl Textbox {
         }
     }
 
+    pub fn set_fg(&mut self, fg: Foreground) {
+        match self {
+            Self::Static(s) => s.base.set_fg(fg),
+            Self::Dynamic(d) => {
+                for f in d.frames.iter_mut() {
+                    f.set_fg(fg);
+                }
+            }
+        }
+    }
+
     pub fn is_static(&self) -> bool {
         match self {
             Self::Static(_) => true,
@@ -103,7 +127,7 @@ impl Textbox {
                 }
             }
             Self::Dynamic(d) => {
-                let lines = &mut d.frames[d.cursor].lines;
+                let lines = &mut d.frames[d.anim.cursor()].lines;
                 if row == lines.len() {
                     lines.push(Text::new());
                 }
@@ -121,8 +145,20 @@ impl Textbox {
                 s.base.build_cache(canvas);
             }
             Self::Dynamic(d) => {
-                d.frames[d.cursor].lines.push(text);
-                d.frames[d.cursor].build_cache(canvas);
+                d.frames[d.anim.cursor()].lines.push(text);
+                d.frames[d.anim.cursor()].build_cache(canvas);
+            }
+        }
+    }
+
+    /// Lays the text out again, called when the canvas changes size
+    pub fn rebuild(&mut self, canvas: &Canvas) {
+        match self {
+            Self::Static(s) => s.base.build_cache(canvas),
+            Self::Dynamic(d) => {
+                for f in d.frames.iter_mut() {
+                    f.build_cache(canvas);
+                }
             }
         }
     }
@@ -150,7 +186,7 @@ impl Textbox {
                 }
             }
             Self::Dynamic(d) => {
-                let lines = d.frames[d.cursor].cache.as_ref().unwrap();
+                let lines = d.frames[d.anim.cursor()].cache.as_ref().unwrap();
                 let bot_offset = lines.len() - bot_offset;
                 for (i, l) in lines.iter().enumerate() {
                     if i < top_offset {
@@ -174,19 +210,24 @@ impl Textbox {
         }
     }
 
-    pub fn update(&mut self) -> bool {
+    pub fn update(&mut self) -> Step {
         match self {
-            Self::Dynamic(d) => {
-                if d.last_tick.is_some() && d.last_tick.unwrap().elapsed() > d.tick_rate {
-                    d.cursor = (d.cursor + 1) % d.frames.len();
-                    d.last_tick = Some(Instant::now());
-                    return true;
-                } else if d.last_tick.is_none() {
-                    d.last_tick = Some(Instant::now());
-                }
-                return false;
-            }
-            Self::Static(_) => false,
+            Self::Dynamic(d) => d.anim.update(),
+            Self::Static(_) => Step::Idle,
+        }
+    }
+
+    pub fn animation(&self) -> Option<&Animation> {
+        match self {
+            Self::Dynamic(d) => Some(&d.anim),
+            Self::Static(_) => None,
+        }
+    }
+
+    pub fn animation_mut(&mut self) -> Option<&mut Animation> {
+        match self {
+            Self::Dynamic(d) => Some(&mut d.anim),
+            Self::Static(_) => None,
         }
     }
 }
@@ -212,7 +253,7 @@ impl std::fmt::Display for Textbox {
                 Some(cache) => print(f, cache),
                 None => std::fmt::Result::Ok(()),
             },
-            Self::Dynamic(d) => match d.frames[d.cursor].cache.as_ref() {
+            Self::Dynamic(d) => match d.frames[d.anim.cursor()].cache.as_ref() {
                 Some(cache) => print(f, cache),
                 None => std::fmt::Result::Ok(()),
             },
@@ -256,20 +297,40 @@ impl Base {
         }
     }
 
+    /// Without a set height the box is as tall as the laid out lines
     pub fn height(&self, can: &Canvas) -> usize {
         if let Some(measure) = self.style.height() {
             measure.get(can.height)
         } else {
-            let mut h = 0;
-            for line in self.lines.iter() {
-                if h < line.len() {
-                    h = line.len()
+            let mut h = self.layout(can).len();
+            if let Some(b) = self.style.border.as_ref() {
+                if b.has_top_border() {
+                    h += 1;
+                }
+                h += b.top_pad() + b.bot_pad();
+                if b.has_bot_border() {
+                    h += 1;
                 }
             }
-            if let Some(b) = self.style.border.as_ref() {
-                h + b.width()
-            } else {
-                h
+            h
+        }
+    }
+
+    /// Lines cut or wrapped to fit inside the border the way the style's overflow asks
+    pub fn layout(&self, can: &Canvas) -> Vec<Text> {
+        let max = self.max_text_len(can);
+        let overflow = self.style.overflow();
+        self.lines.iter().flat_map(|l| flow(l, max, overflow)).collect()
+    }
+
+    /// Recolors the laid out cells as well so the change shows without a cache rebuild
+    pub fn set_fg(&mut self, fg: Foreground) {
+        for l in self.lines.iter_mut() {
+            l.set_fg(fg);
+        }
+        if let Some(cache) = self.cache.as_mut() {
+            for l in cache.iter_mut() {
+                l.set_fg(fg);
             }
         }
     }
@@ -293,9 +354,9 @@ impl Base {
 
     pub fn build_cache(&mut self, can: &Canvas) {
         let mut row = 0;
+        let lines = self.layout(can);
         let mut cache: Vec<Text> = Vec::with_capacity(self.height(can));
-        let (t_align, b_align) = self.v_alignments(can);
-        let max_str_len = self.max_text_len(can);
+        let (t_align, b_align) = self.v_alignments(lines.len(), can);
         let fg = self.style.fg();
         let bg = self.style.bg();
 
@@ -347,9 +408,9 @@ impl Base {
         }
 
         // Build Lines
-        for (line_row, line) in self.lines.iter().enumerate() {
+        for line in lines.iter() {
             let mut text = Text::new();
-            let (l_align, r_align) = self.h_alignments(line_row, can);
+            let (l_align, r_align) = self.h_alignments(line.len(), can);
             if let Some(b) = self.style.border.as_ref() {
                 if b.has_left_border() {
                     text.push(Char::new(b.l(row).unwrap_or(' '), fg, bg));
@@ -361,7 +422,7 @@ impl Base {
             for _ in 0..l_align {
                 text.push(Char::new(' ', fg, bg));
             }
-            text.push_textslice(&line.slice(0, max_str_len));
+            text.push_textslice(&line.as_slice());
             for _ in 0..r_align {
                 text.push(Char::new(' ', fg, bg));
             }
@@ -443,7 +504,8 @@ impl Base {
         self.cache = Some(cache);
     }
 
-    pub fn v_alignments(&self, can: &Canvas) -> (usize, usize) {
+    /// Blank rows above and below rows lines of text
+    pub fn v_alignments(&self, rows: usize, can: &Canvas) -> (usize, usize) {
         let mut total = self.height(can);
         if let Some(b) = self.style.border.as_ref() {
             if b.has_top_border() {
@@ -454,7 +516,8 @@ impl Base {
                 total -= 1;
             }
         }
-        total -= self.lines.len();
+        // lines past the bottom are dropped when the box is drawn
+        total = total.saturating_sub(rows);
 
         match self.style.alignment.align {
             Align::Top => (0, total),
@@ -469,7 +532,8 @@ impl Base {
         }
     }
 
-    pub fn h_alignments(&self, row: usize, can: &Canvas) -> (usize, usize) {
+    /// Blank cells left and right of a line len cells long
+    pub fn h_alignments(&self, len: usize, can: &Canvas) -> (usize, usize) {
         let mut total = self.width(can);
         if let Some(b) = self.style.border.as_ref() {
             if b.has_left_border() {
@@ -480,7 +544,7 @@ impl Base {
                 total -= 1;
             }
         }
-        total -= self.lines[row].len();
+        total = total.saturating_sub(len);
         match self.style.justify() {
             Justify::Left => (0, total),
             Justify::Right => (total, 0),
@@ -509,6 +573,46 @@ impl Base {
     }
 }
 
+/// Splits or cuts a line so no piece is longer than width, there is always at least one piece
+pub fn flow(line: &Text, width: usize, overflow: Overflow) -> Vec<Text> {
+    if line.len() <= width {
+        return vec![line.clone()];
+    }
+    if width == 0 {
+        return vec![Text::new()];
+    }
+    let chars: Vec<Char> = line.into_iter().cloned().collect();
+    match overflow {
+        Overflow::Clip => vec![chars[..width].into()],
+        Overflow::Ellipsis => {
+            let mut text: Text = chars[..width - 1].into();
+            let last = chars[width - 1];
+            text.push(last.set_sym('\u{2026}'));
+            vec![text]
+        }
+        Overflow::CharWrap => chars.chunks(width).map(|c| c.into()).collect(),
+        Overflow::WordWrap => {
+            let mut out = Vec::new();
+            let mut start = 0;
+            while chars.len() - start > width {
+                let end = start + width;
+                // a space right at the edge still lets the line before it fit
+                let (cut, next) = match (start + 1..=end).rev().find(|i| chars[*i].as_char() == ' ') {
+                    Some(space) => (space, space + 1),
+                    None => (end, end),
+                };
+                out.push(chars[start..cut].into());
+                start = next;
+                while start < chars.len() && chars[start].as_char() == ' ' {
+                    start += 1;
+                }
+            }
+            out.push(chars[start..].into());
+            out
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Static {
     pub pos: Position3D<i32>,
@@ -519,8 +623,53 @@ pub struct Static {
 pub struct Dynamic {
     pub pos: Position3D<i32>,
     pub frames: Vec<Base>,
-    pub cursor: usize,
-    pub tick_rate: Duration,
-    #[serde(skip)]
-    pub last_tick: Option<Instant>,
+    pub anim: Animation,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::engine::ui::style::Measure;
+
+    #[test]
+    fn flow_test() {
+        let line = Text::from("the goblin flees", 7u8, 0u8);
+        let pieces = |o: Overflow, w: usize| -> Vec<String> { flow(&line, w, o).iter().map(|t| t.to_string()).collect() };
+        assert_eq!(pieces(Overflow::WordWrap, 10), vec!["the goblin", "flees"]);
+        assert_eq!(pieces(Overflow::WordWrap, 4), vec!["the", "gobl", "in", "flee", "s"]);
+        assert_eq!(pieces(Overflow::CharWrap, 6), vec!["the go", "blin f", "lees"]);
+        assert_eq!(pieces(Overflow::Ellipsis, 8), vec!["the gob\u{2026}"]);
+        assert_eq!(pieces(Overflow::Clip, 8), vec!["the gobl"]);
+
+        // the box grows to fit the wrapped lines and reflows when the canvas shrinks
+        let style = Style::default().set_width(Measure::Percent(50)).set_overflow(Overflow::WordWrap);
+        let mut base = Base::new(vec![line.clone()], style, &Canvas::new(40, 10));
+        assert_eq!(base.height(&Canvas::new(40, 10)), 1);
+        let small = Canvas::new(20, 10);
+        base.build_cache(&small);
+        let cache: Vec<String> = base.cache.as_ref().unwrap().iter().map(|t| t.to_string()).collect();
+        assert_eq!(cache, vec!["the goblin", "flees     "]);
+    }
+
+    #[test]
+    fn animated_frame_count_test() {
+        use super::super::{Glyph, sprite::Sprite};
+        let can = Canvas::new(20, 10);
+        let frame = Base::new(vec![Text::from("ab", 7u8, 0u8)], Style::default(), &can);
+        // five durations for two frames
+        let anim = Animation::looping(5, std::time::Duration::from_millis(1));
+        match Textbox::new_animated(Position3D::new(0, 0, 0), vec![frame.clone(), frame], anim.clone()) {
+            Textbox::Dynamic(d) => assert_eq!(d.anim.len(), d.frames.len()),
+            Textbox::Static(_) => panic!("expected a dynamic textbox"),
+        }
+        let empty = Textbox::new_animated(Position3D::new(0, 0, 0), vec![], anim.clone());
+        assert_eq!(empty.width(&can), 0);
+
+        let glyph = Glyph::small(Char::new('@', 7u8, 0u8));
+        match Sprite::new_animated(vec![glyph], Position3D::new(0, 0, 0), anim.clone()) {
+            Sprite::Dynamic(d) => assert_eq!(d.anim.len(), 1),
+            Sprite::Static(_) => panic!("expected a dynamic sprite"),
+        }
+        assert!(Sprite::new_animated(vec![], Position3D::new(0, 0, 0), anim).is_static());
+    }
 }
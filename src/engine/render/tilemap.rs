@@ -0,0 +1,269 @@
+/*
+Copyright 2025 Luke Maple
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+you may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+use std::fmt::Write;
+use std::io::Write as iowrite;
+
+use serde::{Deserialize, Serialize};
+
+use super::{Camera, Char};
+use crate::engine::types::Position3D;
+
+pub const DEFAULT_CHUNK_SIZE: usize = 16;
+
+/// A rectangular block of cells sent to a TileMap in one signal.
+/// `origin` is in map local coordinates and `cells` is row major with `width` columns.
+#[derive(Clone, Debug)]
+pub struct Cells {
+    pub origin: Position3D<usize>,
+    pub width: usize,
+    pub cells: Vec<Char>,
+}
+
+impl Cells {
+    pub fn new(origin: Position3D<usize>, width: usize, cells: Vec<Char>) -> Self {
+        Self {
+            origin,
+            width,
+            cells,
+        }
+    }
+
+    pub fn single(pos: Position3D<usize>, c: Char) -> Self {
+        Self {
+            origin: pos,
+            width: 1,
+            cells: vec![c],
+        }
+    }
+
+    pub fn height(&self) -> usize {
+        if self.width == 0 {
+            0
+        } else {
+            self.cells.len().div_ceil(self.width)
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Chunk {
+    cells: Vec<Option<Char>>,
+}
+
+/// Chunked 2D cell grid for every z level of a map.
+/// Chunks are only allocated once a cell inside of them is set, and only the
+/// part of the current z level that is inside the camera is drawn.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TileMap {
+    pos: Position3D<i32>,
+    width: usize,
+    height: usize,
+    depth: usize,
+    chunk_size: usize,
+    chunks_x: usize,
+    chunks_y: usize,
+    levels: Vec<Vec<Option<Chunk>>>,
+}
+
+impl TileMap {
+    pub fn new(pos: Position3D<i32>, size: Position3D<usize>, chunk_size: usize) -> Self {
+        let chunk_size = if chunk_size == 0 {
+            DEFAULT_CHUNK_SIZE
+        } else {
+            chunk_size
+        };
+        let chunks_x = size.x.div_ceil(chunk_size);
+        let chunks_y = size.y.div_ceil(chunk_size);
+        let mut levels = Vec::with_capacity(size.z);
+        for _ in 0..size.z {
+            levels.push(vec![None; chunks_x * chunks_y]);
+        }
+        Self {
+            pos,
+            width: size.x,
+            height: size.y,
+            depth: size.z,
+            chunk_size,
+            chunks_x,
+            chunks_y,
+            levels,
+        }
+    }
+
+    pub fn pos(&self) -> Position3D<i32> {
+        self.pos
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn move_pos(&mut self, pos: Position3D<i32>) {
+        self.pos += pos;
+    }
+
+    pub fn get(&self, x: usize, y: usize, z: usize) -> Option<Char> {
+        if x >= self.width || y >= self.height || z >= self.depth {
+            return None;
+        }
+        let (chunk, index) = self.chunk_index(x, y);
+        self.levels[z][chunk].as_ref()?.cells[index]
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, z: usize, c: Option<Char>) {
+        if x >= self.width || y >= self.height || z >= self.depth {
+            return;
+        }
+        let (chunk, index) = self.chunk_index(x, y);
+        let size = self.chunk_size * self.chunk_size;
+        let slot = &mut self.levels[z][chunk];
+        if slot.is_none() {
+            if c.is_none() {
+                return;
+            }
+            *slot = Some(Chunk {
+                cells: vec![None; size],
+            });
+        }
+        slot.as_mut().unwrap().cells[index] = c;
+    }
+
+    /// Applies a block of cells, anything outside of the map is ignored.
+    /// Returns true if any cell was written.
+    pub fn set_cells(&mut self, cells: &Cells) -> bool {
+        if cells.width == 0 {
+            return false;
+        }
+        let mut changed = false;
+        for (i, c) in cells.cells.iter().enumerate() {
+            let x = cells.origin.x + (i % cells.width);
+            let y = cells.origin.y + (i / cells.width);
+            if x < self.width && y < self.height && cells.origin.z < self.depth {
+                self.set(x, y, cells.origin.z, Some(*c));
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    pub fn clear_level(&mut self, z: usize) {
+        if let Some(level) = self.levels.get_mut(z) {
+            level.fill(None);
+        }
+    }
+
+    pub fn in_view(&self, cam: &Camera) -> bool {
+        let z = cam.z() - self.pos.z;
+        if z < 0 || z as usize >= self.depth {
+            return false;
+        }
+        self.pos.x < cam.x() + cam.width() as i32
+            && self.pos.x + (self.width as i32) > cam.x()
+            && self.pos.y < cam.y() + cam.height() as i32
+            && self.pos.y + (self.height as i32) > cam.y()
+    }
+
+    /// Draws the visible part of the camera's z level, returns the number of cells written.
+    pub fn draw(&self, cam: &Camera, stream: &mut impl std::io::Write) -> usize {
+        if !self.in_view(cam) {
+            return 0;
+        }
+        let z = (cam.z() - self.pos.z) as usize;
+        let left = cam.x().max(self.pos.x);
+        let right = (cam.x() + cam.width() as i32).min(self.pos.x + self.width as i32);
+        let top = cam.y().max(self.pos.y);
+        let bot = (cam.y() + cam.height() as i32).min(self.pos.y + self.height as i32);
+
+        let mut count = 0;
+        let mut out = String::new();
+        for wy in top..bot {
+            let my = (wy - self.pos.y) as usize;
+            let mut prev: Option<Char> = None;
+            for wx in left..right {
+                let mx = (wx - self.pos.x) as usize;
+                match self.get(mx, my, z) {
+                    None => prev = None,
+                    Some(c) => {
+                        match prev {
+                            None => {
+                                let scr = cam.get_screen_pos(Position3D::new(wx, wy, 0));
+                                let _ = write!(out, "\x1b[{};{}f{c}", scr.y, scr.x);
+                            }
+                            Some(p) if !p.same_style(&c) => {
+                                let _ = write!(out, "{c}");
+                            }
+                            Some(_) => out.push(c.as_char()),
+                        }
+                        prev = Some(c);
+                        count += 1;
+                    }
+                }
+            }
+        }
+        out.push_str("\x1b[0m");
+        let _ = write!(stream, "{out}");
+        count
+    }
+
+    fn chunk_index(&self, x: usize, y: usize) -> (usize, usize) {
+        let chunk = (y / self.chunk_size) * self.chunks_x + (x / self.chunk_size);
+        let index = (y % self.chunk_size) * self.chunk_size + (x % self.chunk_size);
+        (chunk, index)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn tilemap_lazy_chunks_test() {
+        let mut map = TileMap::new(Position3D::new(0, 0, 0), Position3D::new(200, 200, 50), 16);
+        assert!(map.get(10, 10, 3).is_none());
+        map.set(10, 10, 3, Some(Char::new('#', 7, 0)));
+        assert_eq!(map.get(10, 10, 3).unwrap().as_char(), '#');
+        let allocated: usize = map
+            .levels
+            .iter()
+            .map(|l| l.iter().filter(|c| c.is_some()).count())
+            .sum();
+        assert_eq!(allocated, 1);
+    }
+
+    #[test]
+    fn tilemap_set_cells_test() {
+        let mut map = TileMap::new(Position3D::new(0, 0, 0), Position3D::new(20, 20, 2), 8);
+        let cells = Cells::new(
+            Position3D::new(6, 6, 1),
+            3,
+            vec![Char::new('.', 7, 0); 6],
+        );
+        assert!(map.set_cells(&cells));
+        assert_eq!(cells.height(), 2);
+        assert_eq!(map.get(8, 7, 1).unwrap().as_char(), '.');
+        assert!(map.get(9, 7, 1).is_none());
+        assert!(map.get(8, 7, 0).is_none());
+    }
+}
@@ -0,0 +1,240 @@
+/*
+Copyright 2025 Luke Maple
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+you may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+use std::time::{Duration, Instant};
+
+use crate::engine::types::Position3D;
+
+use super::ObjectData;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Easing {
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+}
+
+impl Easing {
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t,
+            Easing::EaseOut => t * (2.0 - t),
+            Easing::EaseInOut => {
+                if t < 0.5 {
+                    2.0 * t * t
+                } else {
+                    -1.0 + (4.0 - 2.0 * t) * t
+                }
+            }
+        }
+    }
+}
+
+/// What happens to a running tween when a Move or Update signal arrives for the same unit
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Interrupt {
+    /// stop the tween where it is and apply the signal right away
+    Cancel,
+    /// hold the signal until every queued tween on the unit is done
+    Queue,
+}
+
+#[derive(Clone, Debug)]
+pub enum TweenKind {
+    /// moves to an absolute position, starting from `from` or wherever the unit is when the tween starts
+    Move {
+        from: Option<Position3D<i32>>,
+        to: Position3D<i32>,
+    },
+    /// blends the foreground of every cell between two palette colors
+    Fade { from: u8, to: u8 },
+    /// toggles visibility every interval, always ends visible
+    Blink { interval: Duration },
+}
+
+#[derive(Clone, Debug)]
+pub struct Tween {
+    pub kind: TweenKind,
+    pub duration: Duration,
+    pub easing: Easing,
+    pub interrupt: Interrupt,
+    started: Option<Instant>,
+    origin: Option<Position3D<i32>>,
+    last: Option<TweenFrame>,
+}
+
+/// Signal held back by a tween with Interrupt::Queue
+#[derive(Debug)]
+pub enum Deferred {
+    Move(Position3D<i32>),
+    Update(Box<ObjectData>),
+}
+
+/// Change the render thread needs to apply to the unit for the current frame
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TweenFrame {
+    Move(Position3D<i32>),
+    Fade(u8),
+    Visible(bool),
+}
+
+impl Tween {
+    pub fn new(kind: TweenKind, duration: Duration) -> Self {
+        Self {
+            kind,
+            duration,
+            easing: Easing::Linear,
+            interrupt: Interrupt::Cancel,
+            started: None,
+            origin: None,
+            last: None,
+        }
+    }
+
+    pub fn move_to(to: Position3D<i32>, duration: Duration) -> Self {
+        Self::new(TweenKind::Move { from: None, to }, duration)
+    }
+
+    pub fn move_between(from: Position3D<i32>, to: Position3D<i32>, duration: Duration) -> Self {
+        Self::new(
+            TweenKind::Move {
+                from: Some(from),
+                to,
+            },
+            duration,
+        )
+    }
+
+    pub fn fade(from: u8, to: u8, duration: Duration) -> Self {
+        Self::new(TweenKind::Fade { from, to }, duration)
+    }
+
+    pub fn blink(interval: Duration, duration: Duration) -> Self {
+        Self::new(TweenKind::Blink { interval }, duration)
+    }
+
+    pub fn set_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    pub fn set_interrupt(mut self, interrupt: Interrupt) -> Self {
+        self.interrupt = interrupt;
+        self
+    }
+
+    pub fn is_started(&self) -> bool {
+        self.started.is_some()
+    }
+
+    pub fn start(&mut self, current: Position3D<i32>) {
+        self.started = Some(Instant::now());
+        self.origin = match &self.kind {
+            TweenKind::Move { from, .. } => Some(from.unwrap_or(current)),
+            _ => None,
+        };
+    }
+
+    pub fn progress(&self) -> f32 {
+        match self.started {
+            None => 0.0,
+            Some(s) => {
+                if self.duration.is_zero() {
+                    1.0
+                } else {
+                    s.elapsed().as_secs_f32() / self.duration.as_secs_f32()
+                }
+            }
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.progress() >= 1.0
+    }
+
+    /// Same as frame but only returns the state when it differs from the last one returned
+    pub fn step(&mut self) -> Option<TweenFrame> {
+        let frame = self.frame();
+        if self.last == Some(frame) {
+            return None;
+        }
+        self.last = Some(frame);
+        Some(frame)
+    }
+
+    /// State of the tween at the current time
+    pub fn frame(&self) -> TweenFrame {
+        let t = self.easing.apply(self.progress());
+        match &self.kind {
+            TweenKind::Move { to, .. } => {
+                let from = self.origin.unwrap_or(*to);
+                let lerp = |a: i32, b: i32| -> i32 { a + ((b - a) as f32 * t).round() as i32 };
+                TweenFrame::Move(Position3D::new(
+                    lerp(from.x, to.x),
+                    lerp(from.y, to.y),
+                    lerp(from.z, to.z),
+                ))
+            }
+            TweenKind::Fade { from, to } => TweenFrame::Fade(super::palette::blend(*from, *to, t)),
+            TweenKind::Blink { interval } => {
+                if self.is_done() || interval.is_zero() {
+                    return TweenFrame::Visible(true);
+                }
+                let elapsed = self.started.map(|s| s.elapsed()).unwrap_or_default();
+                let phase = elapsed.as_millis() / interval.as_millis().max(1);
+                TweenFrame::Visible(phase.is_multiple_of(2))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn easing_bounds_test() {
+        for e in [Easing::Linear, Easing::EaseIn, Easing::EaseOut, Easing::EaseInOut] {
+            assert_eq!(e.apply(0.0), 0.0);
+            assert_eq!(e.apply(1.0), 1.0);
+            assert_eq!(e.apply(2.0), 1.0);
+        }
+        assert!(Easing::EaseIn.apply(0.5) < 0.5);
+        assert!(Easing::EaseOut.apply(0.5) > 0.5);
+    }
+
+    #[test]
+    fn tween_move_test() {
+        let mut t = Tween::move_to(Position3D::new(10, 4, 0), Duration::ZERO);
+        t.start(Position3D::new(0, 0, 0));
+        assert!(t.is_done());
+        assert_eq!(t.frame(), TweenFrame::Move(Position3D::new(10, 4, 0)));
+        assert_eq!(t.step(), Some(TweenFrame::Move(Position3D::new(10, 4, 0))));
+        assert_eq!(t.step(), None);
+    }
+
+    #[test]
+    fn tween_fade_test() {
+        let mut t = Tween::fade(16, 231, Duration::ZERO);
+        t.start(Position3D::new(0, 0, 0));
+        assert_eq!(t.frame(), TweenFrame::Fade(231));
+        assert_eq!(super::super::palette::blend(16, 231, 0.0), 16);
+        assert_eq!(super::super::palette::blend(196, 196, 0.5), 196);
+    }
+}
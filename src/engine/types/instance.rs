@@ -14,26 +14,33 @@ See the License for the specific language governing permissions and
 limitations under the License.
 */
 
-use crate::engine::input::Event;
+use crate::engine::enums::RenderSignal;
+use crate::engine::input::{Event, KeyEvent, OtherEvent};
 use crate::engine::types::Network;
 
 use super::super::core::traits::Scene;
 use super::super::{
     Context,
     consts::DEFAULT_CANVAS,
-    render::{Canvas, RenderQueue},
+    render::{Canvas, DEFAULT_MAX_FPS, RenderQueue},
+    ui::Dialog,
 };
 use logging::Logger;
 use logging::Options as Opts;
 use my_term::{Terminal, term_size};
 use rand_core::OsRng;
+use std::collections::VecDeque;
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, mpsc};
+use std::sync::{Arc, atomic::AtomicU64, mpsc};
 use x25519_dalek::{EphemeralSecret, PublicKey, SharedSecret};
 
 pub struct InstanceConfig {
     log_path: String,
     log_level: logging::LogLevel,
+    /// Some when the session should be recorded from the start, the inner value is the cast file
+    record: Option<Option<String>>,
+    debug_overlay: bool,
+    max_fps: Option<u32>,
 }
 
 impl InstanceConfig {
@@ -41,8 +48,38 @@ impl InstanceConfig {
         Self {
             log_path: path.into(),
             log_level: level,
+            record: None,
+            debug_overlay: false,
+            max_fps: None,
         }
     }
+
+    pub fn set_record(mut self, path: Option<String>) -> Self {
+        self.record = Some(path);
+        self
+    }
+
+    pub fn record(&self) -> Option<Option<String>> {
+        self.record.clone()
+    }
+
+    pub fn set_debug_overlay(mut self, enabled: bool) -> Self {
+        self.debug_overlay = enabled;
+        self
+    }
+
+    pub fn debug_overlay(&self) -> bool {
+        self.debug_overlay
+    }
+
+    pub fn set_max_fps(mut self, fps: u32) -> Self {
+        self.max_fps = Some(fps);
+        self
+    }
+
+    pub fn max_fps(&self) -> Option<u32> {
+        self.max_fps
+    }
 }
 
 pub struct Instance {
@@ -54,6 +91,16 @@ pub struct Instance {
     pub render_queue: RenderQueue,
     pub event_recvier: mpsc::Receiver<Event>,
     pub tick_rate: u16,
+    /// how long the last main loop iteration took in nanoseconds, read by the debug overlay
+    pub tick_time: Arc<AtomicU64>,
+    /// whether the debug overlay is showing, changed by F10 and the settings screen
+    pub debug_overlay: bool,
+    /// last frame rate cap a scene asked the render thread for
+    pub max_fps: u32,
+    /// alerts the main loop shows one at a time over the current scene
+    pub alerts: VecDeque<Dialog>,
+    /// events handed back with unread, they come first on the next call to events
+    unread: Vec<Event>,
 }
 
 impl Instance {
@@ -83,6 +130,41 @@ impl Instance {
             render_queue: queue,
             event_recvier: event_rx,
             tick_rate: 0,
+            tick_time: Arc::new(AtomicU64::new(0)),
+            debug_overlay: config.debug_overlay,
+            max_fps: config.max_fps.unwrap_or(DEFAULT_MAX_FPS),
+            alerts: VecDeque::new(),
+            unread: Vec::new(),
+        }
+    }
+
+    /// Drains the pending events, a screen size change updates the canvas and F10 toggles the
+    /// debug overlay before they are handed on
+    pub fn events(&mut self) -> Vec<Event> {
+        let mut events = std::mem::take(&mut self.unread);
+        let start = events.len();
+        events.extend(self.event_recvier.try_iter());
+        for e in events[start..].iter() {
+            match e {
+                Event::Other(OtherEvent::ScreenSizeChange { width, height }) => {
+                    self.canvas.width = *width as usize;
+                    self.canvas.height = *height as usize;
+                }
+                Event::Keyboard(KeyEvent::F(10)) => {
+                    self.debug_overlay = !self.debug_overlay;
+                    let _ = self
+                        .render_queue
+                        .send(RenderSignal::DebugOverlay(self.debug_overlay));
+                }
+                _ => {}
+            }
         }
+        events
+    }
+
+    /// Puts events back in front of the queue, the canvas and overlay are not changed again
+    pub fn unread(&mut self, mut events: Vec<Event>) {
+        events.append(&mut self.unread);
+        self.unread = events;
     }
 }
@@ -95,7 +95,11 @@ impl<T> SparseSet<T> {
         let index = *data_index; // 1
         let rm = self.dense.swap_remove(index); // rm = { e: 'C', k: 0 } 
         // self.dense[index] = {e: 'F', k: 3
-        self.sparse[self.dense_keys[index]] = index;
+        // the keys have to be swapped the same way or the moved entry points past the end
+        self.dense_keys.swap_remove(index);
+        if index < self.dense_keys.len() {
+            self.sparse[self.dense_keys[index]] = index;
+        }
         self.sparse[key] = usize::MAX;
         self.keys.remove(&key);
         Some(rm)
@@ -14,7 +14,7 @@ See the License for the specific language governing permissions and
 limitations under the License.
 */
 
-use crate::engine::Error;
+use crate::engine::{Error, ErrorKind};
 
 use super::super::traits::Storeable;
 use super::super::super::engine;
@@ -47,59 +47,57 @@ where T::Key: Eq + std::hash::Hash {
     ////////////////////////
     //  Public Functions  //
     ////////////////////////
+    /// Loads every file in dir, anything that can not be loaded is skipped
     pub fn from_dir(dir: &str) -> Result<Self, std::io::Error> {
-        let dir = read_dir(dir);
-        if let Err(dir) = dir {
-            // Log that there is a problem
-            print!("\rFailed to read dir: {}\r\n", dir);
-            return Err(dir);
-        }
-        let mut dir = dir.unwrap();
+        Self::load_dir(dir).map(|(store, _)| store)
+    }
+
+    /// Loads every file in dir along with why each skipped file or entry was left out.
+    /// Nothing is printed, the caller decides where the problems are logged
+    pub fn load_dir(dir: &str) -> Result<(Self, Vec<Error>), std::io::Error> {
+        let mut dir = read_dir(dir)?;
         let mut store = Store::<T>::default();
+        let mut problems = Vec::new();
         while let Some(Ok(entry)) = dir.next() {
-            #[cfg(debug_assertions)]
-            println!("\rprocessing file {}", entry.path().to_str().unwrap());
             if let Ok(t) = entry.file_type() && t.is_file() {
                 let pathbuf = entry.path();
-                let path = pathbuf.to_str();
-                let namebuf = entry.file_name();
-                let name = namebuf.to_str();
-                if path.is_none() || name.is_none() {
-                    // Log that there is a problem
+                let Some(path) = pathbuf.to_str() else {
+                    problems.push(Error::new(
+                        format!("{} is not a valid utf-8 path", pathbuf.display()),
+                        ErrorKind::InvalidData,
+                    ));
                     continue;
-                }
+                };
 
-                let file = std::fs::File::open(path.unwrap().to_string());
-                if file.is_err() {
-                    // Log that there is a problem
-                    #[cfg(debug_assertions)]
-                    println!("\rFailed to open file: {} | {}",path.unwrap().to_string()+name.unwrap(), file.unwrap_err());
-                    continue;
-                }
-                let mut m: Option<Vec<T>> = None;
-                match ron::de::from_reader(file.as_ref().unwrap()) {
-                    Ok(r) => m = Some(r),
+                let file = match std::fs::File::open(path) {
+                    Ok(f) => f,
                     Err(e) => {
-                        // log that there is a problem
-                        println!("\ron::de::from_reader failed for {:?} \n\r{}", file.unwrap(), e);
+                        let kind = e.kind();
+                        problems.push(Error::from(e, format!("failed to open {path}"), ErrorKind::Io(kind)));
+                        continue;
                     }
-                }
-                if m.is_none(){
-                    // log that there is a problem
-                    continue;
-                }
-                let max = m.as_ref().unwrap().len();
-                for _ in 0..max {
-                    store.matrix.push(m.as_mut().unwrap().swap_remove(0));
+                };
+                let m: Vec<T> = match ron::de::from_reader(&file) {
+                    Ok(r) => r,
+                    Err(e) => {
+                        problems.push(Error::new(format!("failed to parse {path}: {e}"), ErrorKind::InvalidData));
+                        continue;
+                    }
+                };
+                for val in m {
+                    if let Err(e) = val.validate() {
+                        problems.push(Error::new(format!("skipping invalid entry in {path}: {e}"), ErrorKind::InvalidData));
+                        continue;
+                    }
+                    store.matrix.push(val);
                 }
             }
         }
         store.rebuild();
         if store.matrix.len() == 0 {
-            // log that there is a problem
-            println!("\rStore Matrix is empty");
+            problems.push(Error::new("store matrix is empty", ErrorKind::NotFound));
         }
-        return Ok(store);
+        return Ok((store, problems));
     }
 
     pub fn from_file(path: &str) -> Option<Self> {
@@ -115,11 +113,17 @@ where T::Key: Eq + std::hash::Hash {
     }
 
     pub fn get(&self, k: T::Key) -> Result<Option<&T>, engine::Error> {
-        Ok(None)
+        if self.need_rebuild() {
+            return Err(engine::Error::new("store index is out of date", engine::ErrorKind::RebuildRequired));
+        }
+        Ok(self.index_map.get(&k).and_then(|i| self.matrix.get(*i)))
     }
 
     pub fn get_mut(&mut self, k: T::Key) -> Result<Option<&mut T>, engine::Error> {
-        Ok(None)
+        if self.need_rebuild() {
+            return Err(engine::Error::new("store index is out of date", engine::ErrorKind::RebuildRequired));
+        }
+        Ok(self.index_map.get(&k).and_then(|i| self.matrix.get_mut(*i)))
     }
 
     pub fn from_vec(v: Vec<T>) -> Self {
@@ -131,6 +135,14 @@ where T::Key: Eq + std::hash::Hash {
         return s;
     }
 
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.matrix.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.matrix.len()
+    }
+
     pub fn rebuild(&mut self) {
         self.index_map.clear();
         for (index, each) in self.matrix.iter().enumerate() {
@@ -16,15 +16,21 @@ limitations under the License.
 
 use crate::engine::ui::style::{Coloring, Style};
 use crate::engine::{enums::RenderSignal, types::Position};
+use crate::engine::input::{Event, KeyEvent};
+use std::sync::mpsc::SendError;
 use std::sync::{Arc, Weak, atomic::AtomicUsize, mpsc::Sender};
 
 use super::{
-    Border, BorderSprite,
+    Border, BorderSprite, Rect, Widget,
+    core::text_bounds,
     style::{Align, Justify, Measure},
 };
-use crate::engine::render::{Layer, Object, ObjectData, RenderUnitId, Text, TextType, Textbox};
+use crate::engine::render::{
+    Canvas, Layer, Object, ObjectData, RenderUnitId, Text, TextType, Textbox,
+};
 use my_term::color::{Background, Foreground};
 
+#[derive(Debug)]
 pub struct Button<I, O> {
     render_id: Weak<RenderUnitId>,
     text: Vec<Text>,
@@ -33,6 +39,8 @@ pub struct Button<I, O> {
     select_color: Coloring,
     selected: bool,
     action: fn(I) -> O,
+    /// handed to the action when the button is pressed through Widget
+    input: Option<I>,
 }
 
 impl<I, O> Button<I, O> {
@@ -51,29 +59,44 @@ impl<I, O> Button<I, O> {
             select_color,
             selected: false,
             action: action,
+            input: None,
         }
     }
 
-    pub fn output(&mut self, render_tx: &Sender<RenderSignal>) {
+    /// Payload the action gets when the button is pressed through Widget, the default of I without one
+    pub fn set_input(mut self, input: I) -> Self {
+        self.input = Some(input);
+        self
+    }
+
+    pub fn output(&mut self, render_tx: &Sender<RenderSignal>) -> Result<(), SendError<RenderSignal>> {
         let (fg, bg) = self.current_colors();
+        let mut text = self.text.clone();
+        for line in text.iter_mut() {
+            line.set_fg(fg.clone());
+            line.set_bg(bg.clone());
+        }
+        let style = self.style.clone().set_fg(fg).set_bg(bg);
+        let data = ObjectData::Text {
+            pos: self.pos.clone().into(),
+            data: TextType::Single(text),
+            style,
+        };
         match self.render_id.upgrade() {
-            Some(arc_id) => {
-                render_tx.send(RenderSignal::Update(
-                    arc_id,
-                    ObjectData::Text { pos: self.pos.clone().into(), data: TextType::Single(self.text.clone()), style:self.style.clone() }
-                ));
-            }
+            Some(arc_id) => render_tx.send(RenderSignal::Update(arc_id, data)),
             None => {
                 let arc_id = RenderUnitId::new(Layer::Ui);
                 self.render_id = Arc::downgrade(&arc_id);
-                render_tx.send(RenderSignal::Insert(
-                    arc_id,
-                    ObjectData::Text { pos: self.pos.clone().into(), data: TextType::Single(self.text.clone()), style: self.style.clone() },
-                ));
+                render_tx.send(RenderSignal::Insert(arc_id, data))
             }
         }
     }
 
+    /// Moves the button, output has to be called for the change to show
+    pub fn set_pos(&mut self, pos: Position<i32>) {
+        self.pos = pos;
+    }
+
     fn current_colors(&self) -> (Foreground, Background) {
         if self.selected {
             (self.select_color.foreground.clone(), self.select_color.background.clone())
@@ -82,12 +105,68 @@ impl<I, O> Button<I, O> {
         }
     }
 
-    pub fn toggle_select(&mut self, render_tx: &Sender<RenderSignal>) {
+    pub fn toggle_select(
+        &mut self,
+        render_tx: &Sender<RenderSignal>,
+    ) -> Result<(), SendError<RenderSignal>> {
         self.selected = !self.selected;
-        self.output(render_tx);
+        self.output(render_tx)
     }
 
     pub fn execute(&self, input: I) -> O {
         (self.action)(input)
     }
 }
+
+/// Enter or space runs the action with the button's input, the select color shows while it has focus
+impl<I: Clone + Default, O> Widget<O> for Button<I, O> {
+    fn handle_event(
+        &mut self,
+        event: &Event,
+        _render_tx: &Sender<RenderSignal>,
+        _canvas: &Canvas,
+    ) -> Option<O> {
+        match event {
+            Event::Keyboard(KeyEvent::Enter) | Event::Keyboard(KeyEvent::Char(' ')) => {
+                Some(self.execute(self.input.clone().unwrap_or_default()))
+            }
+            _ => None,
+        }
+    }
+
+    fn output(
+        &mut self,
+        render_tx: &Sender<RenderSignal>,
+        _canvas: &Canvas,
+    ) -> Result<(), SendError<RenderSignal>> {
+        Button::output(self, render_tx)
+    }
+
+    fn set_focus(&mut self, focus: bool) {
+        self.selected = focus;
+    }
+
+    fn is_focused(&self) -> bool {
+        self.selected
+    }
+
+    fn bounds(&self, canvas: &Canvas) -> Rect {
+        text_bounds(self.pos.clone(), &self.style, &self.text, canvas)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn button_input_test() {
+        let (tx, _rx) = std::sync::mpsc::channel();
+        let canvas = Canvas::new(40, 10);
+        let color = Coloring::new(0u8, 7u8);
+        let double = |n: usize| n * 2;
+        let mut b = Button::new(Position::new(0, 0), vec![], Style::default(), color, double).set_input(21);
+        let enter = Event::Keyboard(KeyEvent::Enter);
+        assert_eq!(Widget::<usize>::handle_event(&mut b, &enter, &tx, &canvas), Some(42));
+    }
+}
@@ -0,0 +1,206 @@
+/*
+Copyright 2025 Luke Maple
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+you may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+use super::super::enums::RenderSignal;
+use super::{
+    Rect, Widget,
+    core::{left_click, text_bounds},
+    style::{Coloring, DISABLED_FG, Style},
+};
+use crate::engine::{
+    input::{Event, KeyEvent},
+    render::{Canvas, Char, Layer, ObjectData, RenderUnitId, Text, TextType},
+    types::Position,
+};
+use std::sync::mpsc::SendError;
+use std::sync::{Arc, Weak, mpsc::Sender};
+
+/// Labeled box that is either checked or not, drawn as `[x] label`
+#[derive(Debug)]
+pub struct CheckBox {
+    pub render_id: Weak<RenderUnitId>,
+    pos: Position<i32>,
+    style: Style,
+    label: Text,
+    hover_color: Coloring,
+    disabled_color: Coloring,
+    checked: bool,
+    focused: bool,
+    disabled: bool,
+}
+
+impl CheckBox {
+    pub fn new(x: i32, y: i32, style: Style, hover_color: Coloring, label: Text) -> Self {
+        Self {
+            render_id: Weak::new(),
+            pos: Position { x, y },
+            disabled_color: Coloring::new(DISABLED_FG, *style.bg()),
+            style,
+            label,
+            hover_color,
+            checked: false,
+            focused: false,
+            disabled: false,
+        }
+    }
+
+    pub fn set_disabled_color(mut self, color: Coloring) -> Self {
+        self.disabled_color = color;
+        self
+    }
+
+    pub fn is_checked(&self) -> bool {
+        self.checked
+    }
+
+    /// Output has to be called for the change to show
+    pub fn set_checked(&mut self, checked: bool) {
+        self.checked = checked;
+    }
+
+    /// Does nothing while disabled
+    pub fn toggle(&mut self) {
+        if !self.disabled {
+            self.checked = !self.checked;
+        }
+    }
+
+    pub fn is_disabled(&self) -> bool {
+        self.disabled
+    }
+
+    /// A disabled checkbox is grayed out and skipped by Tab, output has to be called for the change to show
+    pub fn set_disabled(&mut self, disabled: bool) {
+        self.disabled = disabled;
+        if disabled {
+            self.focused = false;
+        }
+    }
+
+    /// Moves the checkbox, output has to be called for the change to show
+    pub fn set_pos(&mut self, pos: Position<i32>) {
+        self.pos = pos;
+    }
+
+    fn line(&self) -> Text {
+        let (fg, bg) = (self.style.fg(), self.style.bg());
+        let mark = if self.checked { 'x' } else { ' ' };
+        let mut line = Text::new();
+        for c in ['[', mark, ']', ' '] {
+            line.push(Char::new(c, fg, bg));
+        }
+        line.join(self.label.clone());
+        let coloring = if self.disabled {
+            Some(&self.disabled_color)
+        } else if self.focused {
+            Some(&self.hover_color)
+        } else {
+            None
+        };
+        if let Some(c) = coloring {
+            line.set_fg(c.foreground);
+            line.set_bg(c.background);
+        }
+        line
+    }
+
+    pub fn output(&mut self, render_tx: &Sender<RenderSignal>) -> Result<(), SendError<RenderSignal>> {
+        let data = ObjectData::Text {
+            pos: self.pos.into(),
+            data: TextType::Single(vec![self.line()]),
+            style: self.style.clone(),
+        };
+        match self.render_id.upgrade() {
+            None => {
+                let arc_id = RenderUnitId::new(Layer::Ui);
+                self.render_id = Arc::downgrade(&arc_id);
+                render_tx.send(RenderSignal::Insert(arc_id, data))
+            }
+            Some(arc) => render_tx.send(RenderSignal::Update(arc, data)),
+        }
+    }
+}
+
+/// Enter, space or a click toggles the box.
+/// Nothing is handed back, the scene reads the state with is_checked
+impl<A> Widget<A> for CheckBox {
+    fn handle_event(
+        &mut self,
+        event: &Event,
+        render_tx: &Sender<RenderSignal>,
+        canvas: &Canvas,
+    ) -> Option<A> {
+        let toggled = match event {
+            Event::Keyboard(KeyEvent::Enter) | Event::Keyboard(KeyEvent::Char(' ')) => true,
+            event => left_click(event).is_some_and(|(x, y)| Widget::<A>::bounds(self, canvas).contains(x, y)),
+        };
+        if toggled && !self.disabled {
+            self.toggle();
+            let _ = self.output(render_tx);
+        }
+        None
+    }
+
+    fn output(
+        &mut self,
+        render_tx: &Sender<RenderSignal>,
+        _canvas: &Canvas,
+    ) -> Result<(), SendError<RenderSignal>> {
+        CheckBox::output(self, render_tx)
+    }
+
+    fn focusable(&self) -> bool {
+        !self.disabled
+    }
+
+    fn set_focus(&mut self, focus: bool) {
+        self.focused = focus;
+    }
+
+    fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    fn bounds(&self, canvas: &Canvas) -> Rect {
+        text_bounds(self.pos, &self.style, &[self.line()], canvas)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn checkbox_toggle_test() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let canvas = Canvas::new(40, 10);
+        let mut cb = CheckBox::new(2, 1, Style::default(), Coloring::default(), Text::from("Hard core", 7u8, 0u8));
+        assert_eq!(cb.line().to_string(), "[ ] Hard core");
+        assert_eq!(Widget::<()>::bounds(&cb, &canvas), Rect::new(2, 1, 13, 1));
+
+        let space = Event::Keyboard(KeyEvent::Char(' '));
+        assert!(Widget::<()>::handle_event(&mut cb, &space, &tx, &canvas).is_none());
+        assert!(cb.is_checked());
+        assert_eq!(cb.line().to_string(), "[x] Hard core");
+        assert!(matches!(rx.try_recv(), Ok(RenderSignal::Insert(..))));
+
+        cb.set_disabled(true);
+        assert!(!Widget::<()>::focusable(&cb));
+        Widget::<()>::handle_event(&mut cb, &space, &tx, &canvas);
+        assert!(cb.is_checked());
+        assert!(rx.try_recv().is_err());
+    }
+}
@@ -14,11 +14,189 @@ See the License for the specific language governing permissions and
 limitations under the License.
 */
 
-use super::style::Measure;
+use super::{
+    Rect,
+    style::{Measure, Style},
+};
+use crate::engine::{
+    enums::RenderSignal,
+    input::{Event, KeyEvent, MouseButton, MouseEvent, OtherEvent},
+    render::{Canvas, Text},
+    types::Position,
+};
+use std::sync::mpsc::{SendError, Sender};
 
-pub trait UIElement<R> {
-    fn update(&mut self) -> Option<R>;
-    fn output(&self) -> String;
+/// Common interface of every ui widget, A is what the widget hands back to the scene
+pub trait Widget<A> {
+    /// Reacts to an event sent to the widget, Some when the scene has something to do
+    fn handle_event(
+        &mut self,
+        event: &Event,
+        render_tx: &Sender<RenderSignal>,
+        canvas: &Canvas,
+    ) -> Option<A>;
+
+    fn output(
+        &mut self,
+        render_tx: &Sender<RenderSignal>,
+        canvas: &Canvas,
+    ) -> Result<(), SendError<RenderSignal>>;
+
+    /// Widgets that only show something are skipped by Tab
+    fn focusable(&self) -> bool {
+        true
+    }
+
+    /// Only changes how the widget looks, output has to be called for it to show
+    fn set_focus(&mut self, focus: bool);
+
+    fn is_focused(&self) -> bool;
+
+    /// Screen cells the widget covers
+    fn bounds(&self, canvas: &Canvas) -> Rect;
+}
+
+/// Cells covered by lines of text drawn with the style, the same way a Textbox sizes itself
+pub(crate) fn text_bounds(pos: Position<i32>, style: &Style, lines: &[Text], canvas: &Canvas) -> Rect {
+    let border = style.border.as_ref();
+    let width = match style.width() {
+        Some(w) => w.get(canvas.width),
+        None => {
+            lines.iter().map(|l| l.len()).max().unwrap_or(0) + border.map_or(0, |b| b.width())
+        }
+    };
+    let height = match style.height() {
+        Some(h) => h.get(canvas.height),
+        None => lines.len() + border.map_or(0, |b| b.height()),
+    };
+    Rect::new(pos.x, pos.y, width, height)
+}
+
+/// Screen cell of a left mouse press
+pub(crate) fn left_click(event: &Event) -> Option<(i32, i32)> {
+    match event {
+        Event::Mouse(MouseEvent::Pressed(data)) if data.button() == MouseButton::Left => {
+            Some((data.x() as i32, data.y() as i32))
+        }
+        _ => None,
+    }
+}
+
+/// Decides which widget of a scene gets key events.
+/// The scene keeps its widgets and hands them over as a slice in the same order every time
+#[derive(Debug, Default)]
+pub struct FocusManager {
+    current: Option<usize>,
+}
+
+impl FocusManager {
+    pub fn new() -> Self {
+        Self { current: None }
+    }
+
+    /// Index of the focused widget
+    pub fn current(&self) -> Option<usize> {
+        self.current
+    }
+
+    /// Moves focus to the widget at index and outputs every widget whose focus changed
+    pub fn focus<A>(
+        &mut self,
+        index: usize,
+        widgets: &mut [&mut dyn Widget<A>],
+        render_tx: &Sender<RenderSignal>,
+        canvas: &Canvas,
+    ) -> Result<(), SendError<RenderSignal>> {
+        if !widgets.get(index).is_some_and(|w| w.focusable()) {
+            return Ok(());
+        }
+        self.current = Some(index);
+        for (i, w) in widgets.iter_mut().enumerate() {
+            let focus = i == index;
+            if w.is_focused() != focus {
+                w.set_focus(focus);
+                w.output(render_tx, canvas)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn next<A>(
+        &mut self,
+        widgets: &mut [&mut dyn Widget<A>],
+        render_tx: &Sender<RenderSignal>,
+        canvas: &Canvas,
+    ) -> Result<(), SendError<RenderSignal>> {
+        match self.step(widgets, true) {
+            Some(i) => self.focus(i, widgets, render_tx, canvas),
+            None => Ok(()),
+        }
+    }
+
+    pub fn prev<A>(
+        &mut self,
+        widgets: &mut [&mut dyn Widget<A>],
+        render_tx: &Sender<RenderSignal>,
+        canvas: &Canvas,
+    ) -> Result<(), SendError<RenderSignal>> {
+        match self.step(widgets, false) {
+            Some(i) => self.focus(i, widgets, render_tx, canvas),
+            None => Ok(()),
+        }
+    }
+
+    /// Next focusable index in the direction, wrapping around the ends
+    fn step<A>(&self, widgets: &[&mut dyn Widget<A>], forward: bool) -> Option<usize> {
+        let len = widgets.len();
+        if len == 0 {
+            return None;
+        }
+        let start = match self.current {
+            Some(c) => c.min(len - 1),
+            // the first step lands on the first or last widget
+            None if forward => len - 1,
+            None => 0,
+        };
+        (1..=len)
+            .map(|n| if forward { (start + n) % len } else { (start + len - n) % len })
+            .find(|i| widgets[*i].focusable())
+    }
+
+    /// Tab and Shift+Tab move focus and a left click focuses the widget under it before it is
+    /// handed the click, everything else goes to the focused widget
+    pub fn dispatch<A>(
+        &mut self,
+        event: &Event,
+        widgets: &mut [&mut dyn Widget<A>],
+        render_tx: &Sender<RenderSignal>,
+        canvas: &Canvas,
+    ) -> Result<Option<A>, SendError<RenderSignal>> {
+        if let Some((x, y)) = left_click(event) {
+            let hit = widgets
+                .iter()
+                .position(|w| w.focusable() && w.bounds(canvas).contains(x, y));
+            return match hit {
+                Some(i) => {
+                    self.focus(i, widgets, render_tx, canvas)?;
+                    // the click may also mean something to the widget, like toggling a checkbox
+                    Ok(widgets[i].handle_event(event, render_tx, canvas))
+                }
+                None => Ok(None),
+            };
+        }
+        match event {
+            Event::Keyboard(KeyEvent::Tab) => self.next(widgets, render_tx, canvas).map(|_| None),
+            Event::Keyboard(KeyEvent::BackTab) => self.prev(widgets, render_tx, canvas).map(|_| None),
+            // pasted text is the only other event meant for a single widget
+            Event::Other(OtherEvent::Paste(_)) | Event::Keyboard(_) | Event::Mouse(_) => {
+                match self.current.and_then(|i| widgets.get_mut(i)) {
+                    Some(w) => Ok(w.handle_event(event, render_tx, canvas)),
+                    None => Ok(None),
+                }
+            }
+            Event::Other(_) => Ok(None),
+        }
+    }
 }
 
 pub struct DisplayProperties {
@@ -49,3 +227,90 @@ impl Default for DisplayProperties {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct Probe {
+        focused: bool,
+        focusable: bool,
+        keys: usize,
+        rect: Rect,
+    }
+
+    impl Probe {
+        fn new(focusable: bool, y: i32) -> Self {
+            Self {
+                focused: false,
+                focusable,
+                keys: 0,
+                rect: Rect::new(0, y, 10, 1),
+            }
+        }
+    }
+
+    impl Widget<usize> for Probe {
+        fn handle_event(
+            &mut self,
+            _event: &Event,
+            _render_tx: &Sender<RenderSignal>,
+            _canvas: &Canvas,
+        ) -> Option<usize> {
+            self.keys += 1;
+            Some(self.keys)
+        }
+
+        fn output(
+            &mut self,
+            _render_tx: &Sender<RenderSignal>,
+            _canvas: &Canvas,
+        ) -> Result<(), SendError<RenderSignal>> {
+            Ok(())
+        }
+
+        fn focusable(&self) -> bool {
+            self.focusable
+        }
+
+        fn set_focus(&mut self, focus: bool) {
+            self.focused = focus;
+        }
+
+        fn is_focused(&self) -> bool {
+            self.focused
+        }
+
+        fn bounds(&self, _canvas: &Canvas) -> Rect {
+            self.rect
+        }
+    }
+
+    #[test]
+    fn focus_tab_order_test() {
+        let (tx, _rx) = std::sync::mpsc::channel();
+        let canvas = Canvas::new(20, 10);
+        let (mut a, mut b, mut c) = (Probe::new(true, 0), Probe::new(false, 1), Probe::new(true, 2));
+        let mut focus = FocusManager::new();
+        let tab = Event::Keyboard(KeyEvent::Tab);
+        let back = Event::Keyboard(KeyEvent::BackTab);
+        {
+            let mut widgets: [&mut dyn Widget<usize>; 3] = [&mut a, &mut b, &mut c];
+            // nothing has focus so keys go nowhere
+            let key = Event::Keyboard(KeyEvent::Char('x'));
+            assert!(matches!(focus.dispatch(&key, &mut widgets, &tx, &canvas), Ok(None)));
+            focus.dispatch(&tab, &mut widgets, &tx, &canvas).unwrap();
+            assert_eq!(focus.current(), Some(0));
+            // the label in the middle is skipped
+            focus.dispatch(&tab, &mut widgets, &tx, &canvas).unwrap();
+            assert_eq!(focus.current(), Some(2));
+            focus.dispatch(&tab, &mut widgets, &tx, &canvas).unwrap();
+            assert_eq!(focus.current(), Some(0));
+            focus.dispatch(&back, &mut widgets, &tx, &canvas).unwrap();
+            assert_eq!(focus.current(), Some(2));
+            assert!(matches!(focus.dispatch(&key, &mut widgets, &tx, &canvas), Ok(Some(1))));
+        }
+        assert!(!a.focused && !b.focused && c.focused);
+        assert_eq!((a.keys, c.keys), (0, 1));
+    }
+}
@@ -0,0 +1,515 @@
+/*
+Copyright 2025 Luke Maple
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+you may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+use super::super::enums::RenderSignal;
+use super::{
+    Border, Padding, Rect, Widget,
+    core::left_click,
+    style::{Coloring, Measure, Overflow, Size, Style},
+};
+use crate::engine::{
+    input::{Event, KeyEvent},
+    render::{Canvas, Char, Layer, ObjectData, RenderUnitId, Text, TextType, flow, markup_lines},
+    types::Position,
+};
+use my_term::color::{BLACK, WHITE};
+use std::sync::mpsc::SendError;
+use std::sync::{Arc, Weak, mpsc::Sender};
+
+/// Width of the input line of a prompt
+const INPUT_WIDTH: usize = 24;
+/// Cells between buttons
+const BUTTON_GAP: usize = 2;
+/// Cells kept free on either side of the dialog when the message is wrapped
+const MARGIN: usize = 2;
+
+/// What the player picked, handed back once the dialog closes
+#[derive(Debug, Clone, PartialEq)]
+pub enum DialogResult {
+    Ok,
+    Yes,
+    No,
+    Text(String),
+    Choice(usize),
+    Cancel,
+}
+
+#[derive(Debug, Clone)]
+enum Kind {
+    Alert,
+    Confirm,
+    Prompt,
+    Choice(Vec<String>),
+}
+
+/// Bordered box in the middle of the screen drawn over everything else on the ui layer.
+/// While one is open the scene should hand it every event and ignore its other widgets,
+/// the first Some from handle_event is the answer and the dialog should then be removed
+#[derive(Debug)]
+pub struct Dialog {
+    pub render_id: Weak<RenderUnitId>,
+    kind: Kind,
+    title: String,
+    /// markup, see Text::from_markup
+    message: String,
+    /// button, or option of a choice, under the cursor
+    cursor: usize,
+    input: String,
+    max_len: usize,
+    color: Coloring,
+    hover_color: Coloring,
+    /// set on every output so clicks can be placed
+    pos: Position<i32>,
+    /// widest the message may be on the current canvas, set on every output
+    wrap: usize,
+}
+
+impl Dialog {
+    fn new(kind: Kind, title: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            render_id: Weak::new(),
+            kind,
+            title: title.into(),
+            message: message.into(),
+            cursor: 0,
+            input: String::new(),
+            max_len: INPUT_WIDTH - 1,
+            color: Coloring::new(WHITE, BLACK),
+            hover_color: Coloring::new(BLACK, WHITE),
+            pos: Position { x: 0, y: 0 },
+            wrap: usize::MAX,
+        }
+    }
+
+    /// Message with an OK button, always answers Ok.
+    /// Messages of every kind of dialog are markup, escape_markup keeps outside text as it is
+    pub fn alert(title: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::new(Kind::Alert, title, message)
+    }
+
+    /// Yes and No buttons, y and n pick them straight away and Escape answers No
+    pub fn confirm(title: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::new(Kind::Confirm, title, message)
+    }
+
+    /// Line of text input answered with Text, or Cancel
+    pub fn prompt(title: impl Into<String>, message: impl Into<String>, default: impl Into<String>) -> Self {
+        let mut dialog = Self::new(Kind::Prompt, title, message);
+        dialog.input = default.into();
+        dialog
+    }
+
+    /// List of options answered with the index of the one picked, or Cancel
+    pub fn choice(title: impl Into<String>, message: impl Into<String>, options: Vec<String>) -> Self {
+        Self::new(Kind::Choice(options), title, message)
+    }
+
+    pub fn set_color(mut self, color: Coloring) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn set_hover_color(mut self, color: Coloring) -> Self {
+        self.hover_color = color;
+        self
+    }
+
+    /// Longest text a prompt takes, the input line grows to fit it
+    pub fn set_max_len(mut self, len: usize) -> Self {
+        self.max_len = len;
+        if let Some((i, _)) = self.input.char_indices().nth(len) {
+            self.input.truncate(i);
+        }
+        self
+    }
+
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    fn buttons(&self) -> &'static [&'static str] {
+        match self.kind {
+            Kind::Alert => &["OK"],
+            Kind::Confirm => &["Yes", "No"],
+            Kind::Prompt => &["OK", "Cancel"],
+            Kind::Choice(_) => &[],
+        }
+    }
+
+    /// Number of things the cursor moves between
+    fn stops(&self) -> usize {
+        match &self.kind {
+            Kind::Choice(options) => options.len(),
+            _ => self.buttons().len(),
+        }
+    }
+
+    fn step(&mut self, forward: bool) {
+        let n = self.stops();
+        if n > 0 {
+            self.cursor = if forward { (self.cursor + 1) % n } else { (self.cursor + n - 1) % n };
+        }
+    }
+
+    /// Answer of the button or option under the cursor
+    fn activate(&self) -> Option<DialogResult> {
+        match &self.kind {
+            Kind::Alert => Some(DialogResult::Ok),
+            Kind::Confirm if self.cursor == 0 => Some(DialogResult::Yes),
+            Kind::Confirm => Some(DialogResult::No),
+            Kind::Prompt if self.cursor == 0 => Some(DialogResult::Text(self.input.clone())),
+            Kind::Prompt => Some(DialogResult::Cancel),
+            Kind::Choice(options) if self.cursor < options.len() => Some(DialogResult::Choice(self.cursor)),
+            Kind::Choice(_) => None,
+        }
+    }
+
+    fn cancel(&self) -> DialogResult {
+        match self.kind {
+            Kind::Alert => DialogResult::Ok,
+            Kind::Confirm => DialogResult::No,
+            Kind::Prompt | Kind::Choice(_) => DialogResult::Cancel,
+        }
+    }
+
+    fn input_width(&self) -> usize {
+        INPUT_WIDTH.max(self.max_len + 1)
+    }
+
+    /// Message that does not parse as markup is shown as plain text, long lines are word wrapped
+    fn message_lines(&self) -> Vec<Text> {
+        let (fg, bg) = (self.color.foreground, self.color.background);
+        if self.message.is_empty() {
+            return Vec::new();
+        }
+        markup_lines(&self.message, fg, bg)
+            .unwrap_or_else(|_| self.message.lines().map(|l| Text::from(l, fg, bg)).collect())
+            .iter()
+            .flat_map(|l| flow(l, self.wrap, Overflow::WordWrap))
+            .collect()
+    }
+
+    fn button_row_len(&self) -> usize {
+        let buttons = self.buttons();
+        buttons.iter().map(|b| b.len() + 4).sum::<usize>() + BUTTON_GAP * buttons.len().saturating_sub(1)
+    }
+
+    /// Width of the widest line inside the border
+    fn content_width(&self) -> usize {
+        let mut w = self.title.chars().count().max(self.button_row_len());
+        for line in self.message_lines() {
+            w = w.max(line.len());
+        }
+        match &self.kind {
+            Kind::Prompt => w = w.max(self.input_width()),
+            Kind::Choice(options) => {
+                for o in options.iter() {
+                    w = w.max(o.chars().count() + 2);
+                }
+            }
+            _ => {}
+        }
+        w
+    }
+
+    fn border() -> Border {
+        Border::as_heavy(Padding::rectangle(2, 0))
+    }
+
+    /// Lines inside the border, every one exactly content_width long.
+    /// The second value is the line the buttons or the first option are on
+    fn lines(&self) -> (Vec<Text>, usize) {
+        let width = self.content_width();
+        let (fg, bg) = (self.color.foreground, self.color.background);
+        let plain = |s: &str| pad(Text::from(s, fg, bg), width, true, fg, bg);
+        let mut lines = Vec::new();
+        if !self.title.is_empty() {
+            lines.push(plain(&self.title));
+            lines.push(plain(""));
+        }
+        for line in self.message_lines() {
+            lines.push(pad(line, width, false, fg, bg));
+        }
+        if !self.message.is_empty() {
+            lines.push(plain(""));
+        }
+        match &self.kind {
+            Kind::Choice(options) => {
+                let first = lines.len();
+                for (i, o) in options.iter().enumerate() {
+                    let marker = if i == self.cursor { "> " } else { "  " };
+                    let mut line = pad(Text::from(format!("{}{}", marker, o), fg, bg), width, false, fg, bg);
+                    if i == self.cursor {
+                        line.set_fg(self.hover_color.foreground);
+                        line.set_bg(self.hover_color.background);
+                    }
+                    lines.push(line);
+                }
+                return (lines, first);
+            }
+            Kind::Prompt => {
+                let mut input = Text::from(format!("{}_", self.input), self.hover_color.foreground, self.hover_color.background);
+                input = pad(input, self.input_width(), false, self.hover_color.foreground, self.hover_color.background);
+                lines.push(pad(input, width, true, fg, bg));
+                lines.push(plain(""));
+            }
+            _ => {}
+        }
+        let mut row = Text::new();
+        for (i, label) in self.buttons().iter().enumerate() {
+            if i > 0 {
+                for _ in 0..BUTTON_GAP {
+                    row.push(Char::new(' ', fg, bg));
+                }
+            }
+            let mut button = Text::from(format!("[ {} ]", label), fg, bg);
+            if i == self.cursor {
+                button.set_fg(self.hover_color.foreground);
+                button.set_bg(self.hover_color.background);
+            }
+            row.join(button);
+        }
+        let first = lines.len();
+        lines.push(pad(row, width, true, fg, bg));
+        (lines, first)
+    }
+
+    fn style(&self, lines: usize) -> Style {
+        let border = Self::border();
+        let width = self.content_width() + border.width();
+        let height = lines + 2 + border.top_pad() + border.bot_pad();
+        Style::default()
+            .set_border(border)
+            .set_size(Size::rect(Measure::Cell(width as u32), Measure::Cell(height as u32)))
+            .set_fg(self.color.foreground)
+            .set_bg(self.color.background)
+    }
+
+    /// Centers the dialog on the canvas and draws it over the rest of the ui
+    pub fn output(&mut self, render_tx: &Sender<RenderSignal>, canvas: &Canvas) -> Result<(), SendError<RenderSignal>> {
+        self.wrap = canvas.width.saturating_sub(Self::border().width() + 2 * MARGIN).max(1);
+        let (lines, _) = self.lines();
+        let style = self.style(lines.len());
+        let w = style.width().map_or(0, |m| m.get(canvas.width));
+        let h = style.height().map_or(0, |m| m.get(canvas.height));
+        self.pos = Position {
+            x: (canvas.width.saturating_sub(w) / 2) as i32,
+            y: (canvas.height.saturating_sub(h) / 2) as i32,
+        };
+        let data = ObjectData::Text {
+            pos: self.pos.into(),
+            data: TextType::Single(lines),
+            style,
+        };
+        match self.render_id.upgrade() {
+            None => {
+                let arc_id = RenderUnitId::new(Layer::Ui);
+                self.render_id = Arc::downgrade(&arc_id);
+                render_tx.send(RenderSignal::Insert(arc_id.clone(), data))?;
+                render_tx.send(RenderSignal::Raise(arc_id))
+            }
+            Some(arc) => render_tx.send(RenderSignal::Update(arc, data)),
+        }
+    }
+
+    pub fn remove(&mut self, render_tx: &Sender<RenderSignal>) -> Result<(), SendError<RenderSignal>> {
+        match self.render_id.upgrade() {
+            Some(arc) => {
+                self.render_id = Weak::new();
+                render_tx.send(RenderSignal::Remove(arc))
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Button or option drawn at the screen cell
+    fn stop_at(&self, x: i32, y: i32) -> Option<usize> {
+        let border = Self::border();
+        let (_, first) = self.lines();
+        let left = self.pos.x + 1 + border.l_pad() as i32;
+        let row = y - (self.pos.y + 1 + border.top_pad() as i32 + first as i32);
+        if let Kind::Choice(options) = &self.kind {
+            return (row >= 0 && (row as usize) < options.len()).then_some(row as usize);
+        }
+        if row != 0 {
+            return None;
+        }
+        let mut start = left + ((self.content_width() - self.button_row_len()) / 2) as i32;
+        for (i, label) in self.buttons().iter().enumerate() {
+            let end = start + label.len() as i32 + 4;
+            if x >= start && x < end {
+                return Some(i);
+            }
+            start = end + BUTTON_GAP as i32;
+        }
+        None
+    }
+
+    fn key(&mut self, key: &KeyEvent) -> Option<Option<DialogResult>> {
+        let prompt = matches!(self.kind, Kind::Prompt);
+        let choice = matches!(self.kind, Kind::Choice(_));
+        match key {
+            KeyEvent::Enter => return Some(self.activate()),
+            KeyEvent::Escape => return Some(Some(self.cancel())),
+            KeyEvent::Tab => self.step(true),
+            KeyEvent::BackTab => self.step(false),
+            KeyEvent::Right if !choice => self.step(true),
+            KeyEvent::Left if !choice => self.step(false),
+            KeyEvent::Down if choice => self.step(true),
+            KeyEvent::Up if choice => self.step(false),
+            KeyEvent::Backspace if prompt => {
+                self.input.pop();
+            }
+            KeyEvent::Char(c) if prompt => {
+                if !c.is_control() && self.input.chars().count() < self.max_len {
+                    self.input.push(*c);
+                }
+            }
+            KeyEvent::Char(' ') if matches!(self.kind, Kind::Alert) => return Some(Some(DialogResult::Ok)),
+            KeyEvent::Char('y') if matches!(self.kind, Kind::Confirm) => return Some(Some(DialogResult::Yes)),
+            KeyEvent::Char('n') if matches!(self.kind, Kind::Confirm) => return Some(Some(DialogResult::No)),
+            KeyEvent::Char('s') if choice => self.step(true),
+            KeyEvent::Char('w') if choice => self.step(false),
+            KeyEvent::Char(c @ '1'..='9') if choice => {
+                let i = *c as usize - '1' as usize;
+                if i >= self.stops() {
+                    return None;
+                }
+                return Some(Some(DialogResult::Choice(i)));
+            }
+            _ => return None,
+        }
+        Some(None)
+    }
+}
+
+/// Pads the text with spaces out to width, centered or on the left
+fn pad(mut text: Text, width: usize, center: bool, fg: impl Into<u8> + Copy, bg: impl Into<u8> + Copy) -> Text {
+    let free = width.saturating_sub(text.len());
+    let left = if center { free / 2 } else { 0 };
+    let mut out = Text::new();
+    for _ in 0..left {
+        out.push(Char::new(' ', fg, bg));
+    }
+    out.join(text);
+    text = out;
+    for _ in 0..free - left {
+        text.push(Char::new(' ', fg, bg));
+    }
+    text
+}
+
+/// Every key goes to the dialog, clicks pick the button or option under them and
+/// clicks anywhere else are swallowed
+impl Widget<DialogResult> for Dialog {
+    fn handle_event(
+        &mut self,
+        event: &Event,
+        render_tx: &Sender<RenderSignal>,
+        canvas: &Canvas,
+    ) -> Option<DialogResult> {
+        if let Some((x, y)) = left_click(event) {
+            let i = self.stop_at(x, y)?;
+            self.cursor = i;
+            return self.activate();
+        }
+        match event {
+            Event::Keyboard(key) => match self.key(key)? {
+                Some(result) => Some(result),
+                None => {
+                    let _ = Dialog::output(self, render_tx, canvas);
+                    None
+                }
+            },
+            Event::Other(_) => {
+                // a resize moves the middle of the screen
+                let _ = Dialog::output(self, render_tx, canvas);
+                None
+            }
+            _ => None,
+        }
+    }
+
+    fn output(
+        &mut self,
+        render_tx: &Sender<RenderSignal>,
+        canvas: &Canvas,
+    ) -> Result<(), SendError<RenderSignal>> {
+        Dialog::output(self, render_tx, canvas)
+    }
+
+    fn set_focus(&mut self, _focus: bool) {}
+
+    fn is_focused(&self) -> bool {
+        true
+    }
+
+    fn bounds(&self, _canvas: &Canvas) -> Rect {
+        let (lines, _) = self.lines();
+        let border = Self::border();
+        let width = self.content_width() + border.width();
+        let height = lines.len() + 2 + border.top_pad() + border.bot_pad();
+        Rect::new(self.pos.x, self.pos.y, width, height)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn dialog_confirm_test() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let canvas = Canvas::new(40, 20);
+        let mut d = Dialog::confirm("Quit", "Leave the game?");
+        d.output(&tx, &canvas).unwrap();
+        assert!(matches!(rx.try_recv(), Ok(RenderSignal::Insert(..))));
+        assert!(matches!(rx.try_recv(), Ok(RenderSignal::Raise(..))));
+        let (lines, first) = d.lines();
+        assert_eq!(lines[first].to_string(), "[ Yes ]  [ No ]");
+        // 15 wide plus a border and 2 cells of padding on each side, 5 lines plus the border
+        assert_eq!(d.bounds(&canvas), Rect::new(9, 6, 21, 7));
+
+        let right = Event::Keyboard(KeyEvent::Right);
+        assert_eq!(d.handle_event(&right, &tx, &canvas), None);
+        let enter = Event::Keyboard(KeyEvent::Enter);
+        assert_eq!(d.handle_event(&enter, &tx, &canvas), Some(DialogResult::No));
+        // the Yes button starts right after the border and padding
+        assert_eq!(d.stop_at(13, 11), Some(0));
+    }
+
+    #[test]
+    fn dialog_prompt_test() {
+        let (tx, _rx) = std::sync::mpsc::channel();
+        let canvas = Canvas::new(40, 20);
+        let mut d = Dialog::prompt("", "Name your world", "Ur").set_max_len(4);
+        for c in "ist!".chars() {
+            d.handle_event(&Event::Keyboard(KeyEvent::Char(c)), &tx, &canvas);
+        }
+        assert_eq!(d.input(), "Uris");
+        // cut by characters, not bytes
+        assert_eq!(Dialog::prompt("", "", "\u{dc}rist").set_max_len(1).input(), "\u{dc}");
+        let esc = Event::Keyboard(KeyEvent::Escape);
+        assert_eq!(d.handle_event(&esc, &tx, &canvas), Some(DialogResult::Cancel));
+        let enter = Event::Keyboard(KeyEvent::Enter);
+        assert_eq!(d.handle_event(&enter, &tx, &canvas), Some(DialogResult::Text("Uris".to_string())));
+
+        let mut c = Dialog::choice("Pick", "", vec!["Dwarf".to_string(), "Elf".to_string()]);
+        let down = Event::Keyboard(KeyEvent::Down);
+        c.handle_event(&down, &tx, &canvas);
+        assert_eq!(c.handle_event(&enter, &tx, &canvas), Some(DialogResult::Choice(1)));
+    }
+}
@@ -0,0 +1,173 @@
+/*
+Copyright 2025 Luke Maple
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+you may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+use super::super::enums::RenderSignal;
+use super::style::Coloring;
+use crate::engine::{
+    render::{Layer, ObjectData, RenderUnitId, Text, TextType},
+    types::Position,
+    ui::style::Style,
+};
+use my_term::color::{BLACK, RED};
+use std::sync::mpsc::SendError;
+use std::sync::{Arc, Weak, mpsc::Sender};
+
+#[derive(Debug)]
+struct Field {
+    /// where the message is drawn, usually the line under the field
+    pos: Position<i32>,
+    error: Option<String>,
+    render_id: Weak<RenderUnitId>,
+}
+
+/// Validation layer over the widgets of a form.
+/// The scene checks each field's parsed value through it, failures are drawn as messages
+/// and the form stays invalid until every field parses
+#[derive(Debug)]
+pub struct Form {
+    fields: Vec<Field>,
+    color: Coloring,
+}
+
+impl Form {
+    pub fn new() -> Self {
+        Self {
+            fields: Vec::new(),
+            color: Coloring::new(RED, BLACK),
+        }
+    }
+
+    pub fn set_color(mut self, color: Coloring) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Adds a field whose messages are drawn at pos, returns its index
+    pub fn add(&mut self, pos: Position<i32>) -> usize {
+        self.fields.push(Field {
+            pos,
+            error: None,
+            render_id: Weak::new(),
+        });
+        self.fields.len() - 1
+    }
+
+    /// Moves where a field's message is drawn, output has to be called for the change to show
+    pub fn set_pos(&mut self, field: usize, pos: Position<i32>) {
+        if let Some(f) = self.fields.get_mut(field) {
+            f.pos = pos;
+        }
+    }
+
+    /// Records the outcome of parsing a field, the value is handed back when it parsed
+    pub fn check<T>(&mut self, field: usize, result: Result<T, String>) -> Option<T> {
+        match result {
+            Ok(v) => {
+                self.set_error(field, None);
+                Some(v)
+            }
+            Err(msg) => {
+                self.set_error(field, Some(msg));
+                None
+            }
+        }
+    }
+
+    /// Errors that are not about what was typed, like a failed save, can be put on a field directly
+    pub fn set_error(&mut self, field: usize, error: Option<String>) {
+        if let Some(f) = self.fields.get_mut(field) {
+            f.error = error;
+        }
+    }
+
+    pub fn error(&self, field: usize) -> Option<&str> {
+        self.fields.get(field).and_then(|f| f.error.as_deref())
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.fields.iter().all(|f| f.error.is_none())
+    }
+
+    pub fn clear(&mut self) {
+        for f in self.fields.iter_mut() {
+            f.error = None;
+        }
+    }
+
+    /// Draws the current messages and removes the ones that were fixed
+    pub fn output(&mut self, render_tx: &Sender<RenderSignal>) -> Result<(), SendError<RenderSignal>> {
+        let style = Style::default()
+            .set_fg(self.color.foreground)
+            .set_bg(self.color.background);
+        for f in self.fields.iter_mut() {
+            let Some(msg) = f.error.as_ref() else {
+                if let Some(arc) = f.render_id.upgrade() {
+                    f.render_id = Weak::new();
+                    render_tx.send(RenderSignal::Remove(arc))?;
+                }
+                continue;
+            };
+            let line = Text::from(
+                msg.as_str(),
+                self.color.foreground,
+                self.color.background,
+            );
+            let data = ObjectData::Text {
+                pos: f.pos.into(),
+                data: TextType::Single(vec![line]),
+                style: style.clone(),
+            };
+            match f.render_id.upgrade() {
+                None => {
+                    let arc_id = RenderUnitId::new(Layer::Ui);
+                    f.render_id = Arc::downgrade(&arc_id);
+                    render_tx.send(RenderSignal::Insert(arc_id, data))?;
+                }
+                Some(arc) => render_tx.send(RenderSignal::Update(arc, data))?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn form_check_test() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut form = Form::new();
+        let name = form.add(Position::new(0, 1));
+        let size = form.add(Position::new(0, 3));
+        let parsed: Option<u32> = form.check(name, "12".parse().map_err(|_| "not a number".to_string()));
+        assert_eq!(parsed, Some(12));
+        assert_eq!(form.check::<u32>(size, Err("pick a size".to_string())), None);
+        assert!(!form.is_valid());
+        assert_eq!(form.error(size), Some("pick a size"));
+
+        form.output(&tx).unwrap();
+        let id = match rx.try_recv() {
+            Ok(RenderSignal::Insert(id, _)) => id,
+            other => panic!("expected an insert, got {:?}", other),
+        };
+        // fixing the field takes the message down
+        form.check(size, Ok(1));
+        assert!(form.is_valid());
+        form.output(&tx).unwrap();
+        assert!(matches!(rx.try_recv(), Ok(RenderSignal::Remove(r)) if Arc::ptr_eq(&r, &id)));
+    }
+}
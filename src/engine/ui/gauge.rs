@@ -0,0 +1,336 @@
+/*
+Copyright 2025 Luke Maple
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+you may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+
+use super::super::enums::RenderSignal;
+use super::{
+    Rect, SelectionDirection, Widget,
+    core::text_bounds,
+    style::{H_EIGHTHS, LIGHT_BLOCK, Style, V_EIGHTHS},
+};
+use crate::engine::{
+    input::Event,
+    render::{Canvas, Char, Layer, ObjectData, RenderUnitId, Text, TextType},
+    types::Position,
+};
+use std::sync::mpsc::SendError;
+use std::sync::{Arc, Weak, mpsc::Sender};
+
+/// Anything with a current value out of a max that a gauge can show
+pub trait GaugeSource {
+    /// (current, max)
+    fn gauge_values(&self) -> (f64, f64);
+
+    /// Most decimals the values need, whole numbers by default
+    fn decimals(&self) -> usize {
+        0
+    }
+}
+
+impl GaugeSource for (f64, f64) {
+    fn gauge_values(&self) -> (f64, f64) {
+        *self
+    }
+
+    fn decimals(&self) -> usize {
+        2
+    }
+}
+
+/// Steps done out of the total, for progress bars
+impl GaugeSource for (usize, usize) {
+    fn gauge_values(&self) -> (f64, f64) {
+        (self.0 as f64, self.1 as f64)
+    }
+}
+
+/// What is written next to the bar
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GaugeText {
+    None,
+    /// 42%
+    Percent,
+    /// current/max
+    Value,
+}
+
+/// Read only bar filled in eighths of a cell, drawn as `label ███▌░░ 60%` or as a column
+/// filling from the bottom with the label and value under it
+#[derive(Debug)]
+pub struct Gauge {
+    pub render_id: Weak<RenderUnitId>,
+    pos: Position<i32>,
+    style: Style,
+    label: Text,
+    direction: SelectionDirection,
+    /// cells in the bar
+    len: usize,
+    current: f64,
+    max: f64,
+    text: GaugeText,
+    /// most decimals shown for GaugeText::Value, trailing zeros are dropped
+    decimals: usize,
+    /// (fraction, color) sorted by fraction, the first at or above the filled fraction colors the bar
+    thresholds: Vec<(f64, u8)>,
+}
+
+impl Gauge {
+    pub fn new(x: i32, y: i32, style: Style, label: Text, direction: SelectionDirection, len: usize) -> Self {
+        Self {
+            render_id: Weak::new(),
+            pos: Position { x, y },
+            style,
+            label,
+            direction,
+            len: len.max(1),
+            current: 0.0,
+            max: 1.0,
+            text: GaugeText::Value,
+            decimals: 0,
+            thresholds: Vec::new(),
+        }
+    }
+
+    /// Horizontal bar without a label that shows a percentage
+    pub fn progress(x: i32, y: i32, style: Style, len: usize) -> Self {
+        Self::new(x, y, style, Text::new(), SelectionDirection::Horizontal, len).set_text(GaugeText::Percent)
+    }
+
+    pub fn set_text(mut self, text: GaugeText) -> Self {
+        self.text = text;
+        self
+    }
+
+    /// Most decimals shown for values set with set_value, update takes them from the source
+    pub fn set_decimals(mut self, decimals: usize) -> Self {
+        self.decimals = decimals;
+        self
+    }
+
+    /// The bar is drawn in color while it is filled to fraction or less, the lowest fraction that
+    /// applies wins. Above every threshold the style's foreground is used
+    pub fn add_threshold(mut self, fraction: f64, color: u8) -> Self {
+        self.thresholds.push((fraction, color));
+        self.thresholds.sort_by(|a, b| a.0.total_cmp(&b.0));
+        self
+    }
+
+    /// Moves the gauge, output has to be called for the change to show
+    pub fn set_pos(&mut self, pos: Position<i32>) {
+        self.pos = pos;
+    }
+
+    /// Output has to be called for the change to show
+    pub fn set_value(&mut self, current: f64, max: f64) {
+        self.current = current;
+        self.max = max;
+    }
+
+    /// Takes the current and max of the source, true when they changed.
+    /// Output has to be called for the change to show
+    pub fn update(&mut self, source: &impl GaugeSource) -> bool {
+        let (current, max) = source.gauge_values();
+        self.decimals = source.decimals();
+        let changed = (current, max) != (self.current, self.max);
+        self.set_value(current, max);
+        changed
+    }
+
+    /// Part of the bar filled, 0 to 1
+    pub fn fraction(&self) -> f64 {
+        if self.max <= 0.0 {
+            return 0.0;
+        }
+        (self.current / self.max).clamp(0.0, 1.0)
+    }
+
+    fn fill_color(&self) -> u8 {
+        let fraction = self.fraction();
+        self.thresholds
+            .iter()
+            .find(|(at, _)| fraction <= *at)
+            .map_or(self.style.fg().into(), |(_, c)| *c)
+    }
+
+    /// Cells of the bar starting from the end that fills first
+    fn bar(&self) -> Vec<Char> {
+        let eighths = match self.direction {
+            SelectionDirection::Horizontal => H_EIGHTHS,
+            SelectionDirection::Vertical => V_EIGHTHS,
+        };
+        let (fg, bg) = (u8::from(self.style.fg()), u8::from(self.style.bg()));
+        let fill = self.fill_color();
+        let filled = (self.fraction() * (self.len * 8) as f64).round() as usize;
+        (0..self.len)
+            .map(|i| match filled.saturating_sub(i * 8).min(8) {
+                0 => Char::new(LIGHT_BLOCK, fg, bg),
+                e => Char::new(eighths[e - 1], fill, bg),
+            })
+            .collect()
+    }
+
+    fn value_text(&self) -> Option<String> {
+        match self.text {
+            GaugeText::None => None,
+            GaugeText::Percent => Some(format!("{:.0}%", self.fraction() * 100.0)),
+            GaugeText::Value => Some(format!(
+                "{}/{}",
+                number(self.current, self.decimals),
+                number(self.max, self.decimals)
+            )),
+        }
+    }
+
+    fn lines(&self) -> Vec<Text> {
+        let (fg, bg) = (self.style.fg(), self.style.bg());
+        let bar = self.bar();
+        match self.direction {
+            SelectionDirection::Horizontal => {
+                let mut line = self.label.clone();
+                if line.len() > 0 {
+                    line.push(Char::new(' ', fg, bg));
+                }
+                for c in bar {
+                    line.push(c);
+                }
+                if let Some(t) = self.value_text() {
+                    line.join(Text::from(format!(" {t}"), fg, bg));
+                }
+                vec![line]
+            }
+            SelectionDirection::Vertical => {
+                let mut lines: Vec<Text> = bar
+                    .into_iter()
+                    .rev()
+                    .map(|c| {
+                        let mut line = Text::new();
+                        line.push(c);
+                        line
+                    })
+                    .collect();
+                if self.label.len() > 0 {
+                    lines.push(self.label.clone());
+                }
+                if let Some(t) = self.value_text() {
+                    lines.push(Text::from(t, fg, bg));
+                }
+                lines
+            }
+        }
+    }
+
+    pub fn output(&mut self, render_tx: &Sender<RenderSignal>) -> Result<(), SendError<RenderSignal>> {
+        let data = ObjectData::Text {
+            pos: self.pos.into(),
+            data: TextType::Single(self.lines()),
+            style: self.style.clone(),
+        };
+        match self.render_id.upgrade() {
+            None => {
+                let arc_id = RenderUnitId::new(Layer::Ui);
+                self.render_id = Arc::downgrade(&arc_id);
+                render_tx.send(RenderSignal::Insert(arc_id, data))
+            }
+            Some(arc) => render_tx.send(RenderSignal::Update(arc, data)),
+        }
+    }
+
+    pub fn remove(&mut self, render_tx: &Sender<RenderSignal>) -> Result<(), SendError<RenderSignal>> {
+        match self.render_id.upgrade() {
+            Some(arc) => {
+                self.render_id = Weak::new();
+                render_tx.send(RenderSignal::Remove(arc))
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+/// Value rounded to decimals without trailing zeros
+fn number(value: f64, decimals: usize) -> String {
+    let s = format!("{value:.decimals$}");
+    match s.contains('.') {
+        true => s.trim_end_matches('0').trim_end_matches('.').to_string(),
+        false => s,
+    }
+}
+
+/// Only shows a value, it never takes focus
+impl<A> Widget<A> for Gauge {
+    fn handle_event(
+        &mut self,
+        _event: &Event,
+        _render_tx: &Sender<RenderSignal>,
+        _canvas: &Canvas,
+    ) -> Option<A> {
+        None
+    }
+
+    fn output(
+        &mut self,
+        render_tx: &Sender<RenderSignal>,
+        _canvas: &Canvas,
+    ) -> Result<(), SendError<RenderSignal>> {
+        Gauge::output(self, render_tx)
+    }
+
+    fn focusable(&self) -> bool {
+        false
+    }
+
+    fn set_focus(&mut self, _focus: bool) {}
+
+    fn is_focused(&self) -> bool {
+        false
+    }
+
+    fn bounds(&self, canvas: &Canvas) -> Rect {
+        text_bounds(self.pos, &self.style, &self.lines(), canvas)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use my_term::color::{GREEN, RED};
+
+    #[test]
+    fn gauge_test() {
+        let label = Text::from("HP", 7u8, 0u8);
+        let mut hp = Gauge::new(0, 0, Style::default(), label, SelectionDirection::Horizontal, 4)
+            .add_threshold(0.25, RED);
+        hp.update(&(5.0, 8.0));
+        // 5/8 of 32 eighths is two and a half cells
+        assert_eq!(hp.lines()[0].to_string(), "HP \u{2588}\u{2588}\u{258c}\u{2591} 5/8");
+        assert_ne!(u8::from(hp.bar()[0].fg()), RED);
+        assert!(!hp.update(&(5.0, 8.0)));
+        hp.update(&(0.1 + 0.2, 1.0));
+        assert!(hp.lines()[0].to_string().ends_with(" 0.3/1"));
+        hp.set_value(1.0, 8.0);
+        assert_eq!(u8::from(hp.bar()[0].fg()), RED);
+
+        let mut bar = Gauge::progress(0, 0, Style::default(), 2).add_threshold(1.0, GREEN);
+        bar.update(&(3usize, 4usize));
+        assert_eq!(bar.lines()[0].to_string(), "\u{2588}\u{258c} 75%");
+        assert_eq!(u8::from(bar.bar()[0].fg()), GREEN);
+
+        let mut column = Gauge::new(0, 0, Style::default(), Text::new(), SelectionDirection::Vertical, 2)
+            .set_text(GaugeText::None);
+        column.set_value(1.0, 4.0);
+        let lines: Vec<String> = column.lines().iter().map(|l| l.to_string()).collect();
+        assert_eq!(lines, vec!["\u{2591}", "\u{2584}"]);
+    }
+}
@@ -0,0 +1,514 @@
+/*
+Copyright 2025 Luke Maple
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+you may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+use super::style::{Align, Alignment, Justify, Measure, Size};
+use crate::engine::{render::Canvas, types::Position};
+
+/// Area in screen cells handed to a child by its container
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Rect {
+    pub fn new(x: i32, y: i32, width: usize, height: usize) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    pub fn from_canvas(canvas: &Canvas) -> Self {
+        Self::new(0, 0, canvas.width, canvas.height)
+    }
+
+    pub fn pos(&self) -> Position<i32> {
+        Position {
+            x: self.x,
+            y: self.y,
+        }
+    }
+
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x
+            && y >= self.y
+            && x < self.x + self.width as i32
+            && y < self.y + self.height as i32
+    }
+
+    /// Same rect with cells taken off every side
+    pub fn inset(&self, cells: usize) -> Self {
+        Self::new(
+            self.x + cells as i32,
+            self.y + cells as i32,
+            self.width.saturating_sub(cells * 2),
+            self.height.saturating_sub(cells * 2),
+        )
+    }
+}
+
+/// Edge a child of a dock layout is attached to, Fill takes whatever is left
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dock {
+    Top,
+    Bottom,
+    Left,
+    Right,
+    Fill,
+}
+
+/// Size rules for one child. Percentages are of the container's area,
+/// a missing width or height shares out whatever the fixed children leave
+#[derive(Debug, Clone)]
+pub struct Constraint {
+    pub size: Size,
+    pub min: Size,
+    pub max: Size,
+    pub dock: Dock,
+}
+
+impl Constraint {
+    pub fn new(size: Size) -> Self {
+        Self {
+            size,
+            min: Size::default(),
+            max: Size::default(),
+            dock: Dock::Fill,
+        }
+    }
+
+    /// Takes all the space it is given
+    pub fn fill() -> Self {
+        Self::new(Size::default())
+    }
+
+    pub fn set_min(mut self, min: Size) -> Self {
+        self.min = min;
+        self
+    }
+
+    pub fn set_max(mut self, max: Size) -> Self {
+        self.max = max;
+        self
+    }
+
+    pub fn set_dock(mut self, dock: Dock) -> Self {
+        self.dock = dock;
+        self
+    }
+
+    fn clamp_width(&self, w: usize, parent: usize) -> usize {
+        clamp(w, self.min.width, self.max.width, parent)
+    }
+
+    fn clamp_height(&self, h: usize, parent: usize) -> usize {
+        clamp(h, self.min.height, self.max.height, parent)
+    }
+}
+
+fn clamp(val: usize, min: Option<Measure>, max: Option<Measure>, parent: usize) -> usize {
+    let mut val = val;
+    if let Some(max) = max {
+        val = val.min(max.get(parent));
+    }
+    if let Some(min) = min {
+        val = val.max(min.get(parent));
+    }
+    val
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutKind {
+    /// children top to bottom
+    VStack,
+    /// children left to right
+    HStack,
+    /// children fill the cells of a grid row by row
+    Grid { columns: usize },
+    /// children are attached to the edges in order
+    Dock,
+}
+
+#[derive(Debug, Clone)]
+struct LayoutItem {
+    constraint: Constraint,
+    layout: Option<Layout>,
+    rect: Rect,
+}
+
+/// Container that works out where its children go, widgets are then placed at rect(index).
+/// Nested layouts are arranged inside the rect their parent hands them
+#[derive(Debug, Clone)]
+pub struct Layout {
+    kind: LayoutKind,
+    spacing: usize,
+    alignment: Alignment,
+    items: Vec<LayoutItem>,
+    area: Rect,
+}
+
+impl Layout {
+    pub fn new(kind: LayoutKind) -> Self {
+        Self {
+            kind,
+            spacing: 0,
+            alignment: Alignment::default(),
+            items: Vec::new(),
+            area: Rect::default(),
+        }
+    }
+
+    pub fn vstack() -> Self {
+        Self::new(LayoutKind::VStack)
+    }
+
+    pub fn hstack() -> Self {
+        Self::new(LayoutKind::HStack)
+    }
+
+    pub fn grid(columns: usize) -> Self {
+        Self::new(LayoutKind::Grid {
+            columns: columns.max(1),
+        })
+    }
+
+    pub fn dock() -> Self {
+        Self::new(LayoutKind::Dock)
+    }
+
+    /// Empty cells between children
+    pub fn set_spacing(mut self, spacing: usize) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    /// Where children smaller than their space sit inside it
+    pub fn set_alignment(mut self, alignment: Alignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    pub fn push(mut self, constraint: Constraint) -> Self {
+        self.add(constraint);
+        self
+    }
+
+    pub fn push_layout(mut self, constraint: Constraint, layout: Layout) -> Self {
+        self.items.push(LayoutItem {
+            constraint,
+            layout: Some(layout),
+            rect: Rect::default(),
+        });
+        self
+    }
+
+    /// Adds a child and returns its index
+    pub fn add(&mut self, constraint: Constraint) -> usize {
+        self.items.push(LayoutItem {
+            constraint,
+            layout: None,
+            rect: Rect::default(),
+        });
+        self.items.len() - 1
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn area(&self) -> Rect {
+        self.area
+    }
+
+    pub fn rect(&self, index: usize) -> Option<Rect> {
+        self.items.get(index).map(|i| i.rect)
+    }
+
+    pub fn rects(&self) -> Vec<Rect> {
+        self.items.iter().map(|i| i.rect).collect()
+    }
+
+    /// Nested layout pushed with push_layout
+    pub fn child(&self, index: usize) -> Option<&Layout> {
+        self.items.get(index).and_then(|i| i.layout.as_ref())
+    }
+
+    /// Arranges the layout over the whole screen, returns false when nothing moved
+    pub fn resize(&mut self, width: u32, height: u32) -> bool {
+        let before: Vec<Rect> = self.rects();
+        self.arrange(Rect::new(0, 0, width as usize, height as usize));
+        before != self.rects()
+    }
+
+    /// Works out the rect of every child inside area, nested layouts included
+    pub fn arrange(&mut self, area: Rect) {
+        self.area = area;
+        let rects = match self.kind {
+            LayoutKind::VStack => self.stack(true),
+            LayoutKind::HStack => self.stack(false),
+            LayoutKind::Grid { columns } => self.grid_rects(columns),
+            LayoutKind::Dock => self.dock_rects(),
+        };
+        for (item, rect) in self.items.iter_mut().zip(rects) {
+            item.rect = rect;
+            if let Some(layout) = item.layout.as_mut() {
+                layout.arrange(rect);
+            }
+        }
+    }
+
+    fn stack(&self, vertical: bool) -> Vec<Rect> {
+        let area = self.area;
+        let (main, cross) = if vertical {
+            (area.height, area.width)
+        } else {
+            (area.width, area.height)
+        };
+        let gaps = self.spacing * self.items.len().saturating_sub(1);
+        let available = main.saturating_sub(gaps);
+
+        // fixed children first, the rest split what is left
+        let mut sizes: Vec<Option<usize>> = Vec::with_capacity(self.items.len());
+        for item in self.items.iter() {
+            let c = &item.constraint;
+            let fixed = if vertical {
+                c.size.height.map(|m| c.clamp_height(m.get(main), main))
+            } else {
+                c.size.width.map(|m| c.clamp_width(m.get(main), main))
+            };
+            sizes.push(fixed);
+        }
+        let used: usize = sizes.iter().flatten().sum();
+        let flexible = sizes.iter().filter(|s| s.is_none()).count();
+        let left = available.saturating_sub(used);
+        let share = left.checked_div(flexible).unwrap_or(0);
+        let mut extra = left.checked_rem(flexible).unwrap_or(0);
+        let sizes: Vec<usize> = sizes
+            .iter()
+            .zip(self.items.iter())
+            .map(|(s, item)| match s {
+                Some(s) => *s,
+                None => {
+                    let mut size = share;
+                    if extra > 0 {
+                        size += 1;
+                        extra -= 1;
+                    }
+                    let c = &item.constraint;
+                    if vertical {
+                        c.clamp_height(size, main)
+                    } else {
+                        c.clamp_width(size, main)
+                    }
+                }
+            })
+            .collect();
+
+        let total: usize = sizes.iter().sum::<usize>() + gaps;
+        let free = main.saturating_sub(total);
+        let mut offset = if vertical {
+            match self.alignment.align {
+                Align::Top => 0,
+                Align::Center => free / 2,
+                Align::Bottom => free,
+            }
+        } else {
+            match self.alignment.justify {
+                Justify::Left => 0,
+                Justify::Center => free / 2,
+                Justify::Right => free,
+            }
+        } as i32;
+
+        let mut rects = Vec::with_capacity(self.items.len());
+        for (item, size) in self.items.iter().zip(sizes) {
+            let c = &item.constraint;
+            let rect = if vertical {
+                let w = c.clamp_width(c.size.width.map_or(cross, |m| m.get(cross)), cross);
+                let x = area.x + justify_offset(self.alignment.justify, cross, w);
+                Rect::new(x, area.y + offset, w, size)
+            } else {
+                let h = c.clamp_height(c.size.height.map_or(cross, |m| m.get(cross)), cross);
+                let y = area.y + align_offset(self.alignment.align, cross, h);
+                Rect::new(area.x + offset, y, size, h)
+            };
+            rects.push(rect);
+            offset += (size + self.spacing) as i32;
+        }
+        rects
+    }
+
+    fn grid_rects(&self, columns: usize) -> Vec<Rect> {
+        let area = self.area;
+        let rows = self.items.len().div_ceil(columns).max(1);
+        let cell_w = area.width.saturating_sub(self.spacing * (columns - 1)) / columns;
+        let cell_h = area.height.saturating_sub(self.spacing * (rows - 1)) / rows;
+        self.items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| {
+                let c = &item.constraint;
+                let (col, row) = (i % columns, i / columns);
+                let w = c.clamp_width(c.size.width.map_or(cell_w, |m| m.get(cell_w)), cell_w);
+                let h = c.clamp_height(c.size.height.map_or(cell_h, |m| m.get(cell_h)), cell_h);
+                let x = area.x + (col * (cell_w + self.spacing)) as i32;
+                let y = area.y + (row * (cell_h + self.spacing)) as i32;
+                Rect::new(
+                    x + justify_offset(self.alignment.justify, cell_w, w),
+                    y + align_offset(self.alignment.align, cell_h, h),
+                    w,
+                    h,
+                )
+            })
+            .collect()
+    }
+
+    fn dock_rects(&self) -> Vec<Rect> {
+        let mut free = self.area;
+        let mut rects = Vec::with_capacity(self.items.len());
+        for item in self.items.iter() {
+            let c = &item.constraint;
+            let w = c.clamp_width(
+                c.size.width.map_or(free.width, |m| m.get(self.area.width)),
+                self.area.width,
+            );
+            let h = c.clamp_height(
+                c.size.height.map_or(free.height, |m| m.get(self.area.height)),
+                self.area.height,
+            );
+            let (w, h) = (w.min(free.width), h.min(free.height));
+            let taken_w = (w + self.spacing).min(free.width);
+            let taken_h = (h + self.spacing).min(free.height);
+            let rect = match c.dock {
+                Dock::Top => {
+                    let r = Rect::new(free.x, free.y, free.width, h);
+                    free.y += taken_h as i32;
+                    free.height -= taken_h;
+                    r
+                }
+                Dock::Bottom => {
+                    let r = Rect::new(free.x, free.y + (free.height - h) as i32, free.width, h);
+                    free.height -= taken_h;
+                    r
+                }
+                Dock::Left => {
+                    let r = Rect::new(free.x, free.y, w, free.height);
+                    free.x += taken_w as i32;
+                    free.width -= taken_w;
+                    r
+                }
+                Dock::Right => {
+                    let r = Rect::new(free.x + (free.width - w) as i32, free.y, w, free.height);
+                    free.width -= taken_w;
+                    r
+                }
+                Dock::Fill => Rect::new(
+                    free.x + justify_offset(self.alignment.justify, free.width, w),
+                    free.y + align_offset(self.alignment.align, free.height, h),
+                    w,
+                    h,
+                ),
+            };
+            rects.push(rect);
+        }
+        rects
+    }
+}
+
+fn justify_offset(justify: Justify, space: usize, size: usize) -> i32 {
+    let free = space.saturating_sub(size);
+    (match justify {
+        Justify::Left => 0,
+        Justify::Center => free / 2,
+        Justify::Right => free,
+    }) as i32
+}
+
+fn align_offset(align: Align, space: usize, size: usize) -> i32 {
+    let free = space.saturating_sub(size);
+    (match align {
+        Align::Top => 0,
+        Align::Center => free / 2,
+        Align::Bottom => free,
+    }) as i32
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn vstack_test() {
+        let mut l = Layout::vstack()
+            .set_spacing(1)
+            .push(Constraint::new(Size::h_only(Measure::Cell(3))))
+            .push(Constraint::fill())
+            .push(Constraint::new(Size::rect(Measure::Percent(50), Measure::Cell(2))));
+        l.arrange(Rect::new(0, 0, 20, 20));
+        assert_eq!(l.rect(0), Some(Rect::new(0, 0, 20, 3)));
+        // 20 rows less 2 gaps, 3 and 2 fixed
+        assert_eq!(l.rect(1), Some(Rect::new(0, 4, 20, 13)));
+        assert_eq!(l.rect(2), Some(Rect::new(0, 18, 10, 2)));
+        assert!(!l.resize(20, 20));
+        assert!(l.resize(40, 10));
+        assert_eq!(l.rect(2), Some(Rect::new(0, 8, 20, 2)));
+    }
+
+    #[test]
+    fn hstack_min_max_test() {
+        let mut l = Layout::hstack()
+            .set_alignment(Alignment::centered())
+            .push(Constraint::fill().set_max(Size::w_only(Measure::Cell(5))))
+            .push(Constraint::new(Size::rect(Measure::Cell(2), Measure::Cell(1))))
+            .push(Constraint::fill().set_min(Size::w_only(Measure::Cell(12))));
+        l.arrange(Rect::new(0, 0, 30, 5));
+        let r = l.rects();
+        assert_eq!(r[0].width, 5);
+        assert_eq!(r[2].width, 14);
+        // the short child sits in the middle of the row
+        assert_eq!((r[1].y, r[1].height), (2, 1));
+        // 30 wide with 21 used leaves 9, the row starts 4 in
+        assert_eq!(r[0].x, 4);
+    }
+
+    #[test]
+    fn grid_and_dock_test() {
+        let mut g = Layout::grid(2)
+            .set_spacing(2)
+            .push(Constraint::fill())
+            .push(Constraint::fill())
+            .push(Constraint::fill());
+        g.arrange(Rect::new(1, 1, 22, 12));
+        assert_eq!(g.rect(1), Some(Rect::new(13, 1, 10, 5)));
+        assert_eq!(g.rect(2), Some(Rect::new(1, 8, 10, 5)));
+
+        let mut d = Layout::dock()
+            .push(Constraint::new(Size::h_only(Measure::Cell(1))).set_dock(Dock::Top))
+            .push(Constraint::new(Size::w_only(Measure::Percent(25))).set_dock(Dock::Left))
+            .push(Constraint::new(Size::h_only(Measure::Cell(2))).set_dock(Dock::Bottom))
+            .push_layout(Constraint::fill(), Layout::vstack().push(Constraint::fill()));
+        d.arrange(Rect::new(0, 0, 40, 20));
+        assert_eq!(d.rect(0), Some(Rect::new(0, 0, 40, 1)));
+        assert_eq!(d.rect(1), Some(Rect::new(0, 1, 10, 19)));
+        assert_eq!(d.rect(2), Some(Rect::new(10, 18, 30, 2)));
+        assert_eq!(d.child(3).unwrap().rect(0), Some(Rect::new(10, 1, 30, 17)));
+    }
+}
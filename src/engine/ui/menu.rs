@@ -16,14 +16,17 @@ limitations under the License.
 
 #![deny(unused_variables)]
 
+use crate::engine::input::{Event, KeyEvent};
 use crate::engine::render::{Char, ObjectData, PushChar, PushText, Text, TextType};
 use std::sync::mpsc;
+use std::sync::mpsc::SendError;
 use std::sync::{Arc, Weak, atomic::AtomicUsize, mpsc::Sender};
 
 use super::super::render::{Canvas, RenderQueue};
 use super::{
     super::types::*,
-    Border,
+    Border, Rect, Widget,
+    core::text_bounds,
     style::{Measure, Size, Style},
 };
 use crate::engine::enums::RenderSignal;
@@ -77,6 +80,7 @@ pub struct Menu<O> {
     position: Position<i32>,
     items: Vec<Item<O>>,
     cursor: usize,
+    focused: bool,
     #[allow(unused)]
     max_per_page: u16,
     _page: u16,
@@ -98,6 +102,8 @@ impl<O> Menu<O> {
             style,
             items: items,
             cursor: 0,
+            // a menu is usually the only thing on screen so it starts with the marker shown
+            focused: true,
             max_per_page: 0,
             _page: 0,
         }
@@ -106,7 +112,7 @@ impl<O> Menu<O> {
     pub fn shift(&mut self, x: i32, y: i32) {
         self.position.x += x;
         self.position.y += y;
-        self.output();
+        let _ = self.output();
     }
 
     pub fn style(&self) -> &Style {
@@ -156,13 +162,13 @@ impl<O> Menu<O> {
 
     pub fn add(&mut self, item: Item<O>) {
         self.items.push(item);
-        self.output();
+        let _ = self.output();
     }
 
     pub fn cursor_up(&mut self, amount: usize) -> bool {
         if self.cursor as isize - amount as isize >= 0 {
             self.cursor -= amount;
-            self.output();
+            let _ = self.output();
             return true; // Moved
         }
         false // did not move
@@ -171,16 +177,21 @@ impl<O> Menu<O> {
     pub fn cursor_down(&mut self, amount: usize) -> bool {
         if self.cursor + amount < self.items.len() {
             self.cursor += amount;
-            self.output();
+            let _ = self.output();
             return true; // Moved
         }
         false // did not move
     }
 
-    pub fn output(&mut self) {
+    pub fn output(&mut self) -> Result<(), SendError<RenderSignal>> {
+        let render_queue = self.render_queue.clone();
+        self.output_to(&render_queue)
+    }
+
+    fn output_to(&mut self, render_tx: &Sender<RenderSignal>) -> Result<(), SendError<RenderSignal>> {
         let mut out = Vec::with_capacity(self.items.len());
         for (i, l) in self.items.iter().enumerate() {
-            if i == self.cursor {
+            if i == self.cursor && self.focused {
                 let mut l = l.label.clone();
                 l.insert(0, self.marker);
                 l.insert(1, Char::new(' ', self.style.fg(), self.style.bg()));
@@ -192,30 +203,18 @@ impl<O> Menu<O> {
                 out.push(l);
             }
         }
+        let data = ObjectData::Text {
+            pos: self.position.clone().into(),
+            data: TextType::Single(out),
+            style: self.style.clone(),
+        };
         match self.render_id.upgrade() {
             None => {
                 let arc_id = RenderUnitId::new(Layer::Ui);
                 self.render_id = Arc::downgrade(&arc_id);
-
-                self.render_queue.send(RenderSignal::Insert(
-                    arc_id,
-                    ObjectData::Text {
-                        pos: self.position.clone().into(),
-                        data: TextType::Single(out),
-                        style: self.style.clone(),
-                    },
-                ));
-            }
-            Some(arc) => {
-                self.render_queue.send(RenderSignal::Update(
-                    arc,
-                    ObjectData::Text {
-                        pos: self.position.clone().into(),
-                        data: TextType::Single(out),
-                        style: self.style.clone(),
-                    },
-                ));
+                render_tx.send(RenderSignal::Insert(arc_id, data))
             }
+            Some(arc) => render_tx.send(RenderSignal::Update(arc, data)),
         }
     }
 
@@ -250,6 +249,54 @@ impl<O> Menu<O> {
     }
 }
 
+/// Up and down move the cursor, Enter runs the item under it
+impl<O> Widget<O> for Menu<O> {
+    fn handle_event(
+        &mut self,
+        event: &Event,
+        _render_tx: &Sender<RenderSignal>,
+        _canvas: &Canvas,
+    ) -> Option<O> {
+        match event {
+            Event::Keyboard(KeyEvent::Up) | Event::Keyboard(KeyEvent::Char('w')) => {
+                self.cursor_up(1);
+                None
+            }
+            Event::Keyboard(KeyEvent::Down) | Event::Keyboard(KeyEvent::Char('s')) => {
+                self.cursor_down(1);
+                None
+            }
+            Event::Keyboard(KeyEvent::Enter) if self.items.len() > 0 => Some(self.execute()),
+            _ => None,
+        }
+    }
+
+    fn output(
+        &mut self,
+        render_tx: &Sender<RenderSignal>,
+        _canvas: &Canvas,
+    ) -> Result<(), SendError<RenderSignal>> {
+        self.output_to(render_tx)
+    }
+
+    fn set_focus(&mut self, focus: bool) {
+        self.focused = focus;
+    }
+
+    fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    fn bounds(&self, canvas: &Canvas) -> Rect {
+        let mut bounds = text_bounds(self.position.clone(), &self.style, &Item::lines(&self.items), canvas);
+        if self.style.width().is_none() {
+            // room for the marker
+            bounds.width += CURSOR_OFFSET;
+        }
+        bounds
+    }
+}
+
 /*
 #[cfg(test)]
 mod test {
@@ -0,0 +1,146 @@
+/*
+Copyright 2025 Luke Maple
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+you may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+use super::super::enums::RenderSignal;
+use super::{Rect, Widget};
+use crate::engine::input::Event;
+use crate::engine::render::{Canvas, Layer, RenderTarget, RenderUnitId};
+use crate::engine::types::{Position, Position3D};
+use std::sync::mpsc::SendError;
+use std::sync::{Arc, Weak, mpsc::Sender};
+
+/// Small downsampled view of the world on the ui layer, the render thread redraws it every frame
+#[derive(Debug)]
+pub struct Minimap {
+    pub render_id: Weak<RenderUnitId>,
+    pos: Position<i32>,
+    target: RenderTarget,
+}
+
+impl Minimap {
+    /// width and height are in screen cells, every scale x scale block of the world becomes one cell
+    pub fn new(pos: Position<i32>, width: u32, height: u32, scale: u32) -> Self {
+        Self {
+            render_id: Weak::new(),
+            pos,
+            target: RenderTarget::minimap(width, height, scale),
+        }
+    }
+
+    /// None turns off the main camera outline
+    pub fn set_highlight(mut self, color: Option<u8>) -> Self {
+        self.target = self.target.set_highlight(color);
+        self
+    }
+
+    /// Fixes the top left corner of the map in world cells instead of following the main camera
+    pub fn set_origin(mut self, origin: Option<Position3D<i32>>) -> Self {
+        self.target = self.target.set_pos(origin);
+        self
+    }
+
+    pub fn scale(&self) -> u32 {
+        self.target.scale
+    }
+
+    pub fn rescale(
+        &mut self,
+        scale: u32,
+        render_tx: &Sender<RenderSignal>,
+    ) -> Result<(), SendError<RenderSignal>> {
+        self.target = self.target.clone().set_scale(scale);
+        self.output(render_tx)
+    }
+
+    pub fn output(&mut self, render_tx: &Sender<RenderSignal>) -> Result<(), SendError<RenderSignal>> {
+        match self.render_id.upgrade() {
+            None => {
+                let arc_id = RenderUnitId::new(Layer::Ui);
+                self.render_id = Arc::downgrade(&arc_id);
+                render_tx.send(RenderSignal::InsertTarget {
+                    id: arc_id,
+                    target: self.target.clone(),
+                    pos: self.pos.into(),
+                })
+            }
+            Some(arc) => render_tx.send(RenderSignal::UpdateTarget(arc, self.target.clone())),
+        }
+    }
+
+    pub fn remove(&mut self, render_tx: &Sender<RenderSignal>) -> Result<(), SendError<RenderSignal>> {
+        match self.render_id.upgrade() {
+            Some(arc) => {
+                self.render_id = Weak::new();
+                render_tx.send(RenderSignal::Remove(arc))
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+/// Only shows the world, it never takes focus
+impl<A> Widget<A> for Minimap {
+    fn handle_event(
+        &mut self,
+        _event: &Event,
+        _render_tx: &Sender<RenderSignal>,
+        _canvas: &Canvas,
+    ) -> Option<A> {
+        None
+    }
+
+    fn output(
+        &mut self,
+        render_tx: &Sender<RenderSignal>,
+        _canvas: &Canvas,
+    ) -> Result<(), SendError<RenderSignal>> {
+        Minimap::output(self, render_tx)
+    }
+
+    fn focusable(&self) -> bool {
+        false
+    }
+
+    fn set_focus(&mut self, _focus: bool) {}
+
+    fn is_focused(&self) -> bool {
+        false
+    }
+
+    fn bounds(&self, _canvas: &Canvas) -> Rect {
+        Rect::new(self.pos.x, self.pos.y, self.target.width as usize, self.target.height as usize)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::engine::ui::FocusManager;
+
+    #[test]
+    fn minimap_widget_test() {
+        let (tx, _rx) = std::sync::mpsc::channel();
+        let canvas = Canvas::new(80, 24);
+        let mut map = Minimap::new(Position::new(60, 1), 16, 8, 4);
+        assert!(!Widget::<()>::focusable(&map));
+        assert_eq!(Widget::<()>::bounds(&map, &canvas), Rect::new(60, 1, 16, 8));
+        // a focus manager with only the map has nothing to focus
+        let mut focus = FocusManager::new();
+        let mut widgets: [&mut dyn Widget<()>; 1] = [&mut map];
+        assert!(focus.next(&mut widgets, &tx, &canvas).is_ok());
+        assert_eq!(focus.current(), None);
+    }
+}
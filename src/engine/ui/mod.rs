@@ -16,10 +16,20 @@ limitations under the License.
 
 mod border;
 mod button;
+mod checkbox;
 mod core;
+mod dialog;
+mod form;
+mod gauge;
+mod layout;
 mod menu;
+mod minimap;
+mod number;
+mod radio;
 mod selector;
+mod slider;
 pub mod style;
+mod table;
 mod textbox;
 
 pub use core::*;
@@ -29,9 +39,19 @@ pub use border::BorderSprite;
 pub use border::Padding;
 
 pub use button::Button;
+pub use checkbox::CheckBox;
+pub use dialog::{Dialog, DialogResult};
+pub use form::Form;
+pub use gauge::{Gauge, GaugeSource, GaugeText};
+pub use layout::{Constraint, Dock, Layout, LayoutKind, Rect};
 pub use menu::Item as MenuItem;
 pub use menu::Menu;
+pub use minimap::Minimap;
+pub use number::{NumberField, NumberKind};
+pub use radio::RadioButtons;
 pub use selector::SelectionDirection;
 pub use selector::Selector;
 pub use selector::SelectorItem;
+pub use slider::Slider;
+pub use table::{Cell as TableCell, Column, ColumnKind, Table};
 pub use textbox::TextArea;
\ No newline at end of file
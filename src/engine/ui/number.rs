@@ -0,0 +1,292 @@
+/*
+Copyright 2025 Luke Maple
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+you may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+use super::super::enums::RenderSignal;
+use super::{
+    Rect, Widget,
+    core::text_bounds,
+    style::{Coloring, Style},
+};
+use crate::engine::{
+    input::{Event, KeyEvent},
+    render::{Canvas, Char, Layer, ObjectData, RenderUnitId, Text, TextType},
+    types::Position,
+};
+use my_term::color::{BLACK, RED};
+use std::sync::mpsc::SendError;
+use std::sync::{Arc, Weak, mpsc::Sender};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumberKind {
+    Integer,
+    Float,
+}
+
+/// Labeled number typed in or stepped with the arrow keys, drawn as `label: value`
+#[derive(Debug)]
+pub struct NumberField {
+    pub render_id: Weak<RenderUnitId>,
+    pos: Position<i32>,
+    style: Style,
+    label: Text,
+    hover_color: Coloring,
+    error_color: Coloring,
+    kind: NumberKind,
+    min: f64,
+    max: f64,
+    step: f64,
+    text: String,
+    focused: bool,
+}
+
+impl NumberField {
+    /// Whole numbers from 0 to 100 going up by 1 until told otherwise
+    pub fn new(x: i32, y: i32, style: Style, label: Text) -> Self {
+        Self {
+            render_id: Weak::new(),
+            pos: Position { x, y },
+            hover_color: Coloring::new(*style.bg(), *style.fg()),
+            error_color: Coloring::new(RED, BLACK),
+            style,
+            label,
+            kind: NumberKind::Integer,
+            min: 0.0,
+            max: 100.0,
+            step: 1.0,
+            text: "0".to_string(),
+            focused: false,
+        }
+    }
+
+    pub fn set_kind(mut self, kind: NumberKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    pub fn set_range(mut self, min: f64, max: f64) -> Self {
+        self.min = min.min(max);
+        self.max = max.max(min);
+        self
+    }
+
+    /// How far the arrow keys move the value
+    pub fn set_step(mut self, step: f64) -> Self {
+        self.step = step.abs();
+        self
+    }
+
+    pub fn set_value(mut self, value: f64) -> Self {
+        self.text = self.format(value.clamp(self.min, self.max));
+        self
+    }
+
+    pub fn set_hover_color(mut self, color: Coloring) -> Self {
+        self.hover_color = color;
+        self
+    }
+
+    /// Color of the value while it does not parse or is out of range
+    pub fn set_error_color(mut self, color: Coloring) -> Self {
+        self.error_color = color;
+        self
+    }
+
+    /// Moves the field, output has to be called for the change to show
+    pub fn set_pos(&mut self, pos: Position<i32>) {
+        self.pos = pos;
+    }
+
+    /// What has been typed so far
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// The typed value, Err holds a message fit to show the player
+    pub fn value(&self) -> Result<f64, String> {
+        let value: f64 = match self.text.trim().parse() {
+            Ok(v) => v,
+            Err(_) => return Err(format!("\"{}\" is not a number", self.text)),
+        };
+        if self.kind == NumberKind::Integer && value.fract() != 0.0 {
+            return Err("must be a whole number".to_string());
+        }
+        if value < self.min || value > self.max {
+            return Err(format!(
+                "must be between {} and {}",
+                self.format(self.min),
+                self.format(self.max)
+            ));
+        }
+        Ok(value)
+    }
+
+    /// Moves the value by steps, a value that does not parse starts over from the bottom of the range
+    pub fn increment(&mut self, steps: i32) {
+        let start = self.text.trim().parse::<f64>().unwrap_or(self.min);
+        let value = (start + self.step * steps as f64).clamp(self.min, self.max);
+        self.text = self.format(value);
+    }
+
+    /// Integers without a point, floats with as many decimals as the step
+    fn format(&self, value: f64) -> String {
+        match self.kind {
+            NumberKind::Integer => format!("{}", value.round() as i64),
+            NumberKind::Float => {
+                let step = format!("{}", self.step);
+                let decimals = step.split_once('.').map_or(0, |(_, d)| d.len()).max(1);
+                format!("{:.*}", decimals, value)
+            }
+        }
+    }
+
+    /// Edits the text, returns false when the key means nothing to the field
+    fn type_key(&mut self, key: &KeyEvent) -> bool {
+        match key {
+            KeyEvent::Up | KeyEvent::Right | KeyEvent::Char('+') => self.increment(1),
+            KeyEvent::Down | KeyEvent::Left => self.increment(-1),
+            KeyEvent::Backspace => {
+                self.text.pop();
+            }
+            KeyEvent::Char(c) if c.is_ascii_digit() => self.text.push(*c),
+            KeyEvent::Char('.') if self.kind == NumberKind::Float && !self.text.contains('.') => {
+                self.text.push('.')
+            }
+            KeyEvent::Char('-') if self.min < 0.0 => {
+                // flips the sign wherever the cursor is
+                match self.text.strip_prefix('-') {
+                    Some(rest) => self.text = rest.to_string(),
+                    None => self.text.insert(0, '-'),
+                }
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    fn line(&self) -> Text {
+        let mut line = self.label.clone();
+        let (fg, bg) = (self.style.fg(), self.style.bg());
+        line.push(Char::new(':', fg, bg));
+        line.push(Char::new(' ', fg, bg));
+        let mut value = Text::from(self.text.as_str(), fg, bg);
+        if self.focused {
+            value.push(Char::new('_', fg, bg));
+        }
+        let coloring = if self.value().is_err() {
+            Some(&self.error_color)
+        } else if self.focused {
+            Some(&self.hover_color)
+        } else {
+            None
+        };
+        if let Some(c) = coloring {
+            value.set_fg(c.foreground);
+            value.set_bg(c.background);
+        }
+        line.join(value);
+        line
+    }
+
+    pub fn output(&mut self, render_tx: &Sender<RenderSignal>) -> Result<(), SendError<RenderSignal>> {
+        let data = ObjectData::Text {
+            pos: self.pos.into(),
+            data: TextType::Single(vec![self.line()]),
+            style: self.style.clone(),
+        };
+        match self.render_id.upgrade() {
+            None => {
+                let arc_id = RenderUnitId::new(Layer::Ui);
+                self.render_id = Arc::downgrade(&arc_id);
+                render_tx.send(RenderSignal::Insert(arc_id, data))
+            }
+            Some(arc) => render_tx.send(RenderSignal::Update(arc, data)),
+        }
+    }
+}
+
+/// Digits, the point and the minus sign are typed in, up and down step the value.
+/// Nothing is handed back, the scene reads the number with value
+impl<A> Widget<A> for NumberField {
+    fn handle_event(
+        &mut self,
+        event: &Event,
+        render_tx: &Sender<RenderSignal>,
+        _canvas: &Canvas,
+    ) -> Option<A> {
+        if let Event::Keyboard(key) = event
+            && self.type_key(key)
+        {
+            let _ = self.output(render_tx);
+        }
+        None
+    }
+
+    fn output(
+        &mut self,
+        render_tx: &Sender<RenderSignal>,
+        _canvas: &Canvas,
+    ) -> Result<(), SendError<RenderSignal>> {
+        NumberField::output(self, render_tx)
+    }
+
+    fn set_focus(&mut self, focus: bool) {
+        self.focused = focus;
+    }
+
+    fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    fn bounds(&self, canvas: &Canvas) -> Rect {
+        text_bounds(self.pos, &self.style, &[self.line()], canvas)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn number_field_test() {
+        let label = Text::from("Sea level", 7u8, 0u8);
+        let mut f = NumberField::new(0, 0, Style::default(), label.clone())
+            .set_kind(NumberKind::Float)
+            .set_range(-1.0, 1.0)
+            .set_step(0.25)
+            .set_value(0.0);
+        assert_eq!(f.text(), "0.00");
+        f.increment(3);
+        assert_eq!(f.value(), Ok(0.75));
+        // clamped to the top of the range
+        f.increment(3);
+        assert_eq!(f.text(), "1.00");
+        for key in [KeyEvent::Backspace, KeyEvent::Backspace, KeyEvent::Char('5'), KeyEvent::Char('-')] {
+            f.type_key(&key);
+        }
+        assert_eq!(f.text(), "-1.5");
+        assert!(f.value().unwrap_err().contains("between -1.00 and 1.00"));
+
+        let mut whole = NumberField::new(0, 0, Style::default(), label).set_range(1.0, 10.0);
+        assert!(!whole.type_key(&KeyEvent::Char('.')));
+        whole.type_key(&KeyEvent::Char('7'));
+        assert_eq!(whole.value(), Ok(7.0));
+        whole.text = "x".to_string();
+        assert!(whole.value().is_err());
+        // starts again from the bottom of the range
+        whole.increment(1);
+        assert_eq!(whole.text(), "2");
+    }
+}
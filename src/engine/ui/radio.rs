@@ -0,0 +1,309 @@
+/*
+Copyright 2025 Luke Maple
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+you may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+use super::super::enums::RenderSignal;
+use super::{
+    Rect, SelectionDirection, SelectorItem, Widget,
+    core::{left_click, text_bounds},
+    style::{Coloring, DISABLED_FG, Style},
+};
+use crate::engine::{
+    input::{Event, KeyEvent},
+    render::{Canvas, Char, Layer, ObjectData, RenderUnitId, Text, TextType},
+    types::Position,
+};
+use std::sync::mpsc::SendError;
+use std::sync::{Arc, Weak, mpsc::Sender};
+
+/// cells between two options laid out side by side
+const GAP: usize = 2;
+
+/// Group of labeled options where exactly one is picked, drawn as `(*) label`
+#[derive(Debug)]
+pub struct RadioButtons {
+    pub render_id: Weak<RenderUnitId>,
+    pos: Position<i32>,
+    style: Style,
+    items: Vec<SelectorItem>,
+    hover_color: Coloring,
+    disabled_color: Coloring,
+    direction: SelectionDirection,
+    selected: usize,
+    cursor: usize,
+    focused: bool,
+    disabled: bool,
+}
+
+impl RadioButtons {
+    pub fn new(
+        x: i32,
+        y: i32,
+        style: Style,
+        hover_color: Coloring,
+        direction: SelectionDirection,
+        items: Vec<SelectorItem>,
+    ) -> Self {
+        Self {
+            render_id: Weak::new(),
+            pos: Position { x, y },
+            disabled_color: Coloring::new(DISABLED_FG, *style.bg()),
+            style,
+            items,
+            hover_color,
+            direction,
+            selected: 0,
+            cursor: 0,
+            focused: false,
+            disabled: false,
+        }
+    }
+
+    pub fn set_disabled_color(mut self, color: Coloring) -> Self {
+        self.disabled_color = color;
+        self
+    }
+
+    /// Index of the picked option
+    pub fn get_selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Value of the picked option
+    pub fn value(&self) -> Option<usize> {
+        self.items.get(self.selected).map(|i| i.value)
+    }
+
+    /// Picks the option at index and moves the cursor to it, output has to be called for the change to show
+    pub fn select(&mut self, index: usize) {
+        if index < self.items.len() {
+            self.selected = index;
+            self.cursor = index;
+        }
+    }
+
+    /// Picks the first option with the value, returns false when there is none
+    pub fn select_value(&mut self, value: usize) -> bool {
+        match self.items.iter().position(|i| i.value == value) {
+            Some(index) => {
+                self.select(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn next(&mut self) {
+        if !self.items.is_empty() {
+            self.cursor = (self.cursor + 1) % self.items.len();
+        }
+    }
+
+    pub fn prev(&mut self) {
+        if !self.items.is_empty() {
+            self.cursor = (self.cursor + self.items.len() - 1) % self.items.len();
+        }
+    }
+
+    pub fn is_disabled(&self) -> bool {
+        self.disabled
+    }
+
+    /// A disabled group is grayed out and skipped by Tab, output has to be called for the change to show
+    pub fn set_disabled(&mut self, disabled: bool) {
+        self.disabled = disabled;
+        if disabled {
+            self.focused = false;
+        }
+    }
+
+    /// Moves the group, output has to be called for the change to show
+    pub fn set_pos(&mut self, pos: Position<i32>) {
+        self.pos = pos;
+    }
+
+    fn option(&self, index: usize) -> Text {
+        let (fg, bg) = (self.style.fg(), self.style.bg());
+        let mark = if index == self.selected { '*' } else { ' ' };
+        let mut option = Text::new();
+        for c in ['(', mark, ')', ' '] {
+            option.push(Char::new(c, fg, bg));
+        }
+        option.join(self.items[index].label.clone());
+        let coloring = if self.disabled {
+            Some(&self.disabled_color)
+        } else if self.focused && index == self.cursor {
+            Some(&self.hover_color)
+        } else {
+            None
+        };
+        if let Some(c) = coloring {
+            option.set_fg(c.foreground);
+            option.set_bg(c.background);
+        }
+        option
+    }
+
+    fn lines(&self) -> Vec<Text> {
+        let options = (0..self.items.len()).map(|i| self.option(i));
+        match self.direction {
+            SelectionDirection::Horizontal => {
+                let mut t = Text::new();
+                for (i, option) in options.enumerate() {
+                    if i > 0 {
+                        for _ in 0..GAP {
+                            t.push(Char::new(' ', self.style.fg(), self.style.bg()));
+                        }
+                    }
+                    t.join(option);
+                }
+                vec![t]
+            }
+            SelectionDirection::Vertical => options.collect(),
+        }
+    }
+
+    /// Option drawn at the screen cell
+    fn option_at(&self, x: i32, y: i32) -> Option<usize> {
+        let (mut col, mut row) = (self.pos.x, self.pos.y);
+        if let Some(b) = &self.style.border {
+            col += 1 + b.l_pad() as i32;
+            row += 1 + b.top_pad() as i32;
+        }
+        for (i, item) in self.items.iter().enumerate() {
+            let width = 4 + item.label.len() as i32;
+            if y == row && x >= col && x < col + width {
+                return Some(i);
+            }
+            match self.direction {
+                SelectionDirection::Horizontal => col += width + GAP as i32,
+                SelectionDirection::Vertical => row += 1,
+            }
+        }
+        None
+    }
+
+    pub fn output(&mut self, render_tx: &Sender<RenderSignal>) -> Result<(), SendError<RenderSignal>> {
+        let data = ObjectData::Text {
+            pos: self.pos.into(),
+            data: TextType::Single(self.lines()),
+            style: self.style.clone(),
+        };
+        match self.render_id.upgrade() {
+            None => {
+                let arc_id = RenderUnitId::new(Layer::Ui);
+                self.render_id = Arc::downgrade(&arc_id);
+                render_tx.send(RenderSignal::Insert(arc_id, data))
+            }
+            Some(arc) => render_tx.send(RenderSignal::Update(arc, data)),
+        }
+    }
+}
+
+/// The arrows along the group's direction move the cursor, Enter or space picks the option under it
+/// and a click picks the option clicked. Nothing is handed back, the scene reads the choice with value
+impl<A> Widget<A> for RadioButtons {
+    fn handle_event(
+        &mut self,
+        event: &Event,
+        render_tx: &Sender<RenderSignal>,
+        _canvas: &Canvas,
+    ) -> Option<A> {
+        if self.disabled {
+            return None;
+        }
+        if let Some((x, y)) = left_click(event) {
+            let index = self.option_at(x, y)?;
+            self.select(index);
+        } else {
+            let Event::Keyboard(key) = event else {
+                return None;
+            };
+            match (&self.direction, key) {
+                (SelectionDirection::Horizontal, KeyEvent::Left | KeyEvent::Char('a'))
+                | (SelectionDirection::Vertical, KeyEvent::Up | KeyEvent::Char('w')) => self.prev(),
+                (SelectionDirection::Horizontal, KeyEvent::Right | KeyEvent::Char('d'))
+                | (SelectionDirection::Vertical, KeyEvent::Down | KeyEvent::Char('s')) => self.next(),
+                (_, KeyEvent::Enter | KeyEvent::Char(' ')) => self.select(self.cursor),
+                _ => return None,
+            }
+        }
+        let _ = self.output(render_tx);
+        None
+    }
+
+    fn output(
+        &mut self,
+        render_tx: &Sender<RenderSignal>,
+        _canvas: &Canvas,
+    ) -> Result<(), SendError<RenderSignal>> {
+        RadioButtons::output(self, render_tx)
+    }
+
+    fn focusable(&self) -> bool {
+        !self.disabled
+    }
+
+    fn set_focus(&mut self, focus: bool) {
+        self.focused = focus;
+    }
+
+    fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    fn bounds(&self, canvas: &Canvas) -> Rect {
+        text_bounds(self.pos, &self.style, &self.lines(), canvas)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::engine::input::poll_event;
+
+    #[test]
+    fn radio_select_test() {
+        let (tx, _rx) = std::sync::mpsc::channel();
+        let canvas = Canvas::new(60, 10);
+        let item = |l: &str, v: usize| SelectorItem::new(Text::from(l, 7u8, 0u8), v);
+        let mut r = RadioButtons::new(
+            0,
+            0,
+            Style::default(),
+            Coloring::default(),
+            SelectionDirection::Horizontal,
+            vec![item("30", 30), item("60", 60), item("120", 120)],
+        );
+        assert_eq!(r.lines()[0].to_string(), "(*) 30  ( ) 60  ( ) 120");
+        assert!(r.select_value(60));
+        assert_eq!(r.value(), Some(60));
+
+        let right = Event::Keyboard(KeyEvent::Right);
+        let enter = Event::Keyboard(KeyEvent::Enter);
+        Widget::<()>::handle_event(&mut r, &right, &tx, &canvas);
+        // moving the cursor does not pick anything until Enter
+        assert_eq!(r.value(), Some(60));
+        Widget::<()>::handle_event(&mut r, &enter, &tx, &canvas);
+        assert_eq!(r.value(), Some(120));
+
+        // a left click on the first option, the terminal counts from 1
+        let click = poll_event(b"\x1b[<0;2;1M").unwrap();
+        Widget::<()>::handle_event(&mut r, &click, &tx, &canvas);
+        assert_eq!(r.value(), Some(30));
+        assert_eq!(r.option_at(8, 0), Some(1));
+        assert_eq!(r.option_at(6, 0), None);
+    }
+}
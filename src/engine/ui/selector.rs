@@ -15,7 +15,8 @@ limitations under the License.
 */
 
 use super::super::enums::RenderSignal;
-use super::Border;
+use super::{Border, Rect, Widget, core::text_bounds};
+use crate::engine::input::{Event, KeyEvent};
 use crate::engine::render::{Char, ObjectData, TextType};
 use crate::engine::types::Position;
 use crate::engine::ui::style::Style;
@@ -97,7 +98,7 @@ impl Selector {
     }
 
     pub fn prev(&mut self) {
-        if !self.selected.is_none() {
+        if self.selected.is_none() {
             if self.cursor == 0 {
                 self.cursor = self.items.len() - 1;
             } else {
@@ -147,50 +148,80 @@ impl Selector {
         output
     }
 */
-    pub fn output(
-        &mut self,
-        render_tx: &Sender<RenderSignal>,
-    ) -> Result<(), SendError<RenderSignal>> {
-
-        let out: Vec<Text> = match self.direction {
+    /// Labels laid out in the selector's direction, colored for the cursor and the selection
+    fn lines(&self) -> Vec<Text> {
+        let labels = self.items.iter().enumerate().map(|(i, each)| {
+            let mut label = each.label.clone();
+            let coloring = if self.selected == Some(i) {
+                Some(&self.select_color)
+            } else if i == self.cursor && self.has_focus() {
+                Some(&self.hover_color)
+            } else {
+                None
+            };
+            if let Some(c) = coloring {
+                label.set_fg(c.foreground.clone());
+                label.set_bg(c.background.clone());
+            }
+            label
+        });
+        match self.direction {
             SelectionDirection::Horizontal => {
                 let mut t: Text = Text::from("", self.style.fg(), self.style.bg());
-                for each in self.items.iter() {
-                    t.join(each.label.clone());
+                for label in labels {
+                    t.join(label);
                     t.push(Char::new(' ', self.style.fg(), self.style.bg()));
                 }
                 vec![t]
             }
-            SelectionDirection::Vertical => {
-                let mut v = Vec::with_capacity(self.items.len());
-                for each in self.items.iter() {
-                    v.push(each.label.clone())
-                }
-                v
-            }
+            SelectionDirection::Vertical => labels.collect(),
+        }
+    }
+
+    pub fn output(
+        &mut self,
+        render_tx: &Sender<RenderSignal>,
+    ) -> Result<(), SendError<RenderSignal>> {
+        let data = ObjectData::Text {
+            pos: self.pos.clone().into(),
+            data: TextType::Single(self.lines()),
+            style: self.style.clone(),
         };
         match self.render_id.upgrade() {
             None => {
                 let arc_id = RenderUnitId::new(Layer::Ui);
                 self.render_id = Arc::downgrade(&arc_id);
-                render_tx.send(RenderSignal::Insert(
-                    arc_id,
-                    ObjectData::Text { pos: self.pos.clone().into(), data: TextType::Single(out), style: self.style.clone() },
-                ))
+                render_tx.send(RenderSignal::Insert(arc_id, data))
             }
-            Some(arc) => render_tx.send(RenderSignal::Update(
-                arc,
-                ObjectData::Text { pos: self.pos.clone().into(), data: TextType::Single(out), style: self.style.clone()},
-            )),
+            Some(arc) => render_tx.send(RenderSignal::Update(arc, data)),
         }
     }
 
+    /// Moves the selector, output has to be called for the change to show
+    pub fn set_pos(&mut self, pos: Position<i32>) {
+        self.pos = pos;
+    }
+
     pub fn toggle_select(&mut self) {
         if self.selected.is_none() {
             self.selected = Some(self.cursor);
         } else {
             self.selected = None;
         }
+        if self.has_focus() {
+            self.state = self.focus_state();
+        }
+    }
+
+    fn has_focus(&self) -> bool {
+        !matches!(self.state, SelectorState::None)
+    }
+
+    fn focus_state(&self) -> SelectorState {
+        match self.selected {
+            Some(_) => SelectorState::Selected,
+            None => SelectorState::Hovered,
+        }
     }
 
     pub fn get_selected(&self) -> Option<usize> {
@@ -198,6 +229,55 @@ impl Selector {
     }
 }
 
+/// The arrows along the selector's direction move the cursor and Enter picks or drops the item under it.
+/// Nothing is handed back, the scene reads the choice with get_selected
+impl<A> Widget<A> for Selector {
+    fn handle_event(
+        &mut self,
+        event: &Event,
+        render_tx: &Sender<RenderSignal>,
+        _canvas: &Canvas,
+    ) -> Option<A> {
+        let Event::Keyboard(key) = event else {
+            return None;
+        };
+        match (&self.direction, key) {
+            (SelectionDirection::Horizontal, KeyEvent::Left | KeyEvent::Char('a'))
+            | (SelectionDirection::Vertical, KeyEvent::Up | KeyEvent::Char('w')) => self.prev(),
+            (SelectionDirection::Horizontal, KeyEvent::Right | KeyEvent::Char('d'))
+            | (SelectionDirection::Vertical, KeyEvent::Down | KeyEvent::Char('s')) => self.next(),
+            (_, KeyEvent::Enter) => self.toggle_select(),
+            _ => return None,
+        }
+        let _ = self.output(render_tx);
+        None
+    }
+
+    fn output(
+        &mut self,
+        render_tx: &Sender<RenderSignal>,
+        _canvas: &Canvas,
+    ) -> Result<(), SendError<RenderSignal>> {
+        Selector::output(self, render_tx)
+    }
+
+    fn set_focus(&mut self, focus: bool) {
+        self.state = if focus {
+            self.focus_state()
+        } else {
+            SelectorState::None
+        };
+    }
+
+    fn is_focused(&self) -> bool {
+        self.has_focus()
+    }
+
+    fn bounds(&self, canvas: &Canvas) -> Rect {
+        text_bounds(self.pos.clone(), &self.style, &self.lines(), canvas)
+    }
+}
+
 /*
 #[cfg(test)]
 mod test {
@@ -0,0 +1,258 @@
+/*
+Copyright 2025 Luke Maple
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+you may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+use super::super::enums::RenderSignal;
+use super::{
+    Rect, Widget,
+    core::{left_click, text_bounds},
+    style::{Coloring, FULL_BLOCK, LIGHT_BLOCK, Style},
+};
+use crate::engine::{
+    input::{Event, KeyEvent},
+    render::{Canvas, Char, Layer, ObjectData, RenderUnitId, Text, TextType},
+    types::Position,
+};
+use std::sync::mpsc::SendError;
+use std::sync::{Arc, Weak, mpsc::Sender};
+
+/// Value in a range shown as a filled track, drawn as `label ████░░░░ value`.
+/// A read only slider can not take focus and works as a gauge
+#[derive(Debug)]
+pub struct Slider {
+    pub render_id: Weak<RenderUnitId>,
+    pos: Position<i32>,
+    style: Style,
+    label: Text,
+    hover_color: Coloring,
+    min: f64,
+    max: f64,
+    step: f64,
+    value: f64,
+    /// cells in the track
+    width: usize,
+    read_only: bool,
+    focused: bool,
+}
+
+impl Slider {
+    pub fn new(x: i32, y: i32, style: Style, label: Text, min: f64, max: f64) -> Self {
+        Self {
+            render_id: Weak::new(),
+            pos: Position { x, y },
+            hover_color: Coloring::new(*style.bg(), *style.fg()),
+            style,
+            label,
+            min: min.min(max),
+            max: max.max(min),
+            step: (max - min).abs() / 10.0,
+            value: min.min(max),
+            width: 10,
+            read_only: false,
+            focused: false,
+        }
+    }
+
+    /// How far the arrow keys move the value
+    pub fn set_step(mut self, step: f64) -> Self {
+        self.step = step.abs();
+        self
+    }
+
+    pub fn set_width(mut self, width: usize) -> Self {
+        self.width = width.max(1);
+        self
+    }
+
+    pub fn set_hover_color(mut self, color: Coloring) -> Self {
+        self.hover_color = color;
+        self
+    }
+
+    pub fn set_read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// Kept inside the range, output has to be called for the change to show
+    pub fn set_value(&mut self, value: f64) {
+        self.value = value.clamp(self.min, self.max);
+    }
+
+    /// Moves the slider, output has to be called for the change to show
+    pub fn set_pos(&mut self, pos: Position<i32>) {
+        self.pos = pos;
+    }
+
+    /// Part of the range covered, 0 to 1
+    fn fraction(&self) -> f64 {
+        if self.max == self.min {
+            return 1.0;
+        }
+        (self.value - self.min) / (self.max - self.min)
+    }
+
+    /// Screen column the track starts at
+    fn track_x(&self) -> i32 {
+        let mut x = self.pos.x + self.label.len() as i32 + 1;
+        if let Some(b) = &self.style.border {
+            x += 1 + b.l_pad() as i32;
+        }
+        x
+    }
+
+    fn line(&self) -> Text {
+        let (fg, bg) = (self.style.fg(), self.style.bg());
+        let mut line = self.label.clone();
+        line.push(Char::new(' ', fg, bg));
+        let filled = (self.fraction() * self.width as f64).round() as usize;
+        let mut track = Text::new();
+        for i in 0..self.width {
+            let c = if i < filled { FULL_BLOCK } else { LIGHT_BLOCK };
+            track.push(Char::new(c, fg, bg));
+        }
+        if self.focused {
+            track.set_fg(self.hover_color.foreground);
+            track.set_bg(self.hover_color.background);
+        }
+        line.join(track);
+        line.join(Text::from(format!(" {}", self.value_text()), fg, bg));
+        line
+    }
+
+    /// Value with as many decimals as the step needs
+    fn value_text(&self) -> String {
+        let step = format!("{}", self.step);
+        let decimals = step.split_once('.').map_or(0, |(_, d)| d.len());
+        format!("{:.*}", decimals, self.value)
+    }
+
+    pub fn output(&mut self, render_tx: &Sender<RenderSignal>) -> Result<(), SendError<RenderSignal>> {
+        let data = ObjectData::Text {
+            pos: self.pos.into(),
+            data: TextType::Single(vec![self.line()]),
+            style: self.style.clone(),
+        };
+        match self.render_id.upgrade() {
+            None => {
+                let arc_id = RenderUnitId::new(Layer::Ui);
+                self.render_id = Arc::downgrade(&arc_id);
+                render_tx.send(RenderSignal::Insert(arc_id, data))
+            }
+            Some(arc) => render_tx.send(RenderSignal::Update(arc, data)),
+        }
+    }
+}
+
+/// Left and right move the value by a step, a click on the track jumps to that spot.
+/// Nothing is handed back, the scene reads the number with value
+impl<A> Widget<A> for Slider {
+    fn handle_event(
+        &mut self,
+        event: &Event,
+        render_tx: &Sender<RenderSignal>,
+        _canvas: &Canvas,
+    ) -> Option<A> {
+        if self.read_only {
+            return None;
+        }
+        let before = self.value;
+        match event {
+            Event::Keyboard(KeyEvent::Right | KeyEvent::Char('d')) => {
+                self.set_value(self.value + self.step)
+            }
+            Event::Keyboard(KeyEvent::Left | KeyEvent::Char('a')) => {
+                self.set_value(self.value - self.step)
+            }
+            event => {
+                let (x, _) = left_click(event)?;
+                let cell = x - self.track_x();
+                if cell < 0 || cell >= self.width as i32 {
+                    return None;
+                }
+                // the last cell is the top of the range
+                let fraction = cell as f64 / (self.width - 1).max(1) as f64;
+                let mut value = self.min + fraction * (self.max - self.min);
+                if self.step > 0.0 {
+                    value = self.min + ((value - self.min) / self.step).round() * self.step;
+                }
+                self.set_value(value);
+            }
+        }
+        if self.value != before {
+            let _ = self.output(render_tx);
+        }
+        None
+    }
+
+    fn output(
+        &mut self,
+        render_tx: &Sender<RenderSignal>,
+        _canvas: &Canvas,
+    ) -> Result<(), SendError<RenderSignal>> {
+        Slider::output(self, render_tx)
+    }
+
+    fn focusable(&self) -> bool {
+        !self.read_only
+    }
+
+    fn set_focus(&mut self, focus: bool) {
+        self.focused = focus;
+    }
+
+    fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    fn bounds(&self, canvas: &Canvas) -> Rect {
+        text_bounds(self.pos, &self.style, &[self.line()], canvas)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::engine::input::poll_event;
+
+    #[test]
+    fn slider_test() {
+        let (tx, _rx) = std::sync::mpsc::channel();
+        let canvas = Canvas::new(60, 10);
+        let mut s = Slider::new(0, 0, Style::default(), Text::from("Sea", 7u8, 0u8), -1.0, 1.0)
+            .set_step(0.5)
+            .set_width(4);
+        assert_eq!(s.line().to_string(), "Sea ░░░░ -1.0");
+        let right = Event::Keyboard(KeyEvent::Right);
+        for _ in 0..3 {
+            Widget::<()>::handle_event(&mut s, &right, &tx, &canvas);
+        }
+        assert_eq!(s.value(), 0.5);
+        assert_eq!(s.line().to_string(), "Sea ███░ 0.5");
+        // the track starts at column 4, the terminal counts from 1
+        let click = poll_event(b"\x1b[<0;6;1M").unwrap();
+        Widget::<()>::handle_event(&mut s, &click, &tx, &canvas);
+        assert_eq!(s.value(), -0.5);
+
+        let mut gauge = Slider::new(0, 0, Style::default(), Text::new(), 0.0, 10.0).set_read_only(true);
+        assert!(!Widget::<()>::focusable(&gauge));
+        Widget::<()>::handle_event(&mut gauge, &right, &tx, &canvas);
+        assert_eq!(gauge.value(), 0.0);
+    }
+}
@@ -17,12 +17,19 @@ limitations under the License.
 pub const CLEAR_COLORS: &str = "\x1b[0m";
 pub const CURSOR_HOME: &str = "\x1b[H";
 
+/// Bright black, shows as gray on most terminals. Used for widgets that can not be used
+pub const DISABLED_FG: u8 = 8;
+
 pub const FULL_BLOCK: char = '\u{2588}'; // █
 pub const DARK_BLOCK: char = '\u{2593}'; // ▓
 pub const MEDIUM_BLOCK: char = '\u{2592}'; // ▓
 pub const LIGHT_BLOCK: char = '\u{2591}'; // ░
 pub const TOP_BLOCK: char = '\u{2580}'; // ▀
 pub const BOTTOM_BLOCK: char = '\u{2584}'; // ▄
+/// Left aligned blocks from one eighth of a cell wide up to a full cell
+pub const H_EIGHTHS: [char; 8] = ['\u{258F}', '\u{258E}', '\u{258D}', '\u{258C}', '\u{258B}', '\u{258A}', '\u{2589}', FULL_BLOCK]; // ▏▎▍▌▋▊▉█
+/// Bottom aligned blocks from one eighth of a cell tall up to a full cell
+pub const V_EIGHTHS: [char; 8] = ['\u{2581}', '\u{2582}', '\u{2583}', '\u{2584}', '\u{2585}', '\u{2586}', '\u{2587}', FULL_BLOCK]; // ▁▂▃▄▅▆▇█
 
 pub const HEAVY_H_LINE: char = '\u{2501}'; // ━
 pub const HEAVY_V_LINE: char = '\u{2503}'; // ┃
@@ -25,6 +25,8 @@ pub struct Style {
     pub border: Option<Border>,
     pub alignment: Alignment,
     pub color: Coloring,
+    #[serde(default)]
+    pub overflow: Overflow,
 }
 
 impl Style {
@@ -41,6 +43,7 @@ impl Style {
             border,
             alignment,
             color,
+            overflow: Overflow::default(),
         }
     }
 
@@ -83,6 +86,12 @@ impl Style {
         self
     }
 
+    /// Lines are laid out again whenever the canvas changes size
+    pub fn set_overflow(mut self, overflow: Overflow) -> Self {
+        self.overflow = overflow;
+        self
+    }
+
     pub fn justify(&self) -> Justify {
         self.alignment.justify
     }
@@ -91,6 +100,10 @@ impl Style {
         self.alignment.align
     }
 
+    pub fn overflow(&self) -> Overflow {
+        self.overflow
+    }
+
     pub fn fg(&self) -> &Foreground {
         &self.color.foreground
     }
@@ -115,6 +128,7 @@ impl Default for Style {
             border: None,
             alignment: Alignment::default(),
             color: Coloring::default(),
+            overflow: Overflow::default(),
         }
     }
 }
@@ -139,6 +139,20 @@ pub enum Align {
     Center,
 }
 
+/// What happens to a line that is longer than the box it is in
+#[derive(Debug, Copy, Clone, Default, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Overflow {
+    /// cut off at the edge
+    #[default]
+    Clip,
+    /// cut off with an ellipsis in the last cell
+    Ellipsis,
+    /// broken at the last space that fits, words longer than a line are split
+    WordWrap,
+    /// broken at the edge wherever it falls
+    CharWrap,
+}
+
 #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct Size {
     pub width: Option<Measure>,
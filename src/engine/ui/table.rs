@@ -0,0 +1,644 @@
+/*
+Copyright 2025 Luke Maple
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+you may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+use super::super::{
+    Error, ErrorKind,
+    enums::RenderSignal,
+};
+use super::{
+    Rect, Widget,
+    core::left_click,
+    style::{Coloring, FULL_BLOCK, Justify, LIGHT_BLOCK, Measure, Style},
+};
+use crate::engine::{
+    input::{Event, KeyEvent},
+    render::{Canvas, Char, Layer, ObjectData, RenderUnitId, Text, TextType},
+    types::Position,
+};
+use std::cmp::Ordering;
+use std::sync::mpsc::SendError;
+use std::sync::{Arc, Weak, mpsc::Sender};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnKind {
+    Text,
+    Int,
+    Float,
+    Bool,
+}
+
+/// Value in one cell of a table
+#[derive(Debug, Clone, PartialEq)]
+pub enum Cell {
+    Text(String),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+}
+
+impl Cell {
+    pub fn kind(&self) -> ColumnKind {
+        match self {
+            Cell::Text(_) => ColumnKind::Text,
+            Cell::Int(_) => ColumnKind::Int,
+            Cell::Float(_) => ColumnKind::Float,
+            Cell::Bool(_) => ColumnKind::Bool,
+        }
+    }
+
+    fn compare(&self, other: &Cell) -> Ordering {
+        match (self, other) {
+            (Cell::Text(a), Cell::Text(b)) => a.to_lowercase().cmp(&b.to_lowercase()),
+            (Cell::Int(a), Cell::Int(b)) => a.cmp(b),
+            (Cell::Float(a), Cell::Float(b)) => a.partial_cmp(b).unwrap_or(Ordering::Equal),
+            (Cell::Bool(a), Cell::Bool(b)) => a.cmp(b),
+            (a, b) => a.to_string().cmp(&b.to_string()),
+        }
+    }
+}
+
+impl std::fmt::Display for Cell {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Cell::Text(s) => write!(f, "{}", s),
+            Cell::Int(i) => write!(f, "{}", i),
+            Cell::Float(v) => write!(f, "{:.2}", v),
+            Cell::Bool(b) => write!(f, "{}", if *b { "yes" } else { "no" }),
+        }
+    }
+}
+
+impl From<&str> for Cell {
+    fn from(value: &str) -> Self {
+        Cell::Text(value.to_string())
+    }
+}
+
+impl From<String> for Cell {
+    fn from(value: String) -> Self {
+        Cell::Text(value)
+    }
+}
+
+impl From<i64> for Cell {
+    fn from(value: i64) -> Self {
+        Cell::Int(value)
+    }
+}
+
+impl From<f64> for Cell {
+    fn from(value: f64) -> Self {
+        Cell::Float(value)
+    }
+}
+
+impl From<bool> for Cell {
+    fn from(value: bool) -> Self {
+        Cell::Bool(value)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Column {
+    pub title: String,
+    pub kind: ColumnKind,
+    /// Percent is of the width left over once the fixed columns are taken out
+    pub width: Measure,
+    pub justify: Justify,
+}
+
+impl Column {
+    /// Numbers line up on the right, everything else on the left
+    pub fn new(title: impl Into<String>, kind: ColumnKind, width: Measure) -> Self {
+        let justify = match kind {
+            ColumnKind::Int | ColumnKind::Float => Justify::Right,
+            ColumnKind::Text | ColumnKind::Bool => Justify::Left,
+        };
+        Self {
+            title: title.into(),
+            kind,
+            width,
+            justify,
+        }
+    }
+
+    pub fn set_justify(mut self, justify: Justify) -> Self {
+        self.justify = justify;
+        self
+    }
+}
+
+#[derive(Debug)]
+struct Row<K> {
+    key: K,
+    cells: Vec<Cell>,
+}
+
+/// Rows of typed cells under a header. Rows can be sorted by any column and narrowed down
+/// with a filter typed after `/`, the cursor row is handed back when it is activated
+#[derive(Debug)]
+pub struct Table<K> {
+    pub render_id: Weak<RenderUnitId>,
+    pos: Position<i32>,
+    style: Style,
+    columns: Vec<Column>,
+    rows: Vec<Row<K>>,
+    /// indices into rows that pass the filter, in sorted order
+    view: Vec<usize>,
+    /// column and whether it goes up
+    sort: Option<(usize, bool)>,
+    filter: String,
+    filtering: bool,
+    cursor: usize,
+    scroll: usize,
+    hover_color: Coloring,
+    header_color: Coloring,
+    focused: bool,
+    /// rows that fit, updated every output
+    page: usize,
+}
+
+impl<K> Table<K> {
+    pub fn new(x: i32, y: i32, style: Style, columns: Vec<Column>) -> Self {
+        Self {
+            render_id: Weak::new(),
+            pos: Position { x, y },
+            hover_color: Coloring::new(*style.bg(), *style.fg()),
+            header_color: Coloring::new(*style.fg(), *style.bg()),
+            style,
+            columns,
+            rows: Vec::new(),
+            view: Vec::new(),
+            sort: None,
+            filter: String::new(),
+            filtering: false,
+            cursor: 0,
+            scroll: 0,
+            focused: false,
+            page: 1,
+        }
+    }
+
+    pub fn set_hover_color(mut self, color: Coloring) -> Self {
+        self.hover_color = color;
+        self
+    }
+
+    pub fn set_header_color(mut self, color: Coloring) -> Self {
+        self.header_color = color;
+        self
+    }
+
+    /// Moves the table, output has to be called for the change to show
+    pub fn set_pos(&mut self, pos: Position<i32>) {
+        self.pos = pos;
+    }
+
+    /// Adds a row, every cell has to match its column's kind
+    pub fn push(&mut self, key: K, cells: Vec<Cell>) -> Result<(), Error> {
+        if cells.len() != self.columns.len() {
+            return Err(Error::new(
+                format!("row has {} cells but the table has {} columns", cells.len(), self.columns.len()),
+                ErrorKind::InvalidData,
+            ));
+        }
+        for (cell, col) in cells.iter().zip(self.columns.iter()) {
+            if cell.kind() != col.kind {
+                return Err(Error::new(
+                    format!("column {} holds {:?} but got {:?}", col.title, col.kind, cell.kind()),
+                    ErrorKind::InvalidData,
+                ));
+            }
+        }
+        self.rows.push(Row { key, cells });
+        self.refresh();
+        Ok(())
+    }
+
+    pub fn clear(&mut self) {
+        self.rows.clear();
+        self.refresh();
+    }
+
+    /// Every row, filtered out or not
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Rows that pass the filter
+    pub fn visible_len(&self) -> usize {
+        self.view.len()
+    }
+
+    pub fn sort_by(&mut self, column: usize, ascending: bool) {
+        if column < self.columns.len() {
+            self.sort = Some((column, ascending));
+            self.refresh();
+        }
+    }
+
+    /// Sorts by the column, or flips the order when it is already sorted by it
+    pub fn toggle_sort(&mut self, column: usize) {
+        match self.sort {
+            Some((c, ascending)) if c == column => self.sort_by(column, !ascending),
+            _ => self.sort_by(column, true),
+        }
+    }
+
+    pub fn sort(&self) -> Option<(usize, bool)> {
+        self.sort
+    }
+
+    /// Keeps the rows with a cell containing the text, case is ignored
+    pub fn set_filter(&mut self, filter: impl Into<String>) {
+        self.filter = filter.into();
+        self.refresh();
+    }
+
+    pub fn filter(&self) -> &str {
+        &self.filter
+    }
+
+    /// Key of the row under the cursor
+    pub fn selected(&self) -> Option<&K> {
+        self.view.get(self.cursor).map(|i| &self.rows[*i].key)
+    }
+
+    pub fn cursor_up(&mut self, amount: usize) {
+        self.cursor = self.cursor.saturating_sub(amount);
+        self.follow_cursor();
+    }
+
+    pub fn cursor_down(&mut self, amount: usize) {
+        self.cursor = (self.cursor + amount).min(self.view.len().saturating_sub(1));
+        self.follow_cursor();
+    }
+
+    /// Rebuilds the view after the rows, sort or filter changed, the cursor stays on its row when it can
+    fn refresh(&mut self) {
+        let before = self.view.get(self.cursor).copied();
+        let needle = self.filter.to_lowercase();
+        self.view = (0..self.rows.len())
+            .filter(|i| {
+                needle.is_empty()
+                    || self.rows[*i]
+                        .cells
+                        .iter()
+                        .any(|c| c.to_string().to_lowercase().contains(&needle))
+            })
+            .collect();
+        if let Some((col, ascending)) = self.sort {
+            let rows = &self.rows;
+            self.view.sort_by(|a, b| {
+                let order = rows[*a].cells[col].compare(&rows[*b].cells[col]);
+                if ascending { order } else { order.reverse() }
+            });
+        }
+        self.cursor = before
+            .and_then(|r| self.view.iter().position(|i| *i == r))
+            .unwrap_or(0);
+        self.follow_cursor();
+    }
+
+    /// Scrolls just enough to keep the cursor row on screen
+    fn follow_cursor(&mut self) {
+        let page = self.page.max(1);
+        if self.cursor < self.scroll {
+            self.scroll = self.cursor;
+        } else if self.cursor >= self.scroll + page {
+            self.scroll = self.cursor + 1 - page;
+        }
+        self.scroll = self.scroll.min(self.view.len().saturating_sub(page));
+    }
+
+    /// Whole width and the rows that fit under the header
+    fn size(&self, canvas: &Canvas) -> (usize, usize) {
+        let free_w = canvas.width.saturating_sub(self.pos.x.max(0) as usize);
+        let free_h = canvas.height.saturating_sub(self.pos.y.max(0) as usize);
+        let width = self.style.width().map_or(free_w, |w| w.get(canvas.width));
+        let height = self.style.height().map_or(free_h, |h| h.get(canvas.height));
+        // the header and the filter line
+        (width, height.saturating_sub(2).max(1))
+    }
+
+    /// Width of every column, a cell of space goes between columns and one is kept for the scrollbar
+    fn column_widths(&self, width: usize) -> Vec<usize> {
+        let gaps = self.columns.len().saturating_sub(1) + 1;
+        let fixed: usize = self
+            .columns
+            .iter()
+            .map(|c| match c.width {
+                Measure::Cell(w) => w as usize,
+                Measure::Percent(_) => 0,
+            })
+            .sum();
+        let left = width.saturating_sub(fixed + gaps);
+        self.columns
+            .iter()
+            .map(|c| match c.width {
+                Measure::Cell(w) => w as usize,
+                Measure::Percent(_) => c.width.get(left),
+            })
+            .collect()
+    }
+
+    fn lines(&mut self, canvas: &Canvas) -> Vec<Text> {
+        let (width, page) = self.size(canvas);
+        self.page = page;
+        self.follow_cursor();
+        let widths = self.column_widths(width);
+        let (fg, bg) = (self.style.fg(), self.style.bg());
+        let blank = Char::new(' ', fg, bg);
+        let mut out = Vec::with_capacity(page + 2);
+
+        let mut header = Text::new();
+        for (i, (col, w)) in self.columns.iter().zip(widths.iter()).enumerate() {
+            let arrow = match self.sort {
+                Some((c, true)) if c == i => " \u{25b2}",
+                Some((c, false)) if c == i => " \u{25bc}",
+                _ => "",
+            };
+            header.join(fit(&format!("{}{}", col.title, arrow), *w, Justify::Left, fg, bg));
+            header.push(blank);
+        }
+        header.set_fg(self.header_color.foreground);
+        header.set_bg(self.header_color.background);
+        out.push(header);
+
+        let bar = scrollbar(self.view.len(), page, self.scroll);
+        for line in 0..page {
+            let mut text = Text::new();
+            match self.view.get(self.scroll + line) {
+                Some(r) => {
+                    for (i, cell) in self.rows[*r].cells.iter().enumerate() {
+                        let col = &self.columns[i];
+                        text.join(fit(&cell.to_string(), widths[i], col.justify, fg, bg));
+                        if i + 1 < self.columns.len() {
+                            text.push(blank);
+                        }
+                    }
+                    if self.focused && self.scroll + line == self.cursor {
+                        text.set_fg(self.hover_color.foreground);
+                        text.set_bg(self.hover_color.background);
+                    }
+                }
+                None => {
+                    let w: usize = widths.iter().sum::<usize>() + self.columns.len().saturating_sub(1);
+                    text.join(fit("", w, Justify::Left, fg, bg));
+                }
+            }
+            text.push(match &bar {
+                Some((start, len)) if line >= *start && line < start + len => Char::new(FULL_BLOCK, fg, bg),
+                Some(_) => Char::new(LIGHT_BLOCK, fg, bg),
+                None => blank,
+            });
+            out.push(text);
+        }
+
+        let status = if self.filtering || !self.filter.is_empty() {
+            format!("/{}{}", self.filter, if self.filtering { "_" } else { "" })
+        } else {
+            String::new()
+        };
+        out.push(fit(&status, width, Justify::Left, fg, bg));
+        out
+    }
+
+    pub fn output(
+        &mut self,
+        render_tx: &Sender<RenderSignal>,
+        canvas: &Canvas,
+    ) -> Result<(), SendError<RenderSignal>> {
+        let data = ObjectData::Text {
+            pos: self.pos.into(),
+            data: TextType::Single(self.lines(canvas)),
+            style: self.style.clone(),
+        };
+        match self.render_id.upgrade() {
+            None => {
+                let arc_id = RenderUnitId::new(Layer::Ui);
+                self.render_id = Arc::downgrade(&arc_id);
+                render_tx.send(RenderSignal::Insert(arc_id, data))
+            }
+            Some(arc) => render_tx.send(RenderSignal::Update(arc, data)),
+        }
+    }
+
+    /// Column whose header is drawn at the screen column
+    fn column_at(&self, x: i32, canvas: &Canvas) -> Option<usize> {
+        let (width, _) = self.size(canvas);
+        let mut left = self.pos.x;
+        for (i, w) in self.column_widths(width).into_iter().enumerate() {
+            if x >= left && x < left + w as i32 {
+                return Some(i);
+            }
+            left += w as i32 + 1;
+        }
+        None
+    }
+
+    /// Edits the filter while it is being typed, returns false when the key means nothing
+    fn filter_key(&mut self, key: &KeyEvent) -> bool {
+        match key {
+            KeyEvent::Char(c) => {
+                self.filter.push(*c);
+                self.refresh();
+            }
+            KeyEvent::Backspace => {
+                self.filter.pop();
+                self.refresh();
+            }
+            KeyEvent::Enter => self.filtering = false,
+            KeyEvent::Escape => {
+                self.filtering = false;
+                self.set_filter("");
+            }
+            _ => return false,
+        }
+        true
+    }
+}
+
+/// Text cut or padded to exactly width cells
+fn fit(s: &str, width: usize, justify: Justify, fg: impl Into<u8>, bg: impl Into<u8>) -> Text {
+    let mut s: String = s.chars().take(width).collect();
+    let pad = width - s.chars().count();
+    let (l, r) = match justify {
+        Justify::Left => (0, pad),
+        Justify::Right => (pad, 0),
+        Justify::Center => (pad / 2, pad - pad / 2),
+    };
+    s = format!("{}{}{}", " ".repeat(l), s, " ".repeat(r));
+    Text::from(s, fg, bg)
+}
+
+/// Start and length of the thumb in rows, None when everything fits
+fn scrollbar(total: usize, page: usize, scroll: usize) -> Option<(usize, usize)> {
+    if total <= page || page == 0 {
+        return None;
+    }
+    let len = (page * page / total).clamp(1, page);
+    let start = scroll * (page - len) / (total - page);
+    Some((start, len))
+}
+
+/// Up and down move the cursor and Enter hands back the key of its row.
+/// The number keys sort by that column, pressing it again flips the order, and so does clicking a
+/// header. `/` starts a filter that narrows the rows with every key until Enter, Escape clears it
+impl<K: Clone> Widget<K> for Table<K> {
+    fn handle_event(
+        &mut self,
+        event: &Event,
+        render_tx: &Sender<RenderSignal>,
+        canvas: &Canvas,
+    ) -> Option<K> {
+        let mut action = None;
+        if let Some((x, y)) = left_click(event) {
+            let row = y - self.pos.y;
+            if row == 0 {
+                self.toggle_sort(self.column_at(x, canvas)?);
+            } else if row > 0 && (row as usize) <= self.page {
+                let index = self.scroll + row as usize - 1;
+                if index >= self.view.len() {
+                    return None;
+                }
+                self.cursor = index;
+            } else {
+                return None;
+            }
+        } else {
+            let Event::Keyboard(key) = event else {
+                return None;
+            };
+            if self.filtering {
+                if !self.filter_key(key) {
+                    return None;
+                }
+            } else {
+                match key {
+                    KeyEvent::Up | KeyEvent::Char('w') => self.cursor_up(1),
+                    KeyEvent::Down | KeyEvent::Char('s') => self.cursor_down(1),
+                    KeyEvent::Char('/') => self.filtering = true,
+                    KeyEvent::Char(c @ '1'..='9') => {
+                        let column = *c as usize - '1' as usize;
+                        if column >= self.columns.len() {
+                            return None;
+                        }
+                        self.toggle_sort(column);
+                    }
+                    KeyEvent::Enter => action = self.selected().cloned(),
+                    _ => return None,
+                }
+            }
+        }
+        let _ = self.output(render_tx, canvas);
+        action
+    }
+
+    fn output(
+        &mut self,
+        render_tx: &Sender<RenderSignal>,
+        canvas: &Canvas,
+    ) -> Result<(), SendError<RenderSignal>> {
+        Table::output(self, render_tx, canvas)
+    }
+
+    fn set_focus(&mut self, focus: bool) {
+        self.focused = focus;
+    }
+
+    fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    fn bounds(&self, canvas: &Canvas) -> Rect {
+        let (width, page) = self.size(canvas);
+        Rect::new(self.pos.x, self.pos.y, width, page + 2)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn dwarves() -> Table<usize> {
+        let mut t = Table::new(
+            0,
+            0,
+            Style::default().set_height(Measure::Cell(4)),
+            vec![
+                Column::new("Name", ColumnKind::Text, Measure::Percent(100)),
+                Column::new("Age", ColumnKind::Int, Measure::Cell(3)),
+            ],
+        );
+        for (i, (name, age)) in [("Urist", 54), ("Doren", 120), ("Asmel", 33), ("Kadol", 77)]
+            .into_iter()
+            .enumerate()
+        {
+            t.push(i, vec![name.into(), Cell::Int(age)]).unwrap();
+        }
+        t
+    }
+
+    #[test]
+    fn table_sort_filter_test() {
+        let (tx, _rx) = std::sync::mpsc::channel();
+        let canvas = Canvas::new(14, 10);
+        let mut t = dwarves();
+        assert!(t.push(9, vec!["Bad".into(), "row".into()]).is_err());
+        let key = |c: char| Event::Keyboard(KeyEvent::Char(c));
+        t.lines(&canvas);
+
+        // sort by age, the cursor stays on Urist
+        Widget::<usize>::handle_event(&mut t, &key('2'), &tx, &canvas);
+        assert_eq!(t.sort(), Some((1, true)));
+        assert_eq!(t.selected(), Some(&0));
+        assert_eq!(t.cursor, 1);
+        let lines: Vec<String> = t.lines(&canvas).iter().map(|l| l.to_string()).collect();
+        // the arrow does not fit in the age column
+        assert_eq!(lines[0], "Name      Age ");
+        assert_eq!(lines[1], "Asmel      33█");
+        // two rows fit in four lines so the scrollbar shows
+        assert_eq!(lines.len(), 4);
+
+        Widget::<usize>::handle_event(&mut t, &key('2'), &tx, &canvas);
+        assert_eq!(t.view, vec![1, 3, 0, 2]);
+
+        for c in "/or".chars() {
+            Widget::<usize>::handle_event(&mut t, &key(c), &tx, &canvas);
+        }
+        // Doren only, Urist has no "or"
+        assert_eq!(t.visible_len(), 1);
+        let enter = Event::Keyboard(KeyEvent::Enter);
+        assert_eq!(Widget::<usize>::handle_event(&mut t, &enter, &tx, &canvas), None);
+        assert_eq!(Widget::<usize>::handle_event(&mut t, &enter, &tx, &canvas), Some(1));
+    }
+
+    #[test]
+    fn table_scroll_test() {
+        let canvas = Canvas::new(14, 10);
+        let mut t = dwarves();
+        t.lines(&canvas);
+        t.cursor_down(3);
+        assert_eq!((t.cursor, t.scroll), (3, 2));
+        t.cursor_up(1);
+        assert_eq!(t.scroll, 2);
+        t.cursor_up(1);
+        assert_eq!(t.scroll, 1);
+        assert_eq!(scrollbar(4, 2, 2), Some((1, 1)));
+        assert_eq!(scrollbar(2, 2, 0), None);
+    }
+}
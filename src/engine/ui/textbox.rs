@@ -14,11 +14,11 @@ See the License for the specific language governing permissions and
 limitations under the License.
 */
 
-use super::style::Style;
+use super::{Rect, Widget, core::text_bounds, style::Style};
 use crate::engine::{
     enums::RenderSignal,
-    input::KeyEvent,
-    render::{Canvas, Char, Layer, Object, ObjectData, RenderUnitId, Text, TextType},
+    input::{Event, KeyEvent, OtherEvent},
+    render::{Attributes, Canvas, Char, Layer, Object, ObjectData, RenderUnitId, Text, TextType},
     types::Position,
     ui::style::Measure,
 };
@@ -27,6 +27,17 @@ use std::sync::{
     mpsc::{SendError, Sender},
 };
 
+/// Undo steps kept before the oldest is dropped
+const HISTORY_LEN: usize = 100;
+
+/// Kind of the last change, runs of the same kind are undone together
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Edit {
+    Typing,
+    Deleting,
+    Other,
+}
+
 #[derive(Debug)]
 pub struct TextArea {
     pub render_id: Weak<RenderUnitId>,
@@ -36,6 +47,14 @@ pub struct TextArea {
     value: Vec<Text>,
     place_holder: Option<String>,
     cursor: Position<usize>,
+    /// other end of the selection, the cursor is the end that moves
+    anchor: Option<Position<usize>>,
+    history: Vec<(Vec<Text>, Position<usize>)>,
+    redo: Vec<(Vec<Text>, Position<usize>)>,
+    last_edit: Option<Edit>,
+    max_len: Option<usize>,
+    single_line: bool,
+    focused: bool,
 }
 
 impl TextArea {
@@ -54,25 +73,47 @@ impl TextArea {
             style,
             place_holder: placeholder,
             cursor: Position {x: 0, y: 0},
+            anchor: None,
+            history: Vec::new(),
+            redo: Vec::new(),
+            last_edit: None,
+            max_len: None,
+            single_line: false,
+            focused: false,
         }
     }
 
+    /// Most characters the area holds, line breaks count as one. Typing past it is ignored
+    /// and pastes are cut short
+    pub fn set_max_len(mut self, max_len: usize) -> Self {
+        self.max_len = Some(max_len);
+        self
+    }
+
+    /// Enter is ignored and pasted line breaks become spaces. Without a max length the
+    /// text is kept to the width of the area
+    pub fn set_single_line(mut self, single_line: bool) -> Self {
+        self.single_line = single_line;
+        self
+    }
+
     pub fn max_len_value(&self, canvas: &Canvas) -> usize {
         if let Some(w) = self.style.size.width {
             if let Some(b) = self.style.border.as_ref() {
-                w.get(canvas.width) as usize
-                    - (self.style.border.as_ref().unwrap().width()
-                        + self.style.border.as_ref().unwrap().l_pad()
-                        + self.style.border.as_ref().unwrap().r_pad()
-                        + 1) as usize
+                w.get(canvas.width).saturating_sub(b.width() + b.l_pad() + b.r_pad() + 1)
             } else {
-                w.get(canvas.width) as usize - 1
+                w.get(canvas.width).saturating_sub(1)
             }
         } else {
-            canvas.width - (self.position.x as usize + 1)
+            canvas.width.saturating_sub(self.position.x.max(0) as usize + 1)
         }
     }
 
+    /// Moves the area, output has to be called for the change to show
+    pub fn set_pos(&mut self, pos: Position<i32>) {
+        self.position = pos;
+    }
+
     pub fn get_value(&self) -> Vec<String> {
         let mut v = vec![];
         for each in self.value.iter() {
@@ -81,6 +122,181 @@ impl TextArea {
         v
     }
 
+    /// Selected text with lines joined by \n, None when nothing is selected
+    pub fn selected_text(&self) -> Option<String> {
+        let (start, end) = self.selection()?;
+        let mut out = String::new();
+        for y in start.y..=end.y {
+            let line = self.value[y].to_string();
+            let from = if y == start.y { start.x } else { 0 };
+            let to = if y == end.y { end.x } else { self.value[y].len() };
+            out.extend(line.chars().skip(from).take(to - from));
+            if y != end.y {
+                out.push('\n');
+            }
+        }
+        Some(out)
+    }
+
+    /// Start and end of the selection in reading order
+    fn selection(&self) -> Option<(Position<usize>, Position<usize>)> {
+        let anchor = self.anchor?;
+        let (a, c) = ((anchor.y, anchor.x), (self.cursor.y, self.cursor.x));
+        if a == c {
+            None
+        } else if a < c {
+            Some((anchor, self.cursor))
+        } else {
+            Some((self.cursor, anchor))
+        }
+    }
+
+    fn char_count(&self) -> usize {
+        self.value.iter().map(|l| l.len()).sum::<usize>() + self.value.len() - 1
+    }
+
+    fn limit(&self, canvas: &Canvas) -> Option<usize> {
+        match self.max_len {
+            Some(max) => Some(max),
+            None if self.single_line => Some(self.max_len_value(canvas)),
+            None => None,
+        }
+    }
+
+    /// Saves the text for undo, a run of the same kind of edit shares one step
+    fn snapshot(&mut self, edit: Edit) {
+        if edit != Edit::Other && self.last_edit == Some(edit) {
+            return;
+        }
+        self.last_edit = Some(edit);
+        self.history.push((self.value.clone(), self.cursor));
+        if self.history.len() > HISTORY_LEN {
+            self.history.remove(0);
+        }
+        self.redo.clear();
+    }
+
+    pub fn undo(&mut self) -> bool {
+        match self.history.pop() {
+            Some((value, cursor)) => {
+                self.redo.push((std::mem::replace(&mut self.value, value), self.cursor));
+                self.cursor = cursor;
+                self.anchor = None;
+                self.last_edit = None;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn redo(&mut self) -> bool {
+        match self.redo.pop() {
+            Some((value, cursor)) => {
+                self.history.push((std::mem::replace(&mut self.value, value), self.cursor));
+                self.cursor = cursor;
+                self.anchor = None;
+                self.last_edit = None;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Removes the selected text and puts the cursor where it started
+    fn delete_selection(&mut self) -> bool {
+        let Some((start, end)) = self.selection() else {
+            self.anchor = None;
+            return false;
+        };
+        let (mut left, _) = self.value[start.y].clone().split(start.x);
+        let (_, right) = self.value[end.y].clone().split(end.x);
+        left.join(right);
+        self.value.splice(start.y..=end.y, [left]);
+        self.cursor = start;
+        self.anchor = None;
+        true
+    }
+
+    /// Inserts at the cursor, stops once the max length is reached
+    fn insert_str(&mut self, s: &str, canvas: &Canvas) {
+        let room = self.limit(canvas).map(|max| max.saturating_sub(self.char_count()));
+        for (i, c) in s.chars().enumerate() {
+            if room.is_some_and(|room| i >= room) {
+                break;
+            }
+            let Position { x, y } = self.cursor;
+            match c {
+                '\n' if !self.single_line => {
+                    let (l, r) = self.value[y].clone().split(x);
+                    self.value[y] = l;
+                    self.value.insert(y + 1, r);
+                    self.cursor = Position { x: 0, y: y + 1 };
+                }
+                c => {
+                    let c = if c == '\n' || c == '\t' { ' ' } else { c };
+                    self.value[y].insert(x, Char::new(c, self.style.fg(), self.style.bg()));
+                    self.cursor.x += 1;
+                }
+            }
+        }
+    }
+
+    fn is_word(&self, pos: Position<usize>) -> bool {
+        self.value[pos.y]
+            .to_string()
+            .chars()
+            .nth(pos.x)
+            .is_some_and(|c| c.is_alphanumeric() || c == '_')
+    }
+
+    /// Start of the previous word, going up a line from the start of one
+    fn word_left(&self) -> Position<usize> {
+        let Position { mut x, y } = self.cursor;
+        if x == 0 {
+            return match y {
+                0 => self.cursor,
+                _ => Position { x: self.value[y - 1].len(), y: y - 1 },
+            };
+        }
+        while x > 0 && !self.is_word(Position { x: x - 1, y }) {
+            x -= 1;
+        }
+        while x > 0 && self.is_word(Position { x: x - 1, y }) {
+            x -= 1;
+        }
+        Position { x, y }
+    }
+
+    /// End of the next word, going down a line from the end of one
+    fn word_right(&self) -> Position<usize> {
+        let Position { mut x, y } = self.cursor;
+        let len = self.value[y].len();
+        if x == len {
+            return match y + 1 < self.value.len() {
+                true => Position { x: 0, y: y + 1 },
+                false => self.cursor,
+            };
+        }
+        while x < len && !self.is_word(Position { x, y }) {
+            x += 1;
+        }
+        while x < len && self.is_word(Position { x, y }) {
+            x += 1;
+        }
+        Position { x, y }
+    }
+
+    /// Moves the cursor, keeping or dropping the selection
+    fn move_to(&mut self, pos: Position<usize>, select: bool) {
+        if select {
+            self.anchor.get_or_insert(self.cursor);
+        } else {
+            self.anchor = None;
+        }
+        self.cursor = pos;
+        self.last_edit = None;
+    }
+
     pub fn process_key(
         &mut self,
         key: KeyEvent,
@@ -88,112 +304,160 @@ impl TextArea {
         canvas: &Canvas,
     ) {
         let mut dirty = true;
+        let Position { x, y } = self.cursor;
+        let len = self.value[y].len();
+        let last = self.value.len() - 1;
         match key {
             KeyEvent::Backspace => {
-                let x = self.cursor.x;
-                let y = self.cursor.y;
-                let rows = self.value.len();
-                let cols = self.value[y].len();
-
-                if rows == 1 {
-                    if x == cols && cols > 0{
-                        self.value[0].pop();
-                        self.cursor.x -= 1;
-                    } else if x != 0 {
-                        self.value[0].remove(x);
-                        self.cursor.x -= 1;
-                    }
-                } else {
-                    if x == 0 && y == 0 {
-
-                    } else if x != 0 && x == cols && cols > 0{
-                        self.value[y].pop();
-                        self.cursor.x -= 1;
-                    } else if x != 0 && x != cols {
-                        self.value[y].remove(x);
-                        self.cursor.x -= 1;
-                    } else if x == 0 && y != 0 {
-                        let val = self.value.remove(y);
-                        self.value[y-1].join(val);
-                        self.cursor.x = self.value[y].len();
-                    }
+                if self.selection().is_some() {
+                    self.snapshot(Edit::Other);
+                    self.delete_selection();
+                } else if x > 0 {
+                    self.snapshot(Edit::Deleting);
+                    self.value[y].remove(x - 1);
+                    self.cursor.x -= 1;
+                } else if y > 0 {
+                    self.snapshot(Edit::Deleting);
+                    let val = self.value.remove(y);
+                    self.cursor = Position { x: self.value[y - 1].len(), y: y - 1 };
+                    self.value[y - 1].join(val);
+                }
+            }
+            KeyEvent::Delete => {
+                if self.selection().is_some() {
+                    self.snapshot(Edit::Other);
+                    self.delete_selection();
+                } else if x < len {
+                    self.snapshot(Edit::Deleting);
+                    self.value[y].remove(x);
+                } else if y < last {
+                    self.snapshot(Edit::Deleting);
+                    let val = self.value.remove(y + 1);
+                    self.value[y].join(val);
                 }
             }
             KeyEvent::Char(c) => {
-                if self.max_len_value(canvas) > self.value.len() {
-                    let x = self.cursor.x;
-                    let y = self.cursor.y;
-                    let rows = self.value.len();
-                    let cols = self.value[y].len();
-
-                    if x == cols {
-                        self.value[y].push(Char::new(c, self.style.fg(), self.style.bg()));
-                    } else {
-                        self.value[y].insert(x, Char::new(c, self.style.fg(), self.style.bg()));
-                    }
-                    self.cursor.x += 1;
+                // replacing a selection starts a new undo step that the typing after it joins
+                if self.selection().is_some() {
+                    self.snapshot(Edit::Other);
+                    self.last_edit = Some(Edit::Typing);
+                } else {
+                    self.snapshot(Edit::Typing);
                 }
+                self.delete_selection();
+                self.insert_str(&c.to_string(), canvas);
             }
             KeyEvent::Enter => {
-                let x = self.cursor.x;
-                let y = self.cursor.y;
-                let rows = self.value.len();
-                let cols = self.value[y].len();
-                if x < cols {
-                    let (l, r) = self.value[y].clone().split(x);
-                    self.value[y] = l;
-                    self.value.insert(y, r);
-                    self.cursor.y += 1;
-                    self.cursor.x = 0;
-                } else if x >= cols {
-                    self.value.insert(y, Text::new());
-                    self.cursor.y +=1;
-                    self.cursor.x = 0;
+                if self.single_line {
+                    dirty = false;
+                } else {
+                    self.snapshot(Edit::Other);
+                    self.delete_selection();
+                    self.insert_str("\n", canvas);
                 }
             }
-            KeyEvent::Up => {
-                if self.cursor.y > 0 {
-                    self.cursor.y -= 1;
-                    if self.cursor.x > self.value[self.cursor.y].len() {
-                        self.cursor.x = self.value[self.cursor.y].len();
-                    }
-                }
-
+            KeyEvent::Up | KeyEvent::ShiftUp if y > 0 => {
+                let x = x.min(self.value[y - 1].len());
+                self.move_to(Position { x, y: y - 1 }, matches!(key, KeyEvent::ShiftUp));
             }
-            KeyEvent::Down => {
-                if self.cursor.y < self.value.len() {
-                    self.cursor.y += 1;
-                    if self.cursor.x > self.value[self.cursor.y].len() {
-                        self.cursor.x = self.value[self.cursor.y].len();
-                    }
-                }
+            KeyEvent::Down | KeyEvent::ShiftDown if y < last => {
+                let x = x.min(self.value[y + 1].len());
+                self.move_to(Position { x, y: y + 1 }, matches!(key, KeyEvent::ShiftDown));
             }
-            KeyEvent::Left => {
-                if self.cursor.x > 0 {
-                    self.cursor.x -= 1;
+            KeyEvent::Left | KeyEvent::ShiftLeft => {
+                let pos = match (x, y) {
+                    (0, 0) => self.cursor,
+                    (0, y) => Position { x: self.value[y - 1].len(), y: y - 1 },
+                    (x, y) => Position { x: x - 1, y },
+                };
+                self.move_to(pos, matches!(key, KeyEvent::ShiftLeft));
+            }
+            KeyEvent::Right | KeyEvent::ShiftRight => {
+                let pos = if x < len {
+                    Position { x: x + 1, y }
+                } else if y < last {
+                    Position { x: 0, y: y + 1 }
+                } else {
+                    self.cursor
+                };
+                self.move_to(pos, matches!(key, KeyEvent::ShiftRight));
+            }
+            KeyEvent::Home | KeyEvent::ShiftHome => {
+                self.move_to(Position { x: 0, y }, matches!(key, KeyEvent::ShiftHome));
+            }
+            KeyEvent::End | KeyEvent::ShiftEnd => {
+                self.move_to(Position { x: len, y }, matches!(key, KeyEvent::ShiftEnd));
+            }
+            KeyEvent::CtrlLeft | KeyEvent::CtrlShiftLeft => {
+                self.move_to(self.word_left(), matches!(key, KeyEvent::CtrlShiftLeft));
+            }
+            KeyEvent::CtrlRight | KeyEvent::CtrlShiftRight => {
+                self.move_to(self.word_right(), matches!(key, KeyEvent::CtrlShiftRight));
+            }
+            KeyEvent::Control('a') => {
+                // an anchor left by an earlier selection would otherwise be kept
+                self.anchor = Some(Position { x: 0, y: 0 });
+                self.cursor = Position { x: self.value[last].len(), y: last };
+                self.last_edit = None;
+            }
+            KeyEvent::Control('c') => {
+                dirty = false;
+                if let Some(text) = self.selected_text() {
+                    let _ = render_tx.send(RenderSignal::SetClipboard(text));
                 }
             }
-            KeyEvent::Right => {
-                if self.cursor.x < self.value[self.cursor.y].len() {
-                    self.cursor.x += 1
+            KeyEvent::Control('x') => {
+                if let Some(text) = self.selected_text() {
+                    let _ = render_tx.send(RenderSignal::SetClipboard(text));
+                    self.snapshot(Edit::Other);
+                    self.delete_selection();
                 }
             }
+            // the text comes back as a Paste event
+            KeyEvent::Control('v') => {
+                dirty = false;
+                let _ = render_tx.send(RenderSignal::RequestClipboard);
+            }
+            KeyEvent::Control('z') => dirty = self.undo(),
+            KeyEvent::Control('y') => dirty = self.redo(),
             _ => {
                 dirty = false;
             }
         }
         if dirty {
-            self.output(render_tx, canvas);
+            let _ = self.output(render_tx, canvas);
         }
     }
 
+    /// Inserts text in place of the selection as one undo step
+    pub fn paste(&mut self, text: &str, render_tx: &Sender<RenderSignal>, canvas: &Canvas) {
+        self.snapshot(Edit::Other);
+        self.delete_selection();
+        self.insert_str(text, canvas);
+        let _ = self.output(render_tx, canvas);
+    }
+
     pub fn output(
         &mut self,
         render_tx: &Sender<RenderSignal>,
         canvas: &Canvas,
     ) -> Result<(), SendError<RenderSignal>> {
         let mut out = self.value.clone();
-        out[self.cursor.y].insert(self.cursor.x, self.marker.clone());
+        if let Some((start, end)) = self.selection() {
+            for (y, line) in out.iter_mut().enumerate().take(end.y + 1).skip(start.y) {
+                let from = if y == start.y { start.x } else { 0 };
+                let to = if y == end.y { end.x } else { line.len() };
+                let (l, r) = line.clone().split(to);
+                let (l, mut mid) = l.split(from);
+                mid.set_attrs(Attributes::REVERSE);
+                *line = l;
+                line.join(mid);
+                line.join(r);
+            }
+        }
+        if self.focused {
+            out[self.cursor.y].insert(self.cursor.x, self.marker.clone());
+        }
         match self.render_id.upgrade() {
             None => {
                 let arc_id = RenderUnitId::new(Layer::Ui);
@@ -210,3 +474,113 @@ impl TextArea {
         }
     }
 }
+
+/// Keys edit the text, the marker shows where typing goes while the area has focus
+impl<A> Widget<A> for TextArea {
+    fn handle_event(
+        &mut self,
+        event: &Event,
+        render_tx: &Sender<RenderSignal>,
+        canvas: &Canvas,
+    ) -> Option<A> {
+        match event {
+            Event::Keyboard(key) => self.process_key(*key, render_tx, canvas),
+            Event::Other(OtherEvent::Paste(text)) => self.paste(text, render_tx, canvas),
+            _ => {}
+        }
+        None
+    }
+
+    fn output(
+        &mut self,
+        render_tx: &Sender<RenderSignal>,
+        canvas: &Canvas,
+    ) -> Result<(), SendError<RenderSignal>> {
+        TextArea::output(self, render_tx, canvas)
+    }
+
+    fn set_focus(&mut self, focus: bool) {
+        self.focused = focus;
+    }
+
+    fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    fn bounds(&self, canvas: &Canvas) -> Rect {
+        let mut bounds = text_bounds(self.position.clone(), &self.style, &self.value, canvas);
+        if self.style.width().is_none() {
+            // room for the marker at the end of the longest line
+            bounds.width += 1;
+        }
+        bounds
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn keys(area: &mut TextArea, keys: &[KeyEvent], tx: &Sender<RenderSignal>, canvas: &Canvas) {
+        for k in keys {
+            area.process_key(*k, tx, canvas);
+        }
+    }
+
+    fn typed(area: &mut TextArea, s: &str, tx: &Sender<RenderSignal>, canvas: &Canvas) {
+        for c in s.chars() {
+            area.process_key(KeyEvent::Char(c), tx, canvas);
+        }
+    }
+
+    #[test]
+    fn text_area_edit_test() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let canvas = Canvas::new(80, 24);
+        let mut area = TextArea::new(0, 0, '|', Style::default(), None);
+        typed(&mut area, "hello world", &tx, &canvas);
+        // the right half goes below the left
+        keys(&mut area, &[KeyEvent::CtrlLeft, KeyEvent::Enter], &tx, &canvas);
+        assert_eq!(area.get_value(), vec!["hello ", "world"]);
+        keys(&mut area, &[KeyEvent::Down, KeyEvent::Down, KeyEvent::End], &tx, &canvas);
+        assert_eq!(area.cursor, Position { x: 5, y: 1 });
+
+        keys(&mut area, &[KeyEvent::CtrlShiftLeft, KeyEvent::ShiftLeft], &tx, &canvas);
+        assert_eq!(area.selected_text().as_deref(), Some("\nworld"));
+        keys(&mut area, &[KeyEvent::Control('x')], &tx, &canvas);
+        assert_eq!(area.get_value(), vec!["hello "]);
+        assert!(rx.try_iter().any(|s| matches!(s, RenderSignal::SetClipboard(t) if t == "\nworld")));
+
+        // typing is one undo step, the cut another
+        typed(&mut area, "there", &tx, &canvas);
+        keys(&mut area, &[KeyEvent::Control('z')], &tx, &canvas);
+        assert_eq!(area.get_value(), vec!["hello "]);
+        keys(&mut area, &[KeyEvent::Control('z')], &tx, &canvas);
+        assert_eq!(area.get_value(), vec!["hello ", "world"]);
+        keys(&mut area, &[KeyEvent::Control('y'), KeyEvent::Home, KeyEvent::Delete], &tx, &canvas);
+        assert_eq!(area.get_value(), vec!["ello "]);
+
+        // select all ends the typing run, so undo brings back what was replaced
+        let mut area = TextArea::new(0, 0, '|', Style::default(), None);
+        typed(&mut area, "abc", &tx, &canvas);
+        keys(&mut area, &[KeyEvent::Control('a'), KeyEvent::Char('x')], &tx, &canvas);
+        typed(&mut area, "yz", &tx, &canvas);
+        assert_eq!(area.get_value(), vec!["xyz"]);
+        keys(&mut area, &[KeyEvent::Control('z')], &tx, &canvas);
+        assert_eq!(area.get_value(), vec!["abc"]);
+
+        // select all replaces an earlier shift selection
+        let mut area = TextArea::new(0, 0, '|', Style::default(), None);
+        typed(&mut area, "hello", &tx, &canvas);
+        keys(&mut area, &[KeyEvent::ShiftLeft, KeyEvent::Control('a')], &tx, &canvas);
+        typed(&mut area, "x", &tx, &canvas);
+        assert_eq!(area.get_value(), vec!["x"]);
+
+        let mut line = TextArea::new(0, 0, '|', Style::default(), None)
+            .set_single_line(true)
+            .set_max_len(8);
+        line.paste("one\ntwo three", &tx, &canvas);
+        keys(&mut line, &[KeyEvent::Enter, KeyEvent::Char('x')], &tx, &canvas);
+        assert_eq!(line.get_value(), vec!["one two "]);
+    }
+}
@@ -17,34 +17,112 @@ limitations under the License.
 
 use my_term::color::{BLUE, Background, Foreground, GREEN, MAGENTA};
 
-use crate::engine::{
-    Instance,
-    enums::{RenderSignal, SceneInitSignals, Signal},
-    input::{Event, KeyEvent},
-    render::Text,
-    traits::Scene,
-    ui::{
-        SelectionDirection, Selector, SelectorItem, TextArea,
-        style::{self, MEDIUM_BLOCK},
+use crate::{
+    engine::{
+        Instance,
+        enums::{RenderSignal, SceneInitSignals, SceneSignal, Signal},
+        input::{Event, KeyEvent, OtherEvent},
+        render::Text,
+        traits::Scene,
+        types::Position,
+        ui::{
+            Button, CheckBox, Constraint, FocusManager, Form, Layout, NumberField, NumberKind,
+            Rect, SelectionDirection, Selector, SelectorItem, Slider, TextArea, Widget,
+            style::{self, Alignment, MEDIUM_BLOCK, Measure, Size},
+        },
     },
+    game::scenes::InGame,
 };
 use logging::{ErrorKind as LogErrorKind, LogLevel};
 
+/// What the inputs hand back to the scene
+#[derive(Debug)]
+enum Action {
+    Submit,
+}
+
+/// Everything World::new takes that comes from the form
+#[derive(Debug, Clone, PartialEq)]
+struct WorldParams {
+    name: String,
+    size: (usize, usize, usize),
+    temp: f32,
+    height: f32,
+    sea: f32,
+    hard_core: bool,
+}
+
 #[derive(Debug)]
 pub struct CreateWorld {
     world_name_input: TextArea,
     world_size_input: Selector,
-    world_height_delta_input: TextArea,
-    world_sea_level_input: TextArea,
-    cursor: usize,
+    world_temp_input: NumberField,
+    world_height_delta_input: NumberField,
+    world_sea_level_input: Slider,
+    hard_core_input: CheckBox,
+    create_button: Button<(), Action>,
+    form: Form,
+    layout: Layout,
+    focus: FocusManager,
+    /// errors are only shown once the player has tried to create the world
+    submitted: bool,
     init_complete: bool,
 }
 
+// rows of the form layout, also the tab order of the inputs and the fields of the form
+const NAME_ROW: usize = 0;
+const SIZE_ROW: usize = 1;
+const TEMP_ROW: usize = 2;
+const HEIGHT_DELTA_ROW: usize = 3;
+const SEA_LEVEL_ROW: usize = 4;
+const HARD_CORE_ROW: usize = 5;
+const CREATE_ROW: usize = 6;
+const ROWS: usize = 7;
+
+/// (x, y, z) in tiles for each world size the selector offers
+const WORLD_SIZES: [(usize, usize, usize); 3] = [(50, 50, 1), (100, 100, 1), (200, 200, 1)];
+
 impl CreateWorld {
     pub fn new() -> Box<dyn Scene> {
-        Box::new(Self {
-            cursor: 0,
-            world_name_input: TextArea::new(0, 1, MEDIUM_BLOCK, style::Style::default(), None),
+        Box::new(Self::build())
+    }
+
+    fn build() -> Self {
+        let mut layout = Layout::vstack()
+            .set_spacing(1)
+            .set_alignment(Alignment::centered());
+        let mut form = Form::new();
+        for _ in 0..ROWS {
+            // the line under every input is kept for its error message
+            layout.add(
+                Constraint::new(Size::rect(Measure::Percent(50), Measure::Cell(2)))
+                    .set_min(Size::w_only(Measure::Cell(32))),
+            );
+            form.add(Position::default());
+        }
+        let hover = style::Coloring {
+            foreground: Foreground::new(BLUE),
+            background: Background::new(MAGENTA),
+        };
+        let label = |s: &str| Text::from(s, style::Style::default().fg(), style::Style::default().bg());
+        let mut sea_level = Slider::new(
+            0,
+            11,
+            style::Style::default(),
+            label("Sea level"),
+            -1.0,
+            1.0,
+        )
+        .set_step(0.1)
+        .set_width(20)
+        .set_hover_color(hover.clone());
+        sea_level.set_value(0.0);
+        Self {
+            focus: FocusManager::new(),
+            layout,
+            form,
+            world_name_input: TextArea::new(0, 1, MEDIUM_BLOCK, style::Style::default(), None)
+                .set_single_line(true),
             world_size_input: Selector::new(
                 0,
                 5,
@@ -53,92 +131,189 @@ impl CreateWorld {
                     foreground: Foreground::new(GREEN),
                     background: Background::new(my_term::color::RED),
                 },
-                style::Coloring {
-                    foreground: Foreground::new(BLUE),
-                    background: Background::new(MAGENTA),
-                },
+                hover.clone(),
                 SelectionDirection::Horizontal,
                 vec![
-                    SelectorItem::new(
-                        Text::from(
-                            "Small",
-                            style::Style::default().fg(),
-                            style::Style::default().bg(),
-                        ),
-                        0,
-                    ),
-                    SelectorItem::new(
-                        Text::from(
-                            "Medium",
-                            style::Style::default().fg(),
-                            style::Style::default().bg(),
-                        ),
-                        1,
-                    ),
-                    SelectorItem::new(
-                        Text::from(
-                            "Large",
-                            style::Style::default().fg(),
-                            style::Style::default().bg(),
-                        ),
-                        2,
-                    ),
+                    SelectorItem::new(label("Small"), 0),
+                    SelectorItem::new(label("Medium"), 1),
+                    SelectorItem::new(label("Large"), 2),
                 ],
             ),
-            world_height_delta_input: TextArea::new(
-                0,
-                9,
-                MEDIUM_BLOCK,
-                style::Style::default(),
-                None,
-            ),
-            world_sea_level_input: TextArea::new(
-                0,
-                13,
-                MEDIUM_BLOCK,
+            world_temp_input: NumberField::new(0, 7, style::Style::default(), label("Average temperature"))
+                .set_range(-30.0, 50.0)
+                .set_value(25.0)
+                .set_hover_color(hover.clone()),
+            world_height_delta_input: NumberField::new(0, 9, style::Style::default(), label("Height delta"))
+                .set_kind(NumberKind::Float)
+                .set_range(0.0, 10.0)
+                .set_step(0.5)
+                .set_value(1.0)
+                .set_hover_color(hover.clone()),
+            world_sea_level_input: sea_level,
+            hard_core_input: CheckBox::new(0, 13, style::Style::default(), hover.clone(), label("Hard core")),
+            create_button: Button::new(
+                Position::new(0, 15),
+                vec![label("[ Create World ]")],
                 style::Style::default(),
-                None,
+                hover,
+                |_| Action::Submit,
             ),
+            submitted: false,
             init_complete: false,
+        }
+    }
+
+    /// Moves every input, and the error line under it, to where the layout put it
+    fn place(&mut self) {
+        for row in 0..ROWS {
+            let rect = self.layout.rect(row).unwrap_or_default();
+            self.form.set_pos(row, Position::new(rect.x, rect.y + 1));
+        }
+        let rect = |row: usize| self.layout.rect(row).unwrap_or_default().pos();
+        let (name, size, temp, delta, sea, hard_core, create) = (
+            rect(NAME_ROW),
+            rect(SIZE_ROW),
+            rect(TEMP_ROW),
+            rect(HEIGHT_DELTA_ROW),
+            rect(SEA_LEVEL_ROW),
+            rect(HARD_CORE_ROW),
+            rect(CREATE_ROW),
+        );
+        self.world_name_input.set_pos(name);
+        self.world_size_input.set_pos(size);
+        self.world_temp_input.set_pos(temp);
+        self.world_height_delta_input.set_pos(delta);
+        self.world_sea_level_input.set_pos(sea);
+        self.hard_core_input.set_pos(hard_core);
+        self.create_button.set_pos(create);
+    }
+
+    /// The focus manager along with the inputs in tab order
+    fn widgets(&mut self) -> (&mut FocusManager, [&mut dyn Widget<Action>; ROWS]) {
+        (
+            &mut self.focus,
+            [
+                &mut self.world_name_input,
+                &mut self.world_size_input,
+                &mut self.world_temp_input,
+                &mut self.world_height_delta_input,
+                &mut self.world_sea_level_input,
+                &mut self.hard_core_input,
+                &mut self.create_button,
+            ],
+        )
+    }
+
+    fn redraw(&mut self, ins: &Instance) {
+        let (_, widgets) = self.widgets();
+        for w in widgets {
+            if let Err(_e) = w.output(&ins.render_queue, &ins.canvas) {
+                // Log the error
+            }
+        }
+        if let Err(_e) = self.form.output(&ins.render_queue) {
+            // Log the error
+        }
+    }
+
+    /// Checks every field, None while any of them does not parse
+    fn validate(&mut self) -> Option<WorldParams> {
+        let name = self.world_name_input.get_value().join("");
+        let name = self.form.check(NAME_ROW, parse_name(&name));
+        let size = self.form.check(
+            SIZE_ROW,
+            self.world_size_input
+                .get_selected()
+                .and_then(|i| WORLD_SIZES.get(i).copied())
+                .ok_or_else(|| "Pick a world size with Enter".to_string()),
+        );
+        let temp = self.form.check(TEMP_ROW, self.world_temp_input.value());
+        let height = self
+            .form
+            .check(HEIGHT_DELTA_ROW, self.world_height_delta_input.value());
+        let sea = self.world_sea_level_input.value();
+        self.form.set_error(CREATE_ROW, None);
+        Some(WorldParams {
+            name: name?,
+            size: size?,
+            temp: temp? as f32,
+            height: height? as f32,
+            sea: sea as f32,
+            hard_core: self.hard_core_input.is_checked(),
         })
     }
+
+    /// Builds the world and moves on to the game, or puts up why it could not
+    fn submit(&mut self) -> Option<Signal> {
+        self.submitted = true;
+        let params = self.validate()?;
+        match InGame::create(
+            params.name,
+            params.size,
+            params.temp,
+            params.height,
+            params.sea,
+            params.hard_core,
+        ) {
+            Ok(scene) => Some(Signal::Scenes(SceneSignal::New {
+                scene,
+                signal: SceneInitSignals::None,
+            })),
+            Err(e) => {
+                self.form
+                    .set_error(CREATE_ROW, Some(format!("Could not create the world: {}", e)));
+                None
+            }
+        }
+    }
+}
+
+/// The name becomes the save file's name so it is kept to letters, digits, spaces, - and _
+fn parse_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("The world needs a name".to_string());
+    }
+    if let Some(c) = name
+        .chars()
+        .find(|c| !(c.is_alphanumeric() || *c == ' ' || *c == '-' || *c == '_'))
+    {
+        return Err(format!("'{}' can not be used in a world name", c));
+    }
+    Ok(name.to_string())
 }
 
 impl Scene for CreateWorld {
     fn init(&mut self, ins: &mut Instance, _sig: SceneInitSignals) -> Signal {
         let lg = ins.logger.clone();
-        if let Err(e) = self.world_name_input.output(&ins.render_queue, &ins.canvas) {
-            match lg.write(
-                LogLevel::Error,
-                format!("Error outputting World Name Input: {}", e),
-            ) {
-                Err(e) => match e.kind {
-                    LogErrorKind::Level => {}
-                    _ => panic!("Failed to log: {}", e.msg),
-                },
-                Ok(_) => {}
-            };
-        }
-        if let Err(e) = self.world_size_input.output(&ins.render_queue) {
-            match lg.write(
-                LogLevel::Error,
-                format!("Error outputting World Size Input: {}", e),
-            ) {
-                Ok(_) => {}
-                Err(e) => match e.kind {
-                    LogErrorKind::Level => {}
-                    _ => panic!("Failed to Log: {}", e.msg),
-                },
-            };
+        self.layout.arrange(Rect::from_canvas(&ins.canvas));
+        self.place();
+        let names = [
+            "World Name",
+            "World Size",
+            "World Temperature",
+            "World delta",
+            "World Sea Level",
+            "Hard Core",
+            "Create",
+        ];
+        let (focus, mut widgets) = self.widgets();
+        for (w, name) in widgets.iter_mut().zip(names) {
+            if let Err(e) = w.output(&ins.render_queue, &ins.canvas) {
+                match lg.write(
+                    LogLevel::Error,
+                    format!("Error outputting {} Input: {}", name, e),
+                ) {
+                    Ok(_) => {}
+                    Err(e) => match e.kind {
+                        LogErrorKind::Level => {}
+                        _ => panic!("Failed to log: {}", e.msg),
+                    },
+                };
+            }
         }
-        if let Err(e) = self
-            .world_sea_level_input
-            .output(&ins.render_queue, &ins.canvas)
-        {
-            match lg.write(
-                LogLevel::Error,
-                format!("Error outputting World Sea Level Input: {}", e),
-            ) {
+        if let Err(e) = focus.focus(NAME_ROW, &mut widgets, &ins.render_queue, &ins.canvas) {
+            match lg.write(LogLevel::Error, format!("Error focusing World Name Input: {}", e)) {
                 Ok(_) => {}
                 Err(e) => match e.kind {
                     LogErrorKind::Level => {}
@@ -146,21 +321,6 @@ impl Scene for CreateWorld {
                 },
             };
         }
-        if let Err(e) = self
-            .world_height_delta_input
-            .output(&ins.render_queue, &ins.canvas)
-        {
-            match lg.write(
-                LogLevel::Error,
-                format!("Error outputting World delta Input: {}", e),
-            ) {
-                Ok(_) => {}
-                Err(e) => match e.kind {
-                    LogErrorKind::Level => {}
-                    _ => panic!("Failed to Log: {}", e.msg),
-                },
-            };
-        }
         self.init_complete = true;
         Signal::None
     }
@@ -177,24 +337,10 @@ impl Scene for CreateWorld {
 
     fn resume(&mut self, ins: &mut Instance) {
         let _ = ins.render_queue.send(RenderSignal::Clear);
-        if let Err(_e) = self.world_name_input.output(&ins.render_queue, &ins.canvas) {
-            // Log the error
-        }
-        if let Err(_e) = self.world_size_input.output(&ins.render_queue) {
-            // Log the error
-        }
-        if let Err(_e) = self
-            .world_height_delta_input
-            .output(&ins.render_queue, &ins.canvas)
-        {
-            // Log the error
-        }
-        if let Err(_e) = self
-            .world_sea_level_input
-            .output(&ins.render_queue, &ins.canvas)
-        {
-            // Log the error
-        }
+        // the screen may have changed size while another scene was on top
+        self.layout.arrange(Rect::from_canvas(&ins.canvas));
+        self.place();
+        self.redraw(ins);
     }
 
     fn suspend(&mut self, ins: &mut Instance) {
@@ -202,61 +348,40 @@ impl Scene for CreateWorld {
     }
 
     fn update(&mut self, inst: &mut Instance, _dt: f32) -> Signal {
+        let events = inst.events();
         let canvas = &inst.canvas;
         let lg = inst.logger.clone();
         let mut signals: Vec<Signal> = vec![];
-        let mut events = vec![];
-        for e in inst.event_recvier.try_iter() {
-            events.push(e);
-        }
         for e in events {
-            match e {
-                Event::Keyboard(key) => match key {
-                    KeyEvent::Char('t') => {
-                        let _ = lg.write(LogLevel::Info, "Testing from Create World Scene");
+            match &e {
+                Event::Other(OtherEvent::ScreenSizeChange { width, height }) => {
+                    if self.layout.resize(*width, *height) {
+                        self.place();
+                        self.redraw(inst);
                     }
-                    KeyEvent::Char('q') => {
-                        signals.push(Signal::Scenes(crate::engine::enums::SceneSignal::Pop))
-                    }
-                    KeyEvent::Tab => {
-                        self.cursor = (self.cursor + 1) % 4;
-                    }
-                    _other => {}
-                },
+                }
+                Event::Keyboard(KeyEvent::Escape) => signals.push(Signal::Scenes(SceneSignal::Pop)),
                 _ => {}
             };
-            match self.cursor {
-                0 => match e {
-                    Event::Keyboard(key) => {
-                        self.world_name_input
-                            .process_key(key, &inst.render_queue, canvas)
+            let (focus, mut widgets) = self.widgets();
+            match focus.dispatch(&e, &mut widgets, &inst.render_queue, canvas) {
+                Ok(Some(Action::Submit)) => {
+                    if let Some(sig) = self.submit() {
+                        signals.push(sig);
                     }
-                    _ => {}
-                },
-                1 => match e {
-                    Event::Keyboard(key) => match key {
-                        KeyEvent::Char('a') => self.world_size_input.prev(),
-                        KeyEvent::Char('d') => self.world_size_input.next(),
-                        KeyEvent::Enter => self.world_size_input.toggle_select(),
-                        _ => {}
-                    },
-                    _ => {}
-                },
-                2 => match e {
-                    Event::Keyboard(key) => {
-                        self.world_height_delta_input
-                            .process_key(key, &inst.render_queue, canvas)
-                    }
-                    _ => {}
-                },
-                3 => match e {
-                    Event::Keyboard(key) => {
-                        self.world_sea_level_input
-                            .process_key(key, &inst.render_queue, canvas)
+                }
+                Ok(None) => {
+                    if self.submitted {
+                        // keep the messages in step with what is typed once they are showing
+                        self.validate();
                     }
-                    _ => {}
-                },
-                _ => {}
+                }
+                Err(e) => {
+                    let _ = lg.write(LogLevel::Error, format!("Error updating inputs: {}", e));
+                }
+            }
+            if let Err(e) = self.form.output(&inst.render_queue) {
+                let _ = lg.write(LogLevel::Error, format!("Error outputting form errors: {}", e));
             }
         }
         if signals.len() > 0 {
@@ -266,3 +391,40 @@ impl Scene for CreateWorld {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn create_world_validate_test() {
+        assert_eq!(parse_name("  Stonehold "), Ok("Stonehold".to_string()));
+        assert!(parse_name("   ").is_err());
+        assert!(parse_name("../saves").is_err());
+
+        let mut scene = CreateWorld::build();
+        // nothing typed and no size picked
+        assert_eq!(scene.validate(), None);
+        assert!(scene.form.error(NAME_ROW).is_some());
+        assert!(scene.form.error(SIZE_ROW).is_some());
+        assert!(scene.form.error(TEMP_ROW).is_none());
+
+        let (tx, _rx) = std::sync::mpsc::channel();
+        let canvas = crate::engine::render::Canvas::new(80, 24);
+        for c in "Stonehold".chars() {
+            scene.world_name_input.process_key(KeyEvent::Char(c), &tx, &canvas);
+        }
+        scene.world_size_input.next();
+        scene.world_size_input.toggle_select();
+        let params = scene.validate().unwrap();
+        assert!(scene.form.is_valid());
+        assert_eq!(params.name, "Stonehold");
+        assert_eq!(params.size, WORLD_SIZES[1]);
+        assert_eq!((params.temp, params.height, params.sea), (25.0, 1.0, 0.0));
+        assert!(!params.hard_core);
+        // the Hard Core checkbox ends up in the params
+        let space = crate::engine::input::Event::Keyboard(KeyEvent::Char(' '));
+        Widget::<Action>::handle_event(&mut scene.hard_core_input, &space, &tx, &canvas);
+        assert!(scene.validate().unwrap().hard_core);
+    }
+}
@@ -18,12 +18,15 @@ use crate::{
     engine::{
         self, Error, Instance,
         enums::{RenderSignal, SceneInitSignals, Signal},
-        input::{Event, KeyEvent},
-        render::{self, Canvas},
+        input::{Event, KeyEvent, OtherEvent},
+        render::{self, Canvas, Text},
         traits::Scene,
+        types::Position,
+        ui::{Gauge, SelectionDirection, style::Style},
     },
-    game::types::World,
+    game::types::{World, entity::Kind},
 };
+use my_term::color::{RED, YELLOW};
 use std::{
     marker::PhantomData,
     sync::{Arc, mpsc},
@@ -32,6 +35,9 @@ use std::{
 #[derive(Debug)]
 pub struct InGame {
     world: World,
+    /// health of the creature in tracked, hidden while there is none
+    health: Gauge,
+    tracked: Option<usize>,
     init_complete: bool,
     is_paused: bool,
 }
@@ -42,39 +48,115 @@ const DEFAULT_WORLD_Z: usize = 1;
 const DEFAULT_WORLD_TEMP: f32 = 25.0; // Celius
 const DEFAULT_WORLD_HEIGHT: f32 = 1.0;
 const DEFAULT_WORLD_SEA_LEVEL: f32 = 0.0;
+const SPRITE_DIR: &str = "./data/sprites/";
+const HEALTH: &str = "Health";
 
 impl InGame {
     pub fn new() -> Result<Box<dyn Scene>, Error> {
-        let mut world: World;
-        match World::new(
+        Self::create(
             "test_world".to_string(),
-            DEFAULT_WORLD_X,
-            DEFAULT_WORLD_Y,
-            DEFAULT_WORLD_Z,
+            (DEFAULT_WORLD_X, DEFAULT_WORLD_Y, DEFAULT_WORLD_Z),
             DEFAULT_WORLD_TEMP,
             DEFAULT_WORLD_HEIGHT,
             DEFAULT_WORLD_SEA_LEVEL,
+            false,
+        )
+    }
+
+    /// New world built from the game data, size is (x, y, z) in tiles
+    pub fn create(
+        name: String,
+        size: (usize, usize, usize),
+        temp: f32,
+        height: f32,
+        sea: f32,
+        hard_core: bool,
+    ) -> Result<Box<dyn Scene>, Error> {
+        let mut world: World;
+        match World::new(
+            name,
+            size.0,
+            size.1,
+            size.2,
+            temp,
+            height,
+            sea,
+            hard_core,
             "./data/materials/",
             "./data/entities/",
-            "./data/sprites/",
+            SPRITE_DIR,
+            "./data/appearance/",
+            "./data/tiles/",
         ) {
             Err(e) => return Err(e),
             Ok(w) => world = w,
         }
+        let style = Style::default();
+        let label = Text::from(HEALTH, style.fg(), style.bg());
+        let health = Gauge::new(1, 0, style, label, SelectionDirection::Horizontal, 20)
+            .add_threshold(0.25, RED)
+            .add_threshold(0.5, YELLOW);
         Ok(Box::new(Self {
             world: world,
+            health,
+            tracked: None,
             init_complete: false,
             is_paused: false,
         }))
     }
 }
 
+impl InGame {
+    /// Lowest keyed creature with a health stat, only searched for when the tracked one is gone
+    fn find_tracked(&self) -> Option<usize> {
+        self.world
+            .entities
+            .all_keys()
+            .map(|(k, _)| *k)
+            .filter(|k| {
+                self.world.entities.get(*k).is_some_and(|e| {
+                    matches!(e.kind, Kind::Creature { .. }) && e.common.stats.contains_key(HEALTH)
+                })
+            })
+            .min()
+    }
+
+    /// Keeps the health gauge in the bottom left corner in step with the tracked creature
+    fn update_health(&mut self, ins: &Instance, resized: bool) {
+        if resized {
+            self.health
+                .set_pos(Position::new(1, ins.canvas.height.saturating_sub(2) as i32));
+        }
+        let gone = self
+            .tracked
+            .is_some_and(|k| self.world.entities.get(k).is_none());
+        if gone || !self.init_complete {
+            let _ = self.health.remove(&ins.render_queue);
+            self.tracked = self.find_tracked();
+        }
+        let stat = self
+            .tracked
+            .and_then(|k| self.world.entities.get(k))
+            .and_then(|e| e.common.stats.get(HEALTH));
+        let Some(stat) = stat else { return };
+        let changed = self.health.update(stat) || gone || !self.init_complete;
+        if changed || resized {
+            let _ = self.health.output(&ins.render_queue);
+        }
+    }
+}
+
 impl Scene for InGame {
     fn init(&mut self, ins: &mut Instance, _sig: SceneInitSignals) -> Signal {
         let _ = self.world.generate(None);
+        self.world.mark_all_dirty();
+        self.update_health(ins, true);
         self.init_complete = true;
 
-        Signal::None
+        Signal::Render(RenderSignal::Batch(vec![
+            RenderSignal::LoadTemplates(SPRITE_DIR.to_string()),
+            RenderSignal::LoadEmitters(render::PARTICLE_DIR.to_string()),
+        ]))
     }
     fn is_init(&self) -> bool {
         self.init_complete
@@ -87,19 +169,20 @@ impl Scene for InGame {
     fn suspend(&mut self, ins: &mut Instance) {}
     fn update(&mut self, inst: &mut Instance, delta_time: f32) -> Signal {
         let canvas = &inst.canvas;
-        let mut events = vec![];
-        for e in inst.event_recvier.try_iter() {
-            events.push(e);
-        }
+        let events = inst.events();
+        let mut resized = false;
         for event in events {
             match event {
                 Event::Keyboard(key) => match key {
                     KeyEvent::Char('q') => return Signal::Quit,
                     _ => {}
                 },
+                Event::Other(OtherEvent::ScreenSizeChange { .. }) => resized = true,
                 _ => {}
             }
         }
+        self.world.sync_appearances(&inst.render_queue);
+        self.update_health(inst, resized);
         Signal::None
     }
 }
@@ -54,22 +54,6 @@ enum Signal {
     Back,
 }
 
-enum Intensity {
-    Low,
-    Medium,
-    High,
-    Critical,
-}
-
-struct NewWorldForm {
-    world_name: String,
-    seed: u64,
-    avg_temp: Intensity,
-    avg_height: Intensity,
-    height_delta: Intensity,
-    hard_core: bool,
-}
-
 #[derive(Debug)]
 pub struct LoadGame {
     menu: Menu<Signal>,
@@ -104,7 +88,9 @@ impl Scene for LoadGame {
         if let Err(_e) = render_clear(&ins.render_queue) {
             // log that there was a problem clearing the screen
         }
-        self.menu.output();
+        if let Err(_e) = self.menu.output() {
+            // Log that there was an error
+        }
         EngineSignal::None
     }
 
@@ -122,7 +108,9 @@ impl Scene for LoadGame {
         if let Err(_e) = ins.render_queue.send(RenderSignal::Clear) {
             // Log that there was an error
         }
-        self.menu.output();
+        if let Err(_e) = self.menu.output() {
+            // Log that there was an error
+        }
     }
 
     fn suspend(&mut self, ins: &mut Instance) {
@@ -134,22 +122,17 @@ impl Scene for LoadGame {
     fn update(&mut self, inst: &mut Instance, delta_time: f32) -> EngineSignal {
         let canvas = &inst.canvas;
         let mut batch: Vec<EngineSignal> = Vec::new();
-        let mut events = vec![];
-        for e in inst.event_recvier.try_iter() {
-            events.push(e);
-        }
+        let events = inst.events();
         for event in events {
             match event {
                 Event::Keyboard(key) => match key {
                     KeyEvent::Char('s') => {
-                        if self.menu.cursor_down(1) {
-                            self.menu.output()
-                        }
+                        // the menu redraws itself when the cursor moves
+                        self.menu.cursor_down(1);
                     }
                     KeyEvent::Char('w') => {
-                        if self.menu.cursor_up(1) {
-                            self.menu.output()
-                        }
+                        // the menu redraws itself when the cursor moves
+                        self.menu.cursor_up(1);
                     }
                     KeyEvent::Char('d') => match self.menu.execute() {
                         Signal::Back => {
@@ -19,12 +19,15 @@ use crate::{
         Instance,
         enums::{RenderSignal, SceneInitSignals, SceneSignal, Signal as EngineSignal},
         input::{Event, KeyEvent},
-        render::{Canvas, Layer, Object, ObjectData, RenderQueue, RenderUnitId, Text, TextType},
+        render::{
+            Canvas, Layer, Object, ObjectData, RenderQueue, RenderUnitId, Text, TextType,
+            escape_markup,
+        },
         traits::Scene,
         types::Position3D,
         ui::{
-            Border, BorderSprite, Menu, MenuItem, Padding, SelectionDirection, Selector,
-            SelectorItem, TextArea,
+            Border, BorderSprite, Dialog, DialogResult, Menu, MenuItem, Padding,
+            SelectionDirection, Selector, SelectorItem, TextArea, Widget,
             style::{self, Align, Justify, Measure, Size, Style},
         },
     },
@@ -58,7 +61,8 @@ enum _Sig {
 
 pub struct MainMenu {
     menu: Menu<_Sig>,
-    err_msg_handle: Option<Arc<RenderUnitId>>,
+    /// open dialog and what its answer turns into, it gets every event until it closes
+    dialog: Option<(Dialog, fn(DialogResult) -> _Sig)>,
     init_complete: bool,
 }
 
@@ -100,7 +104,7 @@ impl MainMenu {
                 ],
             ),
             init_complete: false,
-            err_msg_handle: None,
+            dialog: None,
         })
     }
 
@@ -152,19 +156,30 @@ impl MainMenu {
                 scene: Settings::new(),
                 signal: SceneInitSignals::None,
             }),
-            _Sig::Connect => {
-                let (serv_ver, tick_rate) = match inst.net.send_hel() {
-                    Ok(res) => res,
-                    Err(e) => {
-
-                        let _ = inst.logger.write(logging::LogLevel::Error, "Failed to connect to game server");
-                        return EngineSignal::None;
+            _Sig::Connect => match inst.net.send_hel() {
+                Ok((_serv_ver, tick_rate)) => {
+                    inst.tick_rate = tick_rate;
+                    self.process_signal(inst, _Sig::LoadGameScene)
+                }
+                Err(e) => {
+                    let _ = inst.logger.write(
+                        logging::LogLevel::Error,
+                        format!("Failed to connect to game server: {}", e),
+                    );
+                    let mut dialog = Dialog::confirm(
+                        "Connection failed",
+                        format!(
+                            "Could not reach the game server.\n[red]{}[/]\n\n[b]Play offline?[/]",
+                            escape_markup(&e.to_string())
+                        ),
+                    );
+                    if let Err(_e) = dialog.output(&inst.render_queue, &inst.canvas) {
+                        // Log that there is a problem
                     }
-                };
-                
-                inst.tick_rate = tick_rate;
-                EngineSignal::None
-            }
+                    self.dialog = Some((dialog, answer_offline));
+                    EngineSignal::None
+                }
+            },
             _Sig::Render(r) => EngineSignal::Render(r),
             _Sig::MenuCursorUp(d) => {
                 self.menu.cursor_up(d);
@@ -201,7 +216,9 @@ impl MainMenu {
 
 impl Scene for MainMenu {
     fn init(&mut self, inst: &mut Instance, signal: SceneInitSignals) -> EngineSignal {
-        self.menu.output();
+        if let Err(_e) = self.menu.output() {
+            // Log that there is a problem
+        }
         self.init_complete = true;
         EngineSignal::Render(RenderSignal::Redraw)
     }
@@ -211,12 +228,21 @@ impl Scene for MainMenu {
 
     fn update(&mut self, inst: &mut Instance, _delta_time: f32) -> EngineSignal {
         let mut output = vec![];
-        let mut events = vec![];
-        for e in inst.event_recvier.try_iter() {
-            events.push(e);
-        }
-        for e in events {
-            let sig = self.process_event(e);
+        for e in inst.events() {
+            let sig = match self.dialog.as_mut() {
+                Some((dialog, then)) => {
+                    match dialog.handle_event(&e, &inst.render_queue, &inst.canvas) {
+                        Some(answer) => {
+                            let then = *then;
+                            let _ = dialog.remove(&inst.render_queue);
+                            self.dialog = None;
+                            then(answer)
+                        }
+                        None => _Sig::None,
+                    }
+                }
+                None => self.process_event(e),
+            };
             match self.process_signal(inst, sig) {
                 EngineSignal::None => {}
                 other => output.push(other),
@@ -241,7 +267,9 @@ impl Scene for MainMenu {
         if let Err(_e) = ins.render_queue.send(RenderSignal::Clear) {
             // Log that there is a problem
         }
-        self.menu.output();
+        if let Err(_e) = self.menu.output() {
+            // Log that there is a problem
+        }
     }
     fn suspend(&mut self, ins: &mut Instance) {
         if let Err(_e) = ins.render_queue.send(RenderSignal::Clear) {
@@ -255,7 +283,18 @@ impl Scene for MainMenu {
 ////////////////////
 
 fn action_connect() -> _Sig {
-    _Sig::Batch(vec![_Sig::Connect, _Sig::LoadGameScene])
+    _Sig::Connect
+}
+
+//////////////////////
+//  Dialog Answers  //
+//////////////////////
+
+fn answer_offline(answer: DialogResult) -> _Sig {
+    match answer {
+        DialogResult::Yes => _Sig::LoadGameScene,
+        _ => _Sig::None,
+    }
 }
 
 fn action_goto_settings() -> _Sig {
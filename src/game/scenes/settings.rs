@@ -18,35 +18,131 @@ limitations under the License.
 use crate::engine::{
     self, Instance,
     enums::{RenderSignal, SceneInitSignals, SceneSignal, Signal},
-    input::{Event, KeyEvent},
-    render::{self, Canvas, Object, RenderUnitId},
+    input::{Event, KeyEvent, OtherEvent},
+    render::{self, Canvas, Object, RenderUnitId, Text},
     traits::Scene,
     types::Position3D,
-    ui::style::{Align, Justify},
+    ui::{
+        CheckBox, Constraint, FocusManager, Layout, RadioButtons, Rect, SelectionDirection,
+        SelectorItem, Widget,
+        style::{Align, Alignment, Coloring, Justify, Measure, Size, Style},
+    },
 };
+use my_term::color::{BLACK, GREEN};
 use std::{
     marker::PhantomData,
     sync::{Arc, Weak, atomic::AtomicUsize, mpsc},
 };
 
+// rows of the layout, also the tab order
+const OVERLAY_ROW: usize = 0;
+const FPS_ROW: usize = 1;
+
+const FPS_CHOICES: [u32; 3] = [30, 60, 120];
+
 #[derive(Debug)]
 pub struct Settings {
-    text_handle: Weak<RenderUnitId>,
+    debug_overlay: CheckBox,
+    max_fps: RadioButtons,
+    /// choice selected when the screen opened, the cap is only sent once the player picks another
+    shown_fps: Option<usize>,
+    layout: Layout,
+    focus: FocusManager,
     init_complete: bool,
 }
 
 impl Settings {
     pub fn new() -> Box<dyn Scene> {
+        let style = Style::default();
+        let fg: u8 = style.fg().clone().into();
+        let bg: u8 = style.bg().clone().into();
+        let hover = Coloring::new(BLACK, GREEN);
+        let row = || {
+            Constraint::new(Size::rect(Measure::Percent(50), Measure::Cell(1)))
+                .set_min(Size::w_only(Measure::Cell(32)))
+        };
         Box::new(Self {
-            text_handle: Weak::new(),
+            debug_overlay: CheckBox::new(
+                0,
+                0,
+                style.clone(),
+                hover.clone(),
+                Text::from("Show debug overlay", fg, bg),
+            ),
+            max_fps: RadioButtons::new(
+                0,
+                0,
+                style,
+                hover,
+                SelectionDirection::Horizontal,
+                FPS_CHOICES
+                    .iter()
+                    .map(|fps| SelectorItem::new(Text::from(format!("{fps} fps"), fg, bg), *fps as usize))
+                    .collect(),
+            ),
+            shown_fps: None,
+            layout: Layout::vstack()
+                .set_spacing(1)
+                .set_alignment(Alignment::centered())
+                .push(row())
+                .push(row()),
+            focus: FocusManager::new(),
             init_complete: false,
         })
     }
+
+    /// The focus manager along with the inputs in tab order
+    fn widgets(&mut self) -> (&mut FocusManager, [&mut dyn Widget<Signal>; 2]) {
+        (&mut self.focus, [&mut self.debug_overlay, &mut self.max_fps])
+    }
+
+    /// Lays the inputs out for the canvas and draws them
+    fn redraw(&mut self, ins: &Instance) {
+        self.layout.arrange(Rect::from_canvas(&ins.canvas));
+        let pos = |row: usize| self.layout.rect(row).unwrap_or_default().pos();
+        let (overlay, fps) = (pos(OVERLAY_ROW), pos(FPS_ROW));
+        self.debug_overlay.set_pos(overlay);
+        self.max_fps.set_pos(fps);
+        let (_, widgets) = self.widgets();
+        for w in widgets {
+            if let Err(_e) = w.output(&ins.render_queue, &ins.canvas) {
+                // Log the error
+            }
+        }
+    }
+
+    /// Sends the render thread whatever changed since the last call
+    fn apply(&mut self, ins: &mut Instance) -> Vec<Signal> {
+        let mut signals = vec![];
+        if self.debug_overlay.is_checked() != ins.debug_overlay {
+            ins.debug_overlay = self.debug_overlay.is_checked();
+            signals.push(Signal::Render(RenderSignal::DebugOverlay(ins.debug_overlay)));
+        }
+        if let Some(fps) = self.max_fps.value() {
+            if Some(fps) != self.shown_fps {
+                self.shown_fps = Some(fps);
+                ins.max_fps = fps as u32;
+                signals.push(Signal::Render(RenderSignal::SetMaxFps(ins.max_fps)));
+            }
+        }
+        signals
+    }
 }
 
 impl Scene for Settings {
     fn init(&mut self, ins: &mut Instance, sig: SceneInitSignals) -> Signal {
         ins.render_queue.send(RenderSignal::Clear);
+        self.debug_overlay.set_checked(ins.debug_overlay);
+        if !self.max_fps.select_value(ins.max_fps as usize) {
+            // the cap came from the command line and is not one of the choices
+            self.max_fps.select_value(render::DEFAULT_MAX_FPS as usize);
+        }
+        self.shown_fps = self.max_fps.value();
+        self.redraw(ins);
+        let (focus, mut widgets) = self.widgets();
+        if let Err(_e) = focus.focus(OVERLAY_ROW, &mut widgets, &ins.render_queue, &ins.canvas) {
+            // Log the error
+        }
         self.init_complete = true;
         Signal::None
     }
@@ -57,24 +153,36 @@ impl Scene for Settings {
         false
     }
     fn reset(&mut self, ins: &mut Instance) {}
-    fn resume(&mut self, ins: &mut Instance) {}
-    fn suspend(&mut self, ins: &mut Instance) {}
+    fn resume(&mut self, ins: &mut Instance) {
+        let _ = ins.render_queue.send(RenderSignal::Clear);
+        self.redraw(ins);
+    }
+    fn suspend(&mut self, ins: &mut Instance) {
+        let _ = ins.render_queue.send(RenderSignal::Clear);
+    }
     fn update(&mut self, inst: &mut Instance, delta_time: f32) -> Signal {
-        let canvas = &inst.canvas;
         let mut signals: Vec<Signal> = vec![];
-        let mut events = vec![];
-        for e in inst.event_recvier.try_iter() {
-            events.push(e);
-        }
-        for each in events {
-            match each {
-                Event::Keyboard(k) => match k {
-                    KeyEvent::Char('q') => signals.push(Signal::Scenes(SceneSignal::Pop)),
-                    _ => {}
-                },
+        for each in inst.events() {
+            match &each {
+                Event::Keyboard(KeyEvent::Char('q')) => signals.push(Signal::Scenes(SceneSignal::Pop)),
+                Event::Other(OtherEvent::ScreenSizeChange { .. }) => self.redraw(inst),
+                // the instance has already flipped its state
+                Event::Keyboard(KeyEvent::F(10)) => {
+                    self.debug_overlay.set_checked(inst.debug_overlay);
+                    let _ = self.debug_overlay.output(&inst.render_queue);
+                }
                 _ => {}
             }
+            let (focus, mut widgets) = self.widgets();
+            match focus.dispatch(&each, &mut widgets, &inst.render_queue, &inst.canvas) {
+                Ok(Some(sig)) => signals.push(sig),
+                Ok(None) => {}
+                Err(_e) => {
+                    // Log the error
+                }
+            }
         }
+        signals.extend(self.apply(inst));
         if signals.len() == 0 {
             return Signal::None;
         } else if signals.len() == 1 {
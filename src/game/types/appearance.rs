@@ -0,0 +1,189 @@
+/*
+Copyright 2025 Luke Maple
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+you may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+use super::entity::{CreatureState, CreatureType, Entity, Kind, ObjectState, ObjectType};
+use crate::engine::{
+    Error, ErrorKind,
+    traits::Storeable,
+    types::Store,
+};
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct StatCondition {
+    pub stat: String,
+    /// fraction of the stat's max value, the condition holds when current is strictly below it
+    #[serde(default)]
+    pub below: Option<f32>,
+    /// fraction of the stat's max value, the condition holds when current is at or above it
+    #[serde(default)]
+    pub above: Option<f32>,
+}
+
+/// Maps an entity's kind, state and stats to a sprite template name.
+/// Every field that is set has to match, the matching rule with the highest priority wins.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct AppearanceRule {
+    pub name: String,
+    pub sprite: String,
+    #[serde(default)]
+    pub priority: i32,
+    #[serde(default)]
+    pub creature: Option<CreatureType>,
+    #[serde(default)]
+    pub creature_state: Option<CreatureState>,
+    #[serde(default)]
+    pub object: Option<ObjectType>,
+    #[serde(default)]
+    pub object_state: Option<ObjectState>,
+    #[serde(default)]
+    pub stats: Vec<StatCondition>,
+}
+
+impl StatCondition {
+    pub fn matches(&self, e: &Entity) -> bool {
+        let Some(stat) = e.common.stats.get(&self.stat) else {
+            return false;
+        };
+        let max = stat.max_value();
+        let ratio = if max > 0 {
+            stat.current as f32 / max as f32
+        } else {
+            0.0
+        };
+        self.below.is_none_or(|b| ratio < b) && self.above.is_none_or(|a| ratio >= a)
+    }
+}
+
+impl AppearanceRule {
+    pub fn matches(&self, e: &Entity) -> bool {
+        let kind = match &e.kind {
+            Kind::Creature { kind, state } => {
+                self.object.is_none()
+                    && self.object_state.is_none()
+                    && self.creature.as_ref().is_none_or(|k| k == kind)
+                    && self.creature_state.as_ref().is_none_or(|s| s == state)
+            }
+            Kind::Object { kind, state } => {
+                self.creature.is_none()
+                    && self.creature_state.is_none()
+                    && self.object.as_ref().is_none_or(|k| k == kind)
+                    && self.object_state.as_ref().is_none_or(|s| s == state)
+            }
+        };
+        kind && self.stats.iter().all(|c| c.matches(e))
+    }
+}
+
+impl Storeable for AppearanceRule {
+    type Key = String;
+    fn key(&self) -> Self::Key {
+        self.name.clone()
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        if self.sprite.is_empty() {
+            return Err(Error::new(
+                format!("{}: appearance rule has no sprite", self.name),
+                ErrorKind::InvalidData,
+            ));
+        }
+        if (self.creature.is_some() || self.creature_state.is_some())
+            && (self.object.is_some() || self.object_state.is_some())
+        {
+            return Err(Error::new(
+                format!("{}: rule can not match a creature and an object", self.name),
+                ErrorKind::InvalidData,
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Name of the sprite template the entity should be drawn with
+pub fn resolve<'a>(rules: &'a Store<AppearanceRule>, e: &Entity) -> Option<&'a str> {
+    let mut best: Option<&AppearanceRule> = None;
+    for rule in rules.iter() {
+        if rule.matches(e) && best.is_none_or(|b| rule.priority > b.priority) {
+            best = Some(rule);
+        }
+    }
+    best.map(|r| r.sprite.as_str())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::engine::{enums::RenderSignal, render::SpriteTemplate};
+    use crate::game::types::{entity::Template, stat::StatTemplate};
+    use std::{collections::HashMap, sync::Arc};
+
+    fn dwarf() -> Entity {
+        let mut base_stats = HashMap::new();
+        base_stats.insert(
+            "Health".to_string(),
+            StatTemplate {
+                level: 1,
+                level_scaling: 10,
+            },
+        );
+        Entity::from_template(Template {
+            passable: true,
+            storable: false,
+            name: "dwarf".to_string(),
+            max_inventory_weight: None,
+            base_stats,
+            kind: Kind::Creature {
+                kind: CreatureType::Dwarf,
+                state: CreatureState::Idle,
+            },
+        })
+    }
+
+    #[test]
+    fn appearance_resolve_test() {
+        let rules: Vec<AppearanceRule> = ron::de::from_str(
+            r#"[
+                (name: "idle", sprite: "Dwarf_Idle", creature: Some(Dwarf)),
+                (name: "hurt", sprite: "Dwarf_Wounded", priority: 1, creature: Some(Dwarf), stats: [(stat: "Health", below: Some(0.3))]),
+                (name: "door", sprite: "Door_Broken", object: Some(Door), object_state: Some(Broken)),
+            ]"#,
+        )
+        .unwrap();
+        let rules = Store::from_vec(rules);
+        let mut d = dwarf();
+        assert_eq!(resolve(&rules, &d), Some("Dwarf_Idle"));
+        d.common.stats.get_mut("Health").unwrap().decrease_current(8);
+        assert_eq!(resolve(&rules, &d), Some("Dwarf_Wounded"));
+    }
+
+    #[test]
+    fn appearance_bind_test() {
+        let rules = Store::<AppearanceRule>::from_dir("./data/appearance/").unwrap();
+        let sprites = Store::<SpriteTemplate>::from_dir("./data/sprites/").unwrap();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut d = dwarf();
+        // the first refresh inserts a unit and binds the entity to it
+        assert!(d.refresh_appearance(&rules, &sprites, &tx));
+        let Ok(RenderSignal::Insert(id, _)) = rx.try_recv() else {
+            panic!("expected an insert");
+        };
+        assert!(Arc::ptr_eq(&d.common.render_id().unwrap(), &id));
+        assert!(!d.refresh_appearance(&rules, &sprites, &tx));
+        d.common.stats.get_mut("Health").unwrap().decrease_current(8);
+        assert!(d.refresh_appearance(&rules, &sprites, &tx));
+        assert!(matches!(rx.try_recv(), Ok(RenderSignal::Update(..))));
+    }
+}
@@ -16,13 +16,19 @@ limitations under the License.
 
 use crate::{
     engine::{
-        render,
+        enums::RenderSignal,
+        render::{self, Layer, RenderQueue, RenderUnitId, SpriteTemplate},
         traits::Storeable,
         types::{Position, Position3D, Store},
     },
-    game::types::{inventory::Inventory, stat::Stat, stat::StatTemplate},
+    game::types::{
+        appearance::{self, AppearanceRule},
+        inventory::Inventory,
+        stat::Stat,
+        stat::StatTemplate,
+    },
 };
-use std::sync::{Arc, Weak, atomic::AtomicUsize};
+use std::sync::{Arc, Weak};
 use std::{cmp::Ordering, collections::HashMap, hash::Hash};
 
 ////////////
@@ -38,7 +44,10 @@ const ENTITY_STORABLE_FLAG: u8 = 0b00000010;
 pub struct Common {
     flags: u8,
     #[serde(skip, default = "Common::default_render_id")]
-    render_id: Weak<AtomicUsize>,
+    render_id: Weak<RenderUnitId>,
+    /// sprite template the bound render unit was last updated with
+    #[serde(skip)]
+    sprite: Option<String>,
     pub position: Position3D<usize>,
     pub inventory: Option<Inventory>,
     pub stats: HashMap<String, Stat>,
@@ -76,9 +85,19 @@ pub struct Template {
 // Implementation //
 ////////////////////
 impl Common {
-    fn default_render_id() -> Weak<AtomicUsize> {
+    fn default_render_id() -> Weak<RenderUnitId> {
         Weak::new()
     }
+
+    pub fn render_id(&self) -> Option<Arc<RenderUnitId>> {
+        self.render_id.upgrade()
+    }
+
+    /// Ties the entity to a render unit, the next appearance refresh redraws it
+    pub fn bind(&mut self, id: &Arc<RenderUnitId>) {
+        self.render_id = Arc::downgrade(id);
+        self.sprite = None;
+    }
 }
 
 impl Entity {
@@ -100,6 +119,7 @@ impl Entity {
             common: Common {
                 flags: flags,
                 render_id: Weak::new(),
+                sprite: None,
                 position: Position3D { x: 0, y: 0, z: 0 },
                 inventory: if let Some(weight) = temp.max_inventory_weight {
                     Inventory::new(weight)
@@ -134,6 +154,42 @@ impl Entity {
             self.common.flags &= !ENTITY_STORABLE_FLAG;
         }
     }
+
+    /// Resolves the entity's appearance and updates the bound render unit when the sprite changed.
+    /// An entity without a render unit gets a new one on the middleground and is bound to it.
+    /// Returns true when a signal was sent.
+    pub fn refresh_appearance(
+        &mut self,
+        rules: &Store<AppearanceRule>,
+        sprites: &Store<SpriteTemplate>,
+        tx: &RenderQueue,
+    ) -> bool {
+        let Some(name) = appearance::resolve(rules, self) else {
+            return false;
+        };
+        let id = self.common.render_id.upgrade();
+        if id.is_some() && self.common.sprite.as_deref() == Some(name) {
+            return false;
+        }
+        let Ok(Some(template)) = sprites.get(name.to_string()) else {
+            return false;
+        };
+        let pos: Position3D<i32> = self.common.position.into();
+        let data = template.to_object_data(pos);
+        let sig = match id {
+            Some(id) => RenderSignal::Update(id, data),
+            None => {
+                let id = RenderUnitId::new(Layer::Middleground);
+                self.common.bind(&id);
+                RenderSignal::Insert(id, data)
+            }
+        };
+        if tx.send(sig).is_err() {
+            return false;
+        }
+        self.common.sprite = Some(name.to_string());
+        true
+    }
 }
 
 impl Storeable for Template {
@@ -147,13 +203,13 @@ impl Storeable for Template {
 // Enums //
 ///////////
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum CreatureType {
     Dwarf,
     Human,
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum CreatureState {
     Idle,
     Combat,
@@ -161,7 +217,7 @@ pub enum CreatureState {
     Dead,
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum ObjectType {
     Door,
     Bin,
@@ -169,7 +225,7 @@ pub enum ObjectType {
     Table,
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum ObjectState {
     Normal,
     Damaged,
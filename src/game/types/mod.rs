@@ -14,11 +14,13 @@ See the License for the specific language governing permissions and
 limitations under the License.
 */
 
+pub mod appearance;
 pub mod entity;
 pub mod inventory;
 pub mod material;
 pub mod stat;
 pub mod tile;
+pub mod tileset;
 mod world;
 
 pub use world::*;
@@ -14,6 +14,7 @@ See the License for the specific language governing permissions and
 limitations under the License.
 */
 
+use crate::engine::ui::GaugeSource;
 use std::{cmp::Ordering, hash::Hash};
 
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
@@ -86,3 +87,10 @@ impl Stat {
         self.current -= val;
     }
 }
+
+/// Lets a Gauge show the stat out of its max
+impl GaugeSource for Stat {
+    fn gauge_values(&self) -> (f64, f64) {
+        (self.current as f64, self.max_value() as f64)
+    }
+}
@@ -0,0 +1,165 @@
+/*
+Copyright 2025 Luke Maple
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+you may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+use super::tile::{Shape, StairDirection};
+use crate::engine::{
+    Error, ErrorKind,
+    render::Char,
+    traits::Storeable,
+    types::{Position3D, Store},
+};
+use my_term::color::{BLACK, WHITE};
+
+fn default_weight() -> u32 {
+    1
+}
+
+fn default_bg() -> u8 {
+    BLACK
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct TileVariant {
+    pub glyph: char,
+    pub fg: u8,
+    #[serde(default = "default_bg")]
+    pub bg: u8,
+    /// how often this variant is picked compared to the others in the entry
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+}
+
+/// How a shape looks for one material. Entries without a material are the fallback for that shape.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct TileAppearance {
+    #[serde(default)]
+    pub material: Option<String>,
+    pub shape: Shape,
+    pub variants: Vec<TileVariant>,
+}
+
+impl Storeable for TileAppearance {
+    type Key = (Option<String>, Shape);
+    fn key(&self) -> Self::Key {
+        (self.material.clone(), self.shape.clone())
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        if self.variants.is_empty() || self.variants.iter().all(|v| v.weight == 0) {
+            return Err(Error::new(
+                format!("{:?} {:?}: tile appearance has no variants", self.material, self.shape),
+                ErrorKind::InvalidData,
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl TileAppearance {
+    /// Picks a variant from the position so the same tile always looks the same
+    pub fn variant(&self, pos: Position3D<usize>) -> &TileVariant {
+        let total: u32 = self.variants.iter().map(|v| v.weight).sum();
+        let mut roll = (position_hash(pos) % total.max(1) as u64) as u32;
+        for v in self.variants.iter() {
+            if roll < v.weight {
+                return v;
+            }
+            roll -= v.weight;
+        }
+        &self.variants[0]
+    }
+}
+
+/// Looks up the material entry, then the shape fallback, then the built in glyph for the shape
+pub fn tile_char(
+    table: &Store<TileAppearance>,
+    material: Option<&str>,
+    shape: &Shape,
+    pos: Position3D<usize>,
+) -> Char {
+    let mut entry = None;
+    if let Some(m) = material {
+        entry = table
+            .get((Some(m.to_string()), shape.clone()))
+            .ok()
+            .flatten();
+    }
+    if entry.is_none() {
+        entry = table.get((None, shape.clone())).ok().flatten();
+    }
+    match entry {
+        Some(e) => {
+            let v = e.variant(pos);
+            Char::new(v.glyph, v.fg, v.bg)
+        }
+        None => Char::new(fallback_glyph(shape), WHITE, BLACK),
+    }
+}
+
+pub fn fallback_glyph(shape: &Shape) -> char {
+    match shape {
+        Shape::Floor => '.',
+        Shape::Wall => '#',
+        Shape::Block => '%',
+        Shape::Ramp => '^',
+        Shape::Window => '"',
+        Shape::Fortification => '$',
+        Shape::OpenSpace => ' ',
+        Shape::Stairs(StairDirection::Up) => '<',
+        Shape::Stairs(StairDirection::Down) => '>',
+        Shape::Stairs(StairDirection::Both) => 'X',
+    }
+}
+
+// splitmix64 over the packed position, stable across runs and platforms
+fn position_hash(pos: Position3D<usize>) -> u64 {
+    let mut h = (pos.x as u64)
+        .wrapping_mul(0x9E3779B97F4A7C15)
+        .wrapping_add((pos.y as u64).wrapping_mul(0xC2B2AE3D27D4EB4F))
+        .wrapping_add((pos.z as u64).wrapping_mul(0x165667B19E3779F9));
+    h ^= h >> 30;
+    h = h.wrapping_mul(0xBF58476D1CE4E5B9);
+    h ^= h >> 27;
+    h = h.wrapping_mul(0x94D049BB133111EB);
+    h ^ (h >> 31)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn tile_char_test() {
+        let table: Vec<TileAppearance> = ron::de::from_str(
+            r#"[
+                (material: Some("limestone"), shape: Block, variants: [(glyph: '%', fg: 7), (glyph: ',', fg: 8, weight: 3)]),
+                (shape: Block, variants: [(glyph: 'B', fg: 7)]),
+            ]"#,
+        )
+        .unwrap();
+        let table = Store::from_vec(table);
+        let pos = Position3D::new(3, 4, 0);
+        let a = tile_char(&table, Some("limestone"), &Shape::Block, pos);
+        let b = tile_char(&table, Some("limestone"), &Shape::Block, pos);
+        assert_eq!(a.as_char(), b.as_char());
+        assert!(a.as_char() == '%' || a.as_char() == ',');
+        assert_eq!(tile_char(&table, Some("granite"), &Shape::Block, pos).as_char(), 'B');
+        assert_eq!(
+            tile_char(&table, None, &Shape::Stairs(StairDirection::Down), pos).as_char(),
+            '>'
+        );
+    }
+}
@@ -15,12 +15,14 @@ limitations under the License.
 */
 
 #![allow(dead_code, unused)]
+use super::appearance::AppearanceRule;
 use super::entity::{self, Entity};
 use super::material::Material;
 use super::tile::{self, Tile};
+use super::tileset::{self, TileAppearance};
 use crate::engine::{
     self,
-    render::{self, ObjectTemplate},
+    render::{self, Cells, RenderQueue, SpriteTemplate},
     types::{File, Position3D, SparseSet, Store},
 };
 use crate::engine::{Error, ErrorKind};
@@ -37,6 +39,8 @@ pub struct WorldSave {
     pub avg_temp: f32,
     pub avg_height: f32,
     pub sea_level: f32,
+    #[serde(default)]
+    pub hard_core: bool,
     pub world_size: Position3D<usize>,
     pub materials: SparseSet<Material>,
     pub entities: SparseSet<Entity>,
@@ -50,6 +54,7 @@ impl WorldSave {
             avg_temp: w.avg_temp,
             avg_height: w.avg_height,
             sea_level: w.sea_level,
+            hard_core: w.hard_core,
             world_size: w.world_size,
             materials: w.materials.clone(),
             entities: w.entities.clone(),
@@ -64,25 +69,49 @@ pub struct World {
     pub avg_temp: f32,
     pub avg_height: f32,
     pub sea_level: f32,
+    /// picked on the New World form, saves from before it existed load as false
+    #[serde(default)]
+    pub hard_core: bool,
     pub world_size: Position3D<usize>,
     #[serde(skip, default = "world_init_materials")]
     pub material_templates: Store<Material>,
     #[serde(skip, default = "world_init_entitytemplate")]
     pub entity_templates: Store<entity::Template>,
     #[serde(skip, default = "world_init_sprite_template")]
-    pub sprite_templates: Store<ObjectTemplate>,
+    pub sprite_templates: Store<SpriteTemplate>,
+    #[serde(skip, default = "world_init_appearance_rules")]
+    pub appearance_rules: Store<AppearanceRule>,
+    #[serde(skip, default = "world_init_tile_appearance")]
+    pub tile_appearance: Store<TileAppearance>,
     pub materials: SparseSet<Material>,
     pub entities: SparseSet<Entity>,
     pub tiles: SparseSet<Tile>,
+    /// entities whose appearance has to be checked on the next sync
+    #[serde(skip)]
+    dirty: Vec<usize>,
 }
 
-fn world_init_sprite_template() -> Store<ObjectTemplate> {
+fn world_init_sprite_template() -> Store<SpriteTemplate> {
     match Store::from_dir("./data/sprites/") {
         Err(e) => panic!("world_init_sprite_template: {e}"),
         Ok(s) => s,
     }
 }
 
+fn world_init_appearance_rules() -> Store<AppearanceRule> {
+    match Store::from_dir("./data/appearance/") {
+        Err(e) => panic!("world_init_appearance_rules: {e}"),
+        Ok(s) => s,
+    }
+}
+
+fn world_init_tile_appearance() -> Store<TileAppearance> {
+    match Store::from_dir("./data/tiles/") {
+        Err(e) => panic!("world_init_tile_appearance: {e}"),
+        Ok(s) => s,
+    }
+}
+
 fn world_init_entitytemplate() -> Store<entity::Template> {
     match Store::from_dir("./data/entities/") {
         Err(e) => panic!("world_init_entitytemplate: {e}"),
@@ -106,9 +135,12 @@ impl World {
         temp: f32,
         height: f32,
         sea: f32,
+        hard_core: bool,
         mat_dir: &str,
         ent_dir: &str,
         spr_dir: &str,
+        app_dir: &str,
+        tile_dir: &str,
     ) -> Result<Self, Error> {
         let mut material_templates: Store<Material> = match Store::<Material>::from_dir(mat_dir) {
             Err(e) => {
@@ -133,8 +165,8 @@ impl World {
                 }
                 Ok(e) => e,
             };
-        let mut sprite_templates: Store<ObjectTemplate> =
-            match Store::<ObjectTemplate>::from_dir(spr_dir) {
+        let mut sprite_templates: Store<SpriteTemplate> =
+            match Store::<SpriteTemplate>::from_dir(spr_dir) {
                 Err(e) => {
                     let k = e.kind();
                     return Err(Error::from(
@@ -145,18 +177,46 @@ impl World {
                 }
                 Ok(o) => o,
             };
+        let appearance_rules: Store<AppearanceRule> =
+            match Store::<AppearanceRule>::from_dir(app_dir) {
+                Err(e) => {
+                    let k = e.kind();
+                    return Err(Error::from(
+                        e,
+                        "failed to get appearance rules from dir",
+                        ErrorKind::Io(k),
+                    ));
+                }
+                Ok(a) => a,
+            };
+        let tile_appearance: Store<TileAppearance> =
+            match Store::<TileAppearance>::from_dir(tile_dir) {
+                Err(e) => {
+                    let k = e.kind();
+                    return Err(Error::from(
+                        e,
+                        "failed to get tile appearance from dir",
+                        ErrorKind::Io(k),
+                    ));
+                }
+                Ok(t) => t,
+            };
         let mut world = Self {
             name: name,
             world_size: Position3D::new(x, y, z),
             avg_temp: temp,
             avg_height: height,
             sea_level: sea,
+            hard_core: hard_core,
             material_templates: material_templates,
             entity_templates: entity_templates,
             sprite_templates: sprite_templates,
+            appearance_rules: appearance_rules,
+            tile_appearance: tile_appearance,
             materials: SparseSet::new(20000),
             entities: SparseSet::new(20000),
             tiles: SparseSet::new((x * y * z) + 5),
+            dirty: Vec::new(),
         };
         return Ok(world);
     }
@@ -196,6 +256,63 @@ impl World {
         }
     }
 
+    pub fn tile_char(&self, index: usize) -> Option<render::Char> {
+        let tile = self.tiles.get(index)?;
+        let material = self
+            .materials
+            .get(tile.material)
+            .map(|m| m.common.name.as_str());
+        Some(tileset::tile_char(
+            &self.tile_appearance,
+            material,
+            &tile.shape,
+            self.index_to_pos(index),
+        ))
+    }
+
+    /// Every tile on a z level as a single SetCells update for a TileMap
+    pub fn level_cells(&self, z: usize) -> Cells {
+        let mut cells = Vec::with_capacity(self.world_size.x * self.world_size.y);
+        let start = z * self.world_size.x * self.world_size.y;
+        for index in start..start + self.world_size.x * self.world_size.y {
+            cells.push(self.tile_char(index).unwrap_or_default());
+        }
+        Cells::new(Position3D::new(0, 0, z), self.world_size.x, cells)
+    }
+
+    /// Adds the entity, it is drawn on the next sync
+    pub fn add_entity(&mut self, key: usize, entity: Entity) {
+        self.entities.insert(key, entity);
+        self.dirty.push(key);
+    }
+
+    /// Changes to the entity's state or stats made through this show on the next sync.
+    /// Changes made through entities directly are not picked up
+    pub fn entity_mut(&mut self, key: usize) -> Option<&mut Entity> {
+        if !self.entities.is_filled(key) {
+            return None;
+        }
+        self.dirty.push(key);
+        self.entities.get_mut(key)
+    }
+
+    /// Every entity is checked on the next sync, for a world that was just loaded
+    pub fn mark_all_dirty(&mut self) {
+        self.dirty = self.entities.all_keys().map(|(k, _)| *k).collect();
+    }
+
+    /// Pushes the appearance of entities that changed since the last sync
+    pub fn sync_appearances(&mut self, tx: &RenderQueue) {
+        let mut dirty = std::mem::take(&mut self.dirty);
+        dirty.sort_unstable();
+        dirty.dedup();
+        for k in dirty {
+            if let Some(e) = self.entities.get_mut(k) {
+                e.refresh_appearance(&self.appearance_rules, &self.sprite_templates, tx);
+            }
+        }
+    }
+
     pub fn index_to_pos(&self, mut index: usize) -> Position3D<usize> {
         let z = index / (self.world_size.y * self.world_size.x);
         index = index % (self.world_size.y * self.world_size.x);
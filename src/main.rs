@@ -35,8 +35,25 @@ use ron;
 fn main() -> ExitCode {
     let logging_path = "./logs/";
     let logging_level = logging::LogLevel::Debug;
+    let mut config = InstanceConfig::new(logging_path, logging_level);
 
-    match engine::start(InstanceConfig::new(logging_path, logging_level)) {
+    // --record records the whole session, --record=<file> picks where the cast is saved
+    for arg in std::env::args().skip(1) {
+        if arg == "--record" {
+            config = config.set_record(None);
+        } else if let Some(path) = arg.strip_prefix("--record=") {
+            config = config.set_record(Some(path.to_string()));
+        } else if arg == "--debug-overlay" {
+            config = config.set_debug_overlay(true);
+        } else if let Some(fps) = arg.strip_prefix("--max-fps=") {
+            match fps.parse::<u32>() {
+                Ok(fps) if fps > 0 => config = config.set_max_fps(fps),
+                _ => println!("ignoring invalid --max-fps value: {fps}"),
+            }
+        }
+    }
+
+    match engine::start(config) {
         Ok(_) => return ExitCode::SUCCESS,
         Err(e) => {
             println!("Error While Running Game, {}", e);